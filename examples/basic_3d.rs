@@ -0,0 +1,67 @@
+//! A low resolution 3D scene, rendered by a 3D pixel camera.
+
+use bevy::prelude::*;
+use bevy_smooth_pixel_camera::prelude::*;
+
+/// Marker component for the cube so we can rotate it in `update`
+#[derive(Component)]
+struct Cube;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins.set(ImagePlugin::default_nearest()),
+            PixelCameraPlugin,
+        ))
+        .add_systems(Startup, setup)
+        .add_systems(Update, update)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // Spawn a 3d camera with the PixelCamera and PixelCamera3d components in order to
+    // render it at a low resolution.
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 2.5, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        PixelCamera {
+            smoothing: false,
+            ..PixelCamera::from_size(ViewportSize::PixelFixed(6))
+        },
+        PixelCamera3d::Free,
+    ));
+
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Plane3d::default().mesh().size(6.0, 6.0)),
+        material: materials.add(Color::rgb(0.3, 0.5, 0.3)),
+        ..default()
+    });
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Cuboid::new(1.0, 1.0, 1.0)),
+            material: materials.add(Color::rgb(0.8, 0.7, 0.6)),
+            transform: Transform::from_xyz(0.0, 0.5, 0.0),
+            ..default()
+        },
+        Cube,
+    ));
+    commands.spawn(PointLightBundle {
+        point_light: PointLight {
+            shadows_enabled: true,
+            ..default()
+        },
+        transform: Transform::from_xyz(4.0, 8.0, 4.0),
+        ..default()
+    });
+}
+
+fn update(mut cube: Query<&mut Transform, With<Cube>>, time: Res<Time>) {
+    // 3D pixel cameras are moved with their `Transform`, so only the cube needs updating here.
+    cube.single_mut().rotate_y(time.delta_seconds());
+}
@@ -0,0 +1,65 @@
+//! A pixelated view of the world in a second window, while the primary window stays native resolution.
+
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+use bevy::window::WindowRef;
+use bevy_smooth_pixel_camera::prelude::*;
+
+/// Marker component for the bevy icon so we can move it in `update`
+#[derive(Component)]
+struct BevyIcon;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins.set(ImagePlugin::default_nearest()),
+            PixelCameraPlugin,
+        ))
+        .add_systems(Startup, setup)
+        .add_systems(Update, update)
+        .run();
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    // A regular camera for the primary window.
+    commands.spawn(Camera2dBundle::default());
+
+    let window = commands
+        .spawn(Window {
+            title: "Pixelated".to_string(),
+            ..default()
+        })
+        .id();
+
+    // The viewport of a pixel camera is shown on the window that the camera renders to,
+    // and sized for that window.
+    commands.spawn((
+        Camera2dBundle {
+            camera: Camera {
+                target: RenderTarget::Window(WindowRef::Entity(window)),
+                ..default()
+            },
+            ..default()
+        },
+        PixelCamera::from_size(ViewportSize::PixelFixed(4)),
+    ));
+
+    commands.spawn(SpriteBundle {
+        texture: asset_server.load("checkerboard.png"),
+        ..default()
+    });
+    commands.spawn((
+        SpriteBundle {
+            texture: asset_server.load("bevy_pixel_dark.png"),
+            transform: Transform::from_xyz(0.0, 0.0, 1.0),
+            ..default()
+        },
+        BevyIcon,
+    ));
+}
+
+fn update(mut bevy: Query<&mut Transform, With<BevyIcon>>, time: Res<Time>) {
+    // Both windows show the same world.
+    let mut bevy_transform = bevy.single_mut();
+    bevy_transform.translation.y = (time.elapsed_seconds() / 2.0).sin() * 10.0;
+}
@@ -0,0 +1,100 @@
+//! Two player split-screen, with a pixel camera for each half of the window.
+
+use bevy::prelude::*;
+use bevy::render::camera::Viewport;
+use bevy::window::{PrimaryWindow, WindowResized};
+use bevy_smooth_pixel_camera::prelude::*;
+
+/// Marker component for the cameras, with the index of the half of the window they're shown on.
+#[derive(Component)]
+struct PlayerCamera(u32);
+
+/// Marker component for the players, with the keys that move them.
+#[derive(Component)]
+struct Player([KeyCode; 4]);
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins.set(ImagePlugin::default_nearest()),
+            PixelCameraPlugin,
+        ))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (move_players, set_viewports))
+        .run();
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn(SpriteBundle {
+        texture: asset_server.load("checkerboard.png"),
+        ..default()
+    });
+
+    let keys = [
+        [KeyCode::KeyW, KeyCode::KeyA, KeyCode::KeyS, KeyCode::KeyD],
+        [
+            KeyCode::ArrowUp,
+            KeyCode::ArrowLeft,
+            KeyCode::ArrowDown,
+            KeyCode::ArrowRight,
+        ],
+    ];
+    for (index, keys) in keys.into_iter().enumerate() {
+        let player = commands
+            .spawn((
+                SpriteBundle {
+                    texture: asset_server.load("bevy_pixel_dark.png"),
+                    transform: Transform::from_xyz(index as f32 * 32.0 - 16.0, 0.0, 1.0),
+                    ..default()
+                },
+                Player(keys),
+            ))
+            .id();
+
+        // The viewport layers and orders of the cameras are picked automatically, and each
+        // viewport is sized for its half of the window by `set_viewports`.
+        commands.spawn((
+            Camera2dBundle::default(),
+            PixelCamera::from_size(ViewportSize::PixelFixed(4)),
+            PixelCameraFollow::new(player),
+            PlayerCamera(index as u32),
+        ));
+    }
+}
+
+fn move_players(
+    mut players: Query<(&mut Transform, &Player)>,
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+) {
+    for (mut transform, Player([up, left, down, right])) in &mut players {
+        let axis = |negative, positive| {
+            keys.pressed(positive) as i32 as f32 - keys.pressed(negative) as i32 as f32
+        };
+        let direction = Vec2::new(axis(*left, *right), axis(*down, *up));
+        transform.translation += (direction * 40.0 * time.delta_seconds()).extend(0.0);
+    }
+}
+
+/// Gives each camera half of the window whenever the window is resized.
+fn set_viewports(
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut resized: EventReader<WindowResized>,
+    mut cameras: Query<(&mut Camera, &PlayerCamera)>,
+) {
+    if resized.read().last().is_none() {
+        return;
+    }
+    let Ok(window) = window.get_single() else {
+        return;
+    };
+    let size = UVec2::new(window.physical_width() / 2, window.physical_height());
+
+    for (mut camera, PlayerCamera(index)) in &mut cameras {
+        camera.viewport = Some(Viewport {
+            physical_position: UVec2::new(size.x * index, 0),
+            physical_size: size.max(UVec2::ONE),
+            ..default()
+        });
+    }
+}
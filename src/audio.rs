@@ -0,0 +1,68 @@
+//! Spatial audio that follows the smooth position of a [`PixelCamera`].
+//!
+//! A [`SpatialListener`] on the camera itself hears from the camera's [`Transform`], which is
+//! snapped to whole pixels. Slow camera movement then pans the audio in audible steps.
+
+use bevy::audio::SpatialListener;
+use bevy::prelude::*;
+
+use crate::components::PixelCamera;
+
+/// Hears spatial audio from [`PixelCamera::subpixel_pos`] instead of the snapped [`Transform`].
+///
+/// Add this to a [`PixelCamera`] instead of a [`SpatialListener`]. The listener is put on a
+/// child entity that is offset by the part of the position that the snapping cut off.
+#[derive(Component, Clone, Debug, Default)]
+pub struct SmoothSpatialListener(pub SpatialListener);
+
+/// The child entity that holds the [`SpatialListener`] of a [`SmoothSpatialListener`].
+#[derive(Component)]
+pub(crate) struct SmoothListenerEntity(Entity);
+
+/// Spawns the listener entities of newly added [`SmoothSpatialListener`]s.
+#[allow(clippy::type_complexity)]
+pub(crate) fn spawn_smooth_listeners(
+    cameras: Query<
+        (Entity, &SmoothSpatialListener, Has<SpatialListener>),
+        (With<PixelCamera>, Without<SmoothListenerEntity>),
+    >,
+    mut commands: Commands,
+) {
+    for (entity, SmoothSpatialListener(listener), has_listener) in &cameras {
+        if has_listener {
+            warn!("PixelCamera {entity:?} has both a SpatialListener and a SmoothSpatialListener, only one listener should exist.");
+        }
+
+        let child = commands
+            .spawn((TransformBundle::default(), listener.clone()))
+            .set_parent(entity)
+            .id();
+        commands.entity(entity).insert(SmoothListenerEntity(child));
+    }
+}
+
+/// Offsets the listener entities by the subpixel remainder of their camera's position.
+pub(crate) fn sync_smooth_listeners(
+    cameras: Query<(
+        &PixelCamera,
+        &Transform,
+        Ref<SmoothSpatialListener>,
+        &SmoothListenerEntity,
+    )>,
+    mut listeners: Query<(&mut Transform, &mut SpatialListener), Without<PixelCamera>>,
+) {
+    for (camera, camera_transform, smooth_listener, SmoothListenerEntity(child)) in &cameras {
+        let Ok((mut transform, mut listener)) = listeners.get_mut(*child) else {
+            continue;
+        };
+
+        let remainder = camera.subpixel_pos - camera_transform.translation.truncate();
+        if transform.translation.truncate() != remainder {
+            transform.translation.x = remainder.x;
+            transform.translation.y = remainder.y;
+        }
+        if smooth_listener.is_changed() {
+            *listener = smooth_listener.0.clone();
+        }
+    }
+}
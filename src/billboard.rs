@@ -0,0 +1,83 @@
+//! Snapping billboards to the texel grid of a 3D [`PixelCamera`].
+//!
+//! A [`PixelCamera3d::GridSnapped`] camera only moves in whole texels, so the static scene
+//! stays put on the screen. Billboards (e.g. character sprites drawn on quads) that move freely
+//! would swim against it, [`PixelBillboard`] snaps them to the same grid.
+
+use bevy::prelude::*;
+
+use crate::components::{PixelCamera, PixelCamera3d, PixelViewportReferences};
+
+/// Snaps an entity to the projected texel grid of a [`PixelCamera3d::GridSnapped`] camera.
+///
+/// Only the [`GlobalTransform`] is snapped, so the entity can still be moved smoothly through
+/// its [`Transform`]. Only orthographic cameras are supported.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PixelBillboard {
+    /// The entity of the [`PixelCamera`] whose grid the billboard is snapped to.
+    ///
+    /// If [`None`], the only 3D [`PixelCamera`] in the world is used.
+    pub camera: Option<Entity>,
+    /// Whether the billboard is also rotated to face the camera.
+    pub face_camera: bool,
+}
+
+/// Snaps the [`GlobalTransform`] of [`PixelBillboard`]s after transform propagation.
+#[allow(clippy::type_complexity)]
+pub(crate) fn snap_billboards(
+    mut billboards: Query<(Entity, &PixelBillboard, &mut GlobalTransform), Without<PixelCamera>>,
+    cameras: Query<
+        (
+            &PixelCamera3d,
+            &Projection,
+            &PixelViewportReferences,
+            &GlobalTransform,
+        ),
+        With<PixelCamera>,
+    >,
+) {
+    for (entity, billboard, mut transform) in &mut billboards {
+        let camera = match billboard.camera {
+            Some(camera) => cameras.get(camera).ok(),
+            None => cameras.get_single().ok(),
+        };
+        let Some((camera_3d, projection, viewport, camera_transform)) = camera else {
+            warn_once!("PixelBillboard on {entity:?} doesn't point to a single 3D PixelCamera");
+            continue;
+        };
+        let (PixelCamera3d::GridSnapped { .. }, Projection::Orthographic(projection)) =
+            (camera_3d, projection)
+        else {
+            continue;
+        };
+        if viewport.size.width == 0 || viewport.size.height == 0 {
+            continue;
+        }
+
+        // The size of a texel on the view plane, in world units.
+        let texel_size = Vec2::new(
+            projection.area.width() / viewport.size.width as f32,
+            projection.area.height() / viewport.size.height as f32,
+        );
+        if texel_size.x <= 0.0 || texel_size.y <= 0.0 {
+            continue;
+        }
+
+        // The camera itself is on the grid, so snapping relative to it snaps to the grid.
+        let (_, camera_rotation, camera_translation) =
+            camera_transform.to_scale_rotation_translation();
+        let (scale, rotation, translation) = transform.to_scale_rotation_translation();
+        let local = camera_rotation.inverse() * (translation - camera_translation);
+        let snapped = ((local.truncate() / texel_size).round() * texel_size).extend(local.z);
+
+        *transform = GlobalTransform::from(Transform {
+            translation: camera_translation + camera_rotation * snapped,
+            rotation: if billboard.face_camera {
+                camera_rotation
+            } else {
+                rotation
+            },
+            scale,
+        });
+    }
+}
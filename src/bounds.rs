@@ -0,0 +1,45 @@
+//! Keeping the camera inside an area of the world.
+
+use bevy::prelude::*;
+
+use crate::components::{PixelCamera, PixelCamera3d};
+
+/// Keeps the view of a [`PixelCamera`] inside a rectangle of the world.
+///
+/// Add this component next to a [`PixelCamera`]. Its [`PixelCamera::subpixel_pos`] is clamped
+/// so that the visible area ([`PixelCamera::visible_rect`]) never leaves the bounds.
+/// Along axes where the bounds are smaller than the visible area, the camera is centered on them.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct CameraBounds(pub Rect);
+
+/// Clamps the subpixel position of cameras with [`CameraBounds`].
+pub(crate) fn clamp_to_bounds(
+    mut cameras: Query<
+        (&mut PixelCamera, &OrthographicProjection, &CameraBounds),
+        Without<PixelCamera3d>,
+    >,
+) {
+    for (mut camera, projection, CameraBounds(bounds)) in &mut cameras {
+        let visible = camera.visible_rect(projection);
+        // The range of positions that keep the visible area inside the bounds.
+        let min = bounds.min - (visible.min - camera.subpixel_pos);
+        let max = bounds.max - (visible.max - camera.subpixel_pos);
+
+        let clamp_axis = |pos: f32, min: f32, max: f32| {
+            if min > max {
+                (min + max) / 2.0
+            } else {
+                pos.clamp(min, max)
+            }
+        };
+        let clamped = Vec2::new(
+            clamp_axis(camera.subpixel_pos.x, min.x, max.x),
+            clamp_axis(camera.subpixel_pos.y, min.y, max.y),
+        );
+
+        if clamped != camera.subpixel_pos {
+            camera.subpixel_pos = clamped;
+        }
+    }
+}
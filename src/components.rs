@@ -1,22 +1,36 @@
 //! The components of [`bevy_smooth_pixel_camera`](crate).
 
+use bevy::ecs::entity::{EntityMapper, MapEntities};
+use bevy::ecs::reflect::ReflectMapEntities;
 use bevy::prelude::*;
+use bevy::render::camera::{RenderTarget, Viewport};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureFormat};
 use bevy::render::view::RenderLayers;
 
+use crate::systems::ViewportSizing;
 use crate::viewport::ViewportSize;
 
 /// The pixelated camera component.
 ///
 /// Add this component to a [`Camera2dBundle`] in order to turn it into a
-/// pixelated camera.
+/// pixelated camera. For 3D cameras, see [`PixelCamera3d`].
 ///
 /// **Warning:** In order to move the camera please use the `subpixel_pos`
 /// attribute instead of the [`Transform`] component (the transform is a truncated version of subpixel_pos (for pixel perfect snapping))
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
 pub struct PixelCamera {
     /// The size of the viewport.
     ///
-    /// See [`ViewportSize`] for details.
+    /// See [`ViewportSize`] for details. It can be changed at any time (e.g. from a settings
+    /// menu), the render target and the viewport are resized in the same frame.
+    ///
+    /// The viewport is sized for, and shown on, the window that the camera renders to when it's
+    /// spawned, or the part of it covered by the [`Camera::viewport`] (e.g. for split-screen).
+    /// The [`Camera::viewport`] is then moved over to the viewport camera, setting a new one on
+    /// the camera moves the viewport again. Use [`ViewportTarget`] or [`ViewportRect`] to show it
+    /// somewhere else.
     pub viewport_size: ViewportSize,
     /// The subpixel position of the [`PixelCamera`], use this instead of the camera's [`Transform`].
     pub subpixel_pos: Vec2,
@@ -25,11 +39,56 @@ pub struct PixelCamera {
     ///
     /// Because we want the world camera to render before the viewport camera,
     /// set this value to a number higher the than the world camera's order.
+    /// Changes are applied to the viewport camera after checking the order again, invalid
+    /// changes are reported and undone. Cameras shown on the same window are given distinct
+    /// orders when they're initialized.
+    ///
+    /// The two orders after it are used by the overlay cameras of
+    /// [`HighResOverlay`](crate::high_res::HighResOverlay) and native gizmos.
     pub viewport_order: isize,
     /// The rendering layer the viewport is on.
+    ///
+    /// Changing it moves the viewport sprite and camera to the new layer, which is validated
+    /// against the camera's render layers again. Cameras that share a layer (e.g. for
+    /// split-screen) are given distinct layers when they're initialized.
     pub viewport_layer: RenderLayers,
     /// Whether camera position smoothing is enabled for this camera.
+    ///
+    /// Smoothing renders a margin of one texel around the viewport, which is added to or
+    /// removed from the render target when this is toggled at runtime.
     pub smoothing: bool,
+    /// The texture format of the low resolution render target.
+    ///
+    /// Defaults to [`TextureFormat::Bgra8UnormSrgb`]. Formats with fewer bits per
+    /// pixel can be used to save memory, as long as the device can render to them.
+    ///
+    /// Formats that the device can't render to are replaced with an RGBA format (with a warning),
+    /// e.g. BGRA formats on WebGL2.
+    ///
+    /// Not saved in `DynamicScene`s, loaded cameras use the default.
+    #[reflect(ignore)]
+    pub target_format: TextureFormat,
+    /// Where the render target's pixel data is kept.
+    ///
+    /// By default a copy of the pixel data is retained on the CPU. Set this to
+    /// [`RenderAssetUsages::RENDER_WORLD`] to drop the CPU copy once it has been
+    /// uploaded, halving the memory used by the target. The target image is then
+    /// recreated (instead of resized) whenever the viewport size changes.
+    ///
+    /// The camera renders to an image that the plugin creates, unless it's already pointed at an
+    /// existing image when it's spawned (or later on). That image is resized and used as the
+    /// render target, e.g. to show it on an in game monitor, but only if it keeps a CPU copy
+    /// ([`RenderAssetUsages::MAIN_WORLD`]). Otherwise it's replaced with a new one.
+    ///
+    /// Not saved in `DynamicScene`s, loaded cameras use the default.
+    #[reflect(ignore)]
+    pub target_usage: RenderAssetUsages,
+    /// Extra space around the camera's view, in texels, in which entities are never culled.
+    ///
+    /// The smoothing margin and the overscan of [`FitMode`](crate::viewport::FitMode)s can
+    /// show slightly more of the world than the camera's projection covers.
+    /// This keeps entities at the edges of the screen from popping in and out.
+    pub culling_margin: f32,
 }
 
 impl Default for PixelCamera {
@@ -40,6 +99,9 @@ impl Default for PixelCamera {
             viewport_layer: RenderLayers::layer(1),
             subpixel_pos: Vec2::ZERO,
             smoothing: true,
+            target_format: TextureFormat::Bgra8UnormSrgb,
+            target_usage: RenderAssetUsages::default(),
+            culling_margin: 2.0,
         }
     }
 }
@@ -52,6 +114,61 @@ impl PixelCamera {
             ..default()
         }
     }
+    /// Creates a new pixel camera with the `size` of choice that doesn't
+    /// keep a CPU copy of its render target.
+    ///
+    /// See [`PixelCamera::target_usage`] for details.
+    pub fn low_memory(viewport_size: ViewportSize) -> Self {
+        Self {
+            viewport_size,
+            target_usage: RenderAssetUsages::RENDER_WORLD,
+            ..default()
+        }
+    }
+    /// Returns the area of the world that is visible on the screen, in world units.
+    ///
+    /// Centered on [`PixelCamera::subpixel_pos`], and without the smoothing margin.
+    /// `projection` is the [`OrthographicProjection`] of the camera.
+    pub fn visible_rect(&self, projection: &OrthographicProjection) -> Rect {
+        let margin = if self.smoothing {
+            projection.scale
+        } else {
+            0.0
+        };
+
+        Rect {
+            min: projection.area.min + margin + self.subpixel_pos,
+            max: projection.area.max - margin + self.subpixel_pos,
+        }
+    }
+    /// Returns the zoom of the camera, which is the scale of a [`ViewportSize::PixelFixed`]
+    /// viewport. Other viewport sizes don't have a zoom.
+    pub fn zoom(&self) -> Option<u32> {
+        match self.viewport_size {
+            ViewportSize::PixelFixed(scale) => Some(scale),
+            _ => None,
+        }
+    }
+    /// Zooms the camera to a whole number scale (at least 1), by switching the viewport to
+    /// [`ViewportSize::PixelFixed`]. The render target is resized for the new scale in the
+    /// same frame, so every texel stays pixel perfect.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_smooth_pixel_camera::prelude::*;
+    /// fn zoom_in(mut cameras: Query<&mut PixelCamera>) {
+    ///     for mut camera in &mut cameras {
+    ///         let zoom = camera.zoom().unwrap_or(1);
+    ///         camera.set_zoom(zoom + 1);
+    ///     }
+    /// }
+    /// ```
+    pub fn set_zoom(&mut self, zoom: u32) {
+        let zoom = zoom.max(1);
+        if self.zoom() != Some(zoom) {
+            self.viewport_size = ViewportSize::PixelFixed(zoom);
+        }
+    }
     /// Creates a new pixel camera with the `scaling` of choice and default configuration.'
     #[deprecated(since = "0.2.0", note = "`from_size` should be used instead")]
     pub fn from_scaling(scaling: u8) -> Self {
@@ -62,13 +179,301 @@ impl PixelCamera {
     }
 }
 
-// TODO: Replace these components when we get entity relationships or something like that
+/// Turns a [`PixelCamera`] on a [`Camera3dBundle`] into a low resolution 3D camera.
+///
+/// The 3D camera renders into the low resolution target and is upscaled to the window like a
+/// 2D one. [`CameraBounds`](crate::bounds::CameraBounds) don't apply to 3D cameras.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn setup(mut commands: Commands) {
+///     commands.spawn((
+///         Camera3dBundle::default(),
+///         PixelCamera {
+///             smoothing: false,
+///             ..PixelCamera::from_size(ViewportSize::PixelFixed(4))
+///         },
+///         PixelCamera3d::Free,
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone, Copy, Default, PartialEq)]
+#[reflect(Component, Default)]
+pub enum PixelCamera3d {
+    /// The camera is moved and rotated through its [`Transform`], and
+    /// [`PixelCamera::subpixel_pos`] is unused.
+    ///
+    /// Smoothing isn't supported and is turned off.
+    #[default]
+    Free,
+    /// For orthographic cameras, e.g. for sprite stacking or voxel scenes rendered as pixel art,
+    /// and for slow pans of perspective cameras.
+    ///
+    /// The camera's translation is snapped to the texel grid of its view plane, so that static
+    /// geometry doesn't shimmer as the camera moves. Smoothing works like it does for 2D cameras.
+    ///
+    /// The camera is rotated through its [`Transform`], but moved with `position`.
+    /// [`PixelCamera::subpixel_pos`] is set to the position on the view plane, in texels.
+    GridSnapped {
+        /// The unsnapped translation of the camera.
+        position: Vec3,
+        /// For perspective cameras, the distance from the camera at which the grid is measured.
+        ///
+        /// Texels get bigger with distance, so only geometry at this distance moves exactly
+        /// with the smoothing. Closer and farther geometry moves slightly faster or slower.
+        /// Unused for orthographic cameras.
+        focus_distance: f32,
+    },
+}
+
+/// Temporarily renders a [`PixelCamera`] straight to its window, bypassing the low resolution
+/// target.
+///
+/// While this component is on the camera, the camera renders to the window its viewport would
+/// be shown on, and the viewport camera and sprite are deactivated. The camera isn't snapped
+/// to whole pixels. Remove the component to go back to the pixelated output. Useful for native
+/// resolution menus, map screens or accessibility options.
+///
+/// The [`Projection`] of the camera isn't changed, so one world unit covers one logical pixel
+/// of the window instead of one texel (times the projection's scale).
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn toggle_map(
+///     keys: Res<ButtonInput<KeyCode>>,
+///     cameras: Query<(Entity, Has<PixelBypass>), With<PixelCamera>>,
+///     mut commands: Commands,
+/// ) {
+///     if !keys.just_pressed(KeyCode::KeyM) {
+///         return;
+///     }
+///     for (camera, bypassed) in &cameras {
+///         if bypassed {
+///             commands.entity(camera).remove::<PixelBypass>();
+///         } else {
+///             commands.entity(camera).insert(PixelBypass);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub struct PixelBypass;
+
+/// Freezes a [`PixelCamera`] in place.
+///
+/// While this component is on the camera, its position isn't snapped or smoothed and its
+/// viewport isn't resized, so it keeps showing the world from where it was. The camera still
+/// renders every frame. Useful for background cameras while the game is paused, or for
+/// picture in picture replays. Changes made while frozen are applied once the component is
+/// removed.
+#[derive(Component, Reflect, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub struct PixelFreeze;
+
+/// Where the upscaled output of a [`PixelCamera`] is rendered to.
+///
+/// By default the output goes to the window the camera was pointed at when it was initialized.
+/// Add or change this component to redirect the output at runtime, for example to an image
+/// that is recorded or to a second window. The viewport is resized to fit the new target,
+/// with images sized like a window with a scale factor of 1. Removing the component keeps
+/// the current output.
+///
+/// Cameras that are spawned with an image target don't need a window at all, which together
+/// with [`PixelReadback`](crate::readback::PixelReadback) runs the pipeline headless.
+///
+/// [`RenderTarget::TextureView`] is not supported.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy::render::camera::RenderTarget;
+/// # use bevy::window::WindowRef;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn move_to_window(
+///     window: Query<Entity, Added<Window>>,
+///     cameras: Query<Entity, With<PixelCamera>>,
+///     mut commands: Commands,
+/// ) {
+///     let Ok(window) = window.get_single() else {
+///         return;
+///     };
+///     for camera in &cameras {
+///         let target = RenderTarget::Window(WindowRef::Entity(window));
+///         commands.entity(camera).insert(ViewportTarget(target));
+///     }
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub struct ViewportTarget(pub RenderTarget);
+
+/// Shows the viewport of a [`PixelCamera`] on part of its window, in physical pixels, e.g. in a
+/// panel of an editor's UI.
+///
+/// The viewport is sized and fit for the rect instead of the whole window, and the rect is
+/// clamped to the window. Remove the component to show the viewport on the whole window again.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn setup(mut commands: Commands) {
+///     commands.spawn((
+///         Camera2dBundle::default(),
+///         PixelCamera::default(),
+///         // Leave room for a side panel on the left.
+///         ViewportRect(URect::new(320, 0, 1280, 720)),
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct ViewportRect(pub URect);
+
+/// The viewport entities of an initialized [`PixelCamera`].
+///
+/// The viewport sprite and camera are children of the camera entity (unless it has a
+/// [`CustomViewport`]), this only keeps them apart without walking the [`Children`].
 #[derive(Component)]
 pub(crate) struct PixelViewportReferences {
     pub camera: Entity,
     pub sprite: Entity,
+    /// The image that the camera renders to and the viewport sprite displays.
+    pub image: Handle<Image>,
+    /// Whether the image was created by the plugin, rather than supplied by the user.
+    pub owned_image: bool,
+    /// The target that the viewport camera shows the viewport on.
+    ///
+    /// Kept so that a despawned viewport camera can be respawned on the same target.
+    pub output: RenderTarget,
+    /// The part of the target that the viewport camera shows the viewport on, e.g. for
+    /// split-screen. Kept for the same reason as `output`.
+    pub sub_viewport: Option<Viewport>,
+    /// The current size of the render target, including the smoothing margin.
+    ///
+    /// Tracked here because the target image may not exist in the main world.
+    pub size: Extent3d,
+    /// What the viewport was last sized for, `None` until it's sized.
+    pub sized_for: Option<ViewportSizing>,
+}
+/// Makes a [`PixelCamera`] use viewport entities spawned by the user instead of spawning its own.
+///
+/// Mark a sprite with [`PixelViewport`] and a camera with [`ViewportCamera`], both holding the
+/// [`PixelCamera`] entity. Once both exist, the camera is initialized and renders to a new image
+/// (or the image it's pointed at), which is set as the sprite's image. The plugin keeps
+/// sizing the image to the target of the viewport camera, updating the smoothing rect of the
+/// sprite and mirroring [`Camera::is_active`] to both, but leaves everything else to you: the layers, order and projection of the viewport
+/// camera aren't changed, and the entities aren't despawned with the [`PixelCamera`].
+///
+/// If one of the entities is despawned, the camera is initialized again once both exist.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy::render::view::RenderLayers;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn setup(mut commands: Commands) {
+///     let camera = commands
+///         .spawn((Camera2dBundle::default(), PixelCamera::default(), CustomViewport))
+///         .id();
+///     commands.spawn((
+///         SpriteBundle::default(),
+///         RenderLayers::layer(1),
+///         PixelViewport(camera),
+///     ));
+///     commands.spawn((
+///         Camera2dBundle {
+///             camera: Camera {
+///                 order: 1,
+///                 ..default()
+///             },
+///             ..default()
+///         },
+///         RenderLayers::layer(1),
+///         ViewportCamera(camera),
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub struct CustomViewport;
+
+/// Shows the render target of the [`PixelCamera`] entity it holds on another sprite.
+///
+/// The sprite's image and [`Sprite::rect`] are kept in sync with the viewport sprite, so it's
+/// smoothed in the same way. Use it to show the same view more than once, e.g. a zoomed in
+/// inset or an in game screen. The size, position and render layers of the sprite are up to you.
+///
+/// Other entities, like meshes, can use the image that [`Camera::target`] of the
+/// [`PixelCamera`] points at, without the smoothing.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn setup(mut commands: Commands) {
+///     let camera = commands
+///         .spawn((Camera2dBundle::default(), PixelCamera::default()))
+///         .id();
+///     commands.spawn((
+///         SpriteBundle {
+///             transform: Transform::from_xyz(100.0, 50.0, 1.0).with_scale(Vec3::splat(0.25)),
+///             ..default()
+///         },
+///         ViewportMirror(camera),
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component, MapEntities)]
+pub struct ViewportMirror(pub Entity);
+
+impl MapEntities for ViewportMirror {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.0 = entity_mapper.map_entity(self.0);
+    }
+}
+
+/// Marks the viewport sprite of the [`PixelCamera`] entity it holds.
+///
+/// The plugin spawns the viewport sprite as a child of the camera, unless the camera has a
+/// [`CustomViewport`].
+///
+/// Removing the [`PixelCamera`] (or despawning the camera) despawns the viewport entities and
+/// frees the render target image, unless it's held elsewhere or kept for reuse by
+/// [`PixelCameraConfig::target_image_pool`](crate::PixelCameraConfig::target_image_pool).
+/// A camera that lost its component renders to the viewport's window again.
+/// The viewport entities of a camera loaded from a `DynamicScene` are replaced with new ones.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component, MapEntities)]
+pub struct PixelViewport(pub Entity);
+/// Marks the viewport camera of the [`PixelCamera`] entity it holds.
+///
+/// The plugin spawns the viewport camera as a child of the camera, unless the camera has a
+/// [`CustomViewport`]. Setting the camera's [`Camera::is_active`] to `false` also deactivates
+/// the viewport camera and hides the viewport sprite.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component, MapEntities)]
+pub struct ViewportCamera(pub Entity);
+
+impl MapEntities for PixelViewport {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.0 = entity_mapper.map_entity(self.0);
+    }
 }
+
+impl MapEntities for ViewportCamera {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.0 = entity_mapper.map_entity(self.0);
+    }
+}
+/// Marks a [`PixelCamera`] that released its viewport and isn't initialized until this is removed.
 #[derive(Component)]
-pub(crate) struct PixelViewport;
+pub(crate) struct Suspended;
+/// Marks a [`PixelCamera`] whose window was closed, which is deactivated until the window
+/// exists again.
 #[derive(Component)]
-pub(crate) struct ViewportCamera;
+pub(crate) struct TargetClosed {
+    /// Whether the camera is activated again once the window exists, i.e. whether it was
+    /// deactivated because of the closed window rather than by the user or its state.
+    pub reactivate: bool,
+}
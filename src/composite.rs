@@ -0,0 +1,542 @@
+//! Compositing the render target straight onto the window.
+//!
+//! By default a [`PixelCamera`] is shown with a sprite that a second camera renders to the window.
+//! A camera with [`DirectComposite`] skips both: a render graph node upscales its render target
+//! onto the window surface after all cameras have rendered.
+
+use bevy::core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state;
+use bevy::prelude::*;
+use bevy::render::camera::{NormalizedRenderTarget, RenderTarget};
+use bevy::render::render_asset::RenderAssets;
+use bevy::render::render_graph::{
+    Node, NodeRunError, RenderGraph, RenderGraphContext, RenderLabel,
+};
+use bevy::render::render_resource::binding_types::{sampler, texture_2d, uniform_buffer};
+use bevy::render::render_resource::*;
+use bevy::render::renderer::{RenderContext, RenderDevice, RenderQueue};
+use bevy::render::view::ExtractedWindows;
+use bevy::render::{Extract, ExtractSchedule, Render, RenderApp, RenderSet};
+use bevy::window::{PrimaryWindow, WindowRef};
+
+use crate::components::{PixelCamera, PixelFreeze};
+use crate::error::{report, PixelCameraError, PixelCameraErrorKind};
+use crate::systems::{
+    create_target_image, fit_projection_size, refresh_projection, sizing_resolution, target_size,
+};
+use crate::viewport::{FitMode, ViewportSize};
+use crate::{CameraSystems, PixelCameraConfig};
+
+const COMPOSITE_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(0x3b0c_7d52_91e4_4c1a_a6f8_52d9_0e17_c4b3);
+
+/// Shows a [`PixelCamera`] by compositing its render target straight onto the window.
+///
+/// Add this component next to a [`PixelCamera`] before it is initialized. No viewport sprite or
+/// viewport camera is spawned, so [`PixelCamera::viewport_layer`] and
+/// [`PixelCamera::viewport_order`] are unused.
+///
+/// The render target is drawn after every camera has rendered, on top of anything else on
+/// the window (including other cameras, like a UI camera). Use the default viewport
+/// if something has to be drawn over the pixel art.
+#[derive(Component, Reflect, Debug, Clone, Copy, Default)]
+#[reflect(Component, Default)]
+pub struct DirectComposite;
+
+/// The render target of a [`DirectComposite`] camera.
+#[derive(Component)]
+pub(crate) struct DirectCompositeTarget {
+    pub window: Entity,
+    /// The current size of the render target, including the smoothing margin.
+    pub size: Extent3d,
+}
+
+/// Compositing of [`DirectComposite`] cameras, added by [`PixelCameraPlugin`](crate::PixelCameraPlugin).
+pub(crate) struct DirectCompositePlugin;
+impl Plugin for DirectCompositePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<DirectComposite>().add_systems(
+            PostUpdate,
+            (
+                (init_direct_composites, cleanup_direct_composites)
+                    .in_set(CameraSystems::Initialization),
+                resize_direct_composites.in_set(CameraSystems::ViewportSize),
+            ),
+        );
+
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .init_resource::<ExtractedComposites>()
+            .init_resource::<SpecializedRenderPipelines<CompositePipeline>>()
+            .add_systems(ExtractSchedule, extract_direct_composites)
+            .add_systems(
+                Render,
+                prepare_direct_composites.in_set(RenderSet::PrepareBindGroups),
+            );
+
+        let mut graph = render_app.world.resource_mut::<RenderGraph>();
+        graph.add_node(DirectCompositeLabel, DirectCompositeNode);
+        graph.add_node_edge(bevy::render::graph::CameraDriverLabel, DirectCompositeLabel);
+
+        bevy::asset::load_internal_asset!(
+            app,
+            COMPOSITE_SHADER_HANDLE,
+            "composite.wgsl",
+            Shader::from_wgsl
+        );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app.init_resource::<CompositePipeline>();
+    }
+}
+
+/// Creates the render targets of newly added [`DirectComposite`] cameras.
+///
+/// Cameras without a window are retried whenever their [`PixelCamera`] or [`Camera`] change.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub(crate) fn init_direct_composites(
+    mut cameras: Query<
+        (Entity, &PixelCamera, &mut Camera),
+        (
+            With<DirectComposite>,
+            Without<DirectCompositeTarget>,
+            Or<(
+                Changed<PixelCamera>,
+                Changed<Camera>,
+                Added<DirectComposite>,
+            )>,
+        ),
+    >,
+    windows: Query<&Window>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    render_device: Option<Res<RenderDevice>>,
+    config: Res<PixelCameraConfig>,
+    mut images: ResMut<Assets<Image>>,
+    mut commands: Commands,
+    mut errors: EventWriter<PixelCameraError>,
+) {
+    for (entity, pixel_camera, mut camera) in &mut cameras {
+        let window_entity = match camera.target {
+            RenderTarget::Window(WindowRef::Entity(window_entity)) => Some(window_entity),
+            _ => primary_window.get_single().ok(),
+        };
+        let Some((window_entity, window)) = window_entity
+            .and_then(|window_entity| Some((window_entity, windows.get(window_entity).ok()?)))
+        else {
+            report(
+                &mut errors,
+                entity,
+                PixelCameraErrorKind::MissingWindow(window_entity),
+            );
+            continue;
+        };
+
+        let size = target_size(
+            pixel_camera,
+            &sizing_resolution(window, &config),
+            render_device.as_deref(),
+            &mut errors,
+            entity,
+        );
+        let image = images.add(create_target_image(
+            size,
+            pixel_camera.target_format,
+            pixel_camera.target_usage,
+        ));
+
+        camera.target = RenderTarget::Image(image);
+        commands.entity(entity).insert(DirectCompositeTarget {
+            window: window_entity,
+            size,
+        });
+    }
+}
+
+/// Makes cameras that lost their [`PixelCamera`] or [`DirectComposite`] component render to
+/// their window again.
+#[allow(clippy::type_complexity)]
+fn cleanup_direct_composites(
+    mut cameras: Query<
+        (Entity, &mut Camera, &DirectCompositeTarget),
+        Or<(Without<PixelCamera>, Without<DirectComposite>)>,
+    >,
+    mut commands: Commands,
+) {
+    for (entity, mut camera, target) in &mut cameras {
+        camera.target = RenderTarget::Window(WindowRef::Entity(target.window));
+        commands.entity(entity).remove::<DirectCompositeTarget>();
+    }
+}
+
+/// Recreates the render targets of [`DirectComposite`] cameras whose size has changed.
+#[allow(clippy::type_complexity)]
+fn resize_direct_composites(
+    mut cameras: Query<
+        (
+            Entity,
+            Ref<PixelCamera>,
+            &mut Camera,
+            &mut DirectCompositeTarget,
+            Option<&mut OrthographicProjection>,
+            Option<&mut Projection>,
+        ),
+        Without<PixelFreeze>,
+    >,
+    windows: Query<Ref<Window>>,
+    render_device: Option<Res<RenderDevice>>,
+    config: Res<PixelCameraConfig>,
+    mut images: ResMut<Assets<Image>>,
+    mut errors: EventWriter<PixelCameraError>,
+) {
+    for (entity, pixel_camera, mut camera, mut target, orthographic_projection, projection) in
+        &mut cameras
+    {
+        let Ok(window) = windows.get(target.window) else {
+            continue;
+        };
+        if !window.is_changed() && !pixel_camera.is_changed() && !config.is_changed() {
+            continue;
+        }
+        if window.physical_width() == 0 || window.physical_height() == 0 {
+            // Minimized windows have no size, keep the old target until they're restored.
+            continue;
+        }
+
+        let size = target_size(
+            &pixel_camera,
+            &sizing_resolution(&window, &config),
+            render_device.as_deref(),
+            &mut errors,
+            entity,
+        );
+        if size == target.size {
+            continue;
+        }
+
+        // The old image is dropped with its last handle.
+        camera.target = RenderTarget::Image(images.add(create_target_image(
+            size,
+            pixel_camera.target_format,
+            pixel_camera.target_usage,
+        )));
+        target.size = size;
+        refresh_projection(orthographic_projection, projection);
+    }
+}
+
+use uniform::CompositeUniform;
+
+// `ShaderType` generates layout checks that are never called, and the fields are only read
+// by the shader.
+#[allow(dead_code)]
+mod uniform {
+    use bevy::math::{Vec2, Vec4};
+    use bevy::render::render_resource::ShaderType;
+
+    /// The uniform of the composite shader, in window and render target UV coordinates.
+    #[derive(ShaderType, Debug, Clone, Copy)]
+    pub(crate) struct CompositeUniform {
+        /// The top left corner of the area of the window that shows the render target.
+        pub dest_min: Vec2,
+        pub dest_size: Vec2,
+        /// The top left corner of the part of the render target that is shown.
+        pub src_min: Vec2,
+        pub src_size: Vec2,
+        /// The color of the bars around the shown area, in linear RGBA.
+        pub clear_color: Vec4,
+        /// Whether the bars are cleared with `clear_color` or left alone.
+        pub clear: u32,
+    }
+}
+
+struct ExtractedComposite {
+    window: Entity,
+    image: AssetId<Image>,
+    order: isize,
+    uniform: CompositeUniform,
+    /// The color that the window is cleared with before the composite is drawn, if nothing
+    /// else renders to the window before it.
+    clear: Option<Color>,
+}
+
+#[derive(Resource, Default)]
+struct ExtractedComposites(Vec<ExtractedComposite>);
+
+fn extract_direct_composites(
+    mut extracted: ResMut<ExtractedComposites>,
+    cameras: Extract<Query<(&PixelCamera, &Camera, &DirectCompositeTarget)>>,
+    window_cameras: Extract<Query<&Camera, Without<DirectCompositeTarget>>>,
+    windows: Extract<Query<&Window>>,
+    primary_window: Extract<Query<Entity, With<PrimaryWindow>>>,
+    clear_color: Extract<Res<ClearColor>>,
+) {
+    extracted.0.clear();
+    let primary_window = primary_window.get_single().ok();
+    for (pixel_camera, camera, target) in &cameras {
+        let RenderTarget::Image(image) = &camera.target else {
+            continue;
+        };
+        let Ok(window) = windows.get(target.window) else {
+            continue;
+        };
+        if !camera.is_active || window.height() <= 0.0 {
+            continue;
+        }
+
+        let margin = if pixel_camera.smoothing { 1.0 } else { 0.0 };
+        let size = Vec2::new(target.size.width as f32, target.size.height as f32);
+        let visible = size - 2.0 * margin;
+
+        let projection = fit_projection_size(
+            &pixel_camera.viewport_size,
+            Extent3d {
+                width: visible.x as u32,
+                height: visible.y as u32,
+                ..default()
+            },
+            Vec2::new(window.width(), window.height()),
+        );
+        let dest_size = visible / projection;
+
+        // Same as the sprite rect in `smooth_camera`.
+        let remainder = if pixel_camera.smoothing {
+            Vec2::new(
+                pixel_camera.subpixel_pos.x % 1.0,
+                -pixel_camera.subpixel_pos.y % 1.0,
+            )
+        } else {
+            Vec2::ZERO
+        };
+
+        let bars = match &pixel_camera.viewport_size {
+            ViewportSize::Fixed {
+                fit: FitMode::Fit(clear) | FitMode::IntegerFit(clear),
+                ..
+            }
+            | ViewportSize::Custom {
+                fit: FitMode::Fit(clear) | FitMode::IntegerFit(clear),
+                ..
+            } => match clear {
+                ClearColorConfig::Default => Some(clear_color.0),
+                ClearColorConfig::Custom(color) => Some(*color),
+                ClearColorConfig::None => None,
+            },
+            _ => None,
+        };
+
+        extracted.0.push(ExtractedComposite {
+            window: target.window,
+            image: image.id(),
+            order: camera.order,
+            uniform: CompositeUniform {
+                dest_min: (Vec2::ONE - dest_size) / 2.0,
+                dest_size,
+                src_min: (Vec2::splat(margin) + remainder) / size,
+                src_size: visible / size,
+                clear_color: bars.unwrap_or_default().as_linear_rgba_f32().into(),
+                clear: bars.is_some().into(),
+            },
+            clear: Some(bars.unwrap_or(clear_color.0)),
+        });
+    }
+    extracted.0.sort_by_key(|composite| composite.order);
+
+    // The swap chain texture isn't cleared if no camera renders to the window, so the first
+    // composite clears it. Otherwise the bars would show whatever was left in the texture.
+    let mut cleared_windows: Vec<Entity> = window_cameras
+        .iter()
+        .filter(|camera| camera.is_active)
+        .filter_map(|camera| match camera.target.normalize(primary_window) {
+            Some(NormalizedRenderTarget::Window(window)) => Some(window.entity()),
+            _ => None,
+        })
+        .collect();
+    for composite in &mut extracted.0 {
+        if cleared_windows.contains(&composite.window) {
+            composite.clear = None;
+        } else {
+            cleared_windows.push(composite.window);
+        }
+    }
+}
+
+#[derive(Resource)]
+struct CompositePipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+}
+
+impl FromWorld for CompositePipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let layout = render_device.create_bind_group_layout(
+            "pixel_composite_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    uniform_buffer::<CompositeUniform>(false),
+                ),
+            ),
+        );
+        let sampler = render_device.create_sampler(&SamplerDescriptor {
+            label: Some("pixel_composite_sampler"),
+            mag_filter: FilterMode::Nearest,
+            min_filter: FilterMode::Nearest,
+            ..default()
+        });
+
+        Self { layout, sampler }
+    }
+}
+
+impl SpecializedRenderPipeline for CompositePipeline {
+    /// The format of the window surface.
+    type Key = TextureFormat;
+
+    fn specialize(&self, format: Self::Key) -> RenderPipelineDescriptor {
+        RenderPipelineDescriptor {
+            label: Some("pixel_composite_pipeline".into()),
+            layout: vec![self.layout.clone()],
+            push_constant_ranges: Vec::new(),
+            vertex: fullscreen_shader_vertex_state(),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            fragment: Some(FragmentState {
+                shader: COMPOSITE_SHADER_HANDLE,
+                shader_defs: Vec::new(),
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+        }
+    }
+}
+
+struct PreparedComposite {
+    window: Entity,
+    clear: Option<Color>,
+    pipeline: CachedRenderPipelineId,
+    bind_group: BindGroup,
+}
+
+#[derive(Resource, Default)]
+struct PreparedComposites(Vec<PreparedComposite>);
+
+#[allow(clippy::too_many_arguments)]
+fn prepare_direct_composites(
+    extracted: Res<ExtractedComposites>,
+    composite_pipeline: Option<Res<CompositePipeline>>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<CompositePipeline>>,
+    pipeline_cache: Res<PipelineCache>,
+    windows: Res<ExtractedWindows>,
+    images: Res<RenderAssets<Image>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    mut commands: Commands,
+) {
+    let Some(composite_pipeline) = composite_pipeline else {
+        return;
+    };
+
+    let mut prepared = Vec::with_capacity(extracted.0.len());
+    for composite in &extracted.0 {
+        let Some(format) = windows
+            .get(&composite.window)
+            .and_then(|window| window.swap_chain_texture_format)
+        else {
+            continue;
+        };
+        let Some(image) = images.get(composite.image) else {
+            continue;
+        };
+
+        let mut uniform = UniformBuffer::from(composite.uniform);
+        uniform.write_buffer(&render_device, &render_queue);
+        let Some(uniform) = uniform.binding() else {
+            continue;
+        };
+
+        prepared.push(PreparedComposite {
+            window: composite.window,
+            clear: composite.clear,
+            pipeline: pipelines.specialize(&pipeline_cache, &composite_pipeline, format),
+            bind_group: render_device.create_bind_group(
+                "pixel_composite_bind_group",
+                &composite_pipeline.layout,
+                &BindGroupEntries::sequential((
+                    &image.texture_view,
+                    &composite_pipeline.sampler,
+                    uniform,
+                )),
+            ),
+        });
+    }
+    commands.insert_resource(PreparedComposites(prepared));
+}
+
+#[derive(RenderLabel, Debug, Hash, PartialEq, Eq, Clone)]
+struct DirectCompositeLabel;
+
+/// Draws the prepared composites onto their windows.
+struct DirectCompositeNode;
+impl Node for DirectCompositeNode {
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let Some(prepared) = world.get_resource::<PreparedComposites>() else {
+            return Ok(());
+        };
+        let windows = world.resource::<ExtractedWindows>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+
+        for composite in &prepared.0 {
+            let Some(view) = windows
+                .get(&composite.window)
+                .and_then(|window| window.swap_chain_texture_view.as_ref())
+            else {
+                continue;
+            };
+            let Some(pipeline) = pipeline_cache.get_render_pipeline(composite.pipeline) else {
+                continue;
+            };
+
+            let mut pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+                label: Some("pixel_composite_pass"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: match composite.clear {
+                            Some(color) => LoadOp::Clear(color.into()),
+                            None => LoadOp::Load,
+                        },
+                        store: StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_render_pipeline(pipeline);
+            pass.set_bind_group(0, &composite.bind_group, &[]);
+            pass.draw(0..3, 0..1);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+struct Composite {
+    dest_min: vec2<f32>,
+    dest_size: vec2<f32>,
+    src_min: vec2<f32>,
+    src_size: vec2<f32>,
+    clear_color: vec4<f32>,
+    clear: u32,
+}
+
+@group(0) @binding(0) var source: texture_2d<f32>;
+@group(0) @binding(1) var source_sampler: sampler;
+@group(0) @binding(2) var<uniform> composite: Composite;
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let local = (in.uv - composite.dest_min) / composite.dest_size;
+    if any(local < vec2(0.0)) || any(local >= vec2(1.0)) {
+        // Outside of the shown area, these are the bars of `FitMode::Fit`.
+        if composite.clear == 0u {
+            discard;
+        }
+        return composite.clear_color;
+    }
+    // Sampled with an explicit level, derivatives aren't available after the branch above.
+    return textureSampleLevel(source, source_sampler, composite.src_min + local * composite.src_size, 0.0);
+}
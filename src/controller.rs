@@ -0,0 +1,198 @@
+//! A pan, zoom, peek and reset camera controller for [`PixelCamera`]s.
+//!
+//! The controller is driven by [`PixelCameraActions`], so it works with any input crate:
+//! use [`PixelCameraKeyBindings`] for the keyboard, or fill in the actions from your own
+//! input handling (e.g. a `leafwing-input-manager` `ActionState`) in [`PreUpdate`].
+
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+
+use crate::components::PixelCamera;
+use crate::viewport::ViewportSize;
+
+/// Adds the systems that drive cameras with a [`PixelCameraController`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::controller::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn setup(mut commands: Commands) {
+///     commands.spawn((
+///         Camera2dBundle::default(),
+///         PixelCamera::from_size(ViewportSize::PixelFixed(4)),
+///         PixelCameraController::default(),
+///         PixelCameraKeyBindings::default(),
+///     ));
+/// }
+///
+/// App::new()
+///     .add_plugins(PixelCameraControllerPlugin)
+///     .add_systems(Startup, setup);
+/// ```
+pub struct PixelCameraControllerPlugin;
+impl Plugin for PixelCameraControllerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreUpdate, read_key_bindings.after(InputSystem))
+            .add_systems(Update, control_pixel_cameras);
+    }
+}
+
+/// The input of a [`PixelCameraController`] for the current frame.
+///
+/// Set every frame by [`PixelCameraKeyBindings`], or by your own systems in [`PreUpdate`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PixelCameraActions {
+    /// Moves the camera, each axis from -1 to 1.
+    pub pan: Vec2,
+    /// Looks ahead in a direction while held, each axis from -1 to 1.
+    pub peek: Vec2,
+    /// Makes the pixels bigger, for one frame per step.
+    pub zoom_in: bool,
+    /// Makes the pixels smaller, for one frame per step.
+    pub zoom_out: bool,
+    /// Moves the camera back to [`PixelCameraController::home`].
+    pub reset: bool,
+}
+
+/// Drives a [`PixelCamera`] with its [`PixelCameraActions`].
+///
+/// Zooming steps the scale of [`ViewportSize::PixelFixed`], other viewport sizes can't be zoomed.
+#[derive(Component, Debug, Clone)]
+pub struct PixelCameraController {
+    /// The input of the current frame.
+    pub actions: PixelCameraActions,
+    /// How fast the camera pans, in world units per second.
+    pub pan_speed: f32,
+    /// How far the camera looks ahead while peeking, in world units.
+    pub peek_distance: f32,
+    /// How fast the camera moves towards (and back from) the peek, as a fraction per second.
+    pub peek_speed: f32,
+    /// The smallest pixel scale that zooming out goes to.
+    pub min_scale: u32,
+    /// The largest pixel scale that zooming in goes to.
+    pub max_scale: u32,
+    /// Where [`PixelCameraActions::reset`] moves the camera.
+    pub home: Vec2,
+    peek_offset: Vec2,
+}
+
+impl Default for PixelCameraController {
+    fn default() -> Self {
+        Self {
+            actions: PixelCameraActions::default(),
+            pan_speed: 100.0,
+            peek_distance: 32.0,
+            peek_speed: 8.0,
+            min_scale: 1,
+            max_scale: 8,
+            home: Vec2::ZERO,
+            peek_offset: Vec2::ZERO,
+        }
+    }
+}
+
+/// Sets the [`PixelCameraActions`] of a [`PixelCameraController`] from the keyboard.
+///
+/// Pans with WASD, peeks with the arrow keys, zooms with `+` and `-` and resets with `Home`
+/// by default.
+#[derive(Component, Debug, Clone)]
+pub struct PixelCameraKeyBindings {
+    /// The keys that pan the camera up, down, left and right.
+    pub pan: [KeyCode; 4],
+    /// The keys that peek up, down, left and right.
+    pub peek: [KeyCode; 4],
+    /// The key that makes the pixels bigger.
+    pub zoom_in: KeyCode,
+    /// The key that makes the pixels smaller.
+    pub zoom_out: KeyCode,
+    /// The key that moves the camera back to [`PixelCameraController::home`].
+    pub reset: KeyCode,
+}
+
+impl Default for PixelCameraKeyBindings {
+    fn default() -> Self {
+        Self {
+            pan: [KeyCode::KeyW, KeyCode::KeyS, KeyCode::KeyA, KeyCode::KeyD],
+            peek: [
+                KeyCode::ArrowUp,
+                KeyCode::ArrowDown,
+                KeyCode::ArrowLeft,
+                KeyCode::ArrowRight,
+            ],
+            zoom_in: KeyCode::Equal,
+            zoom_out: KeyCode::Minus,
+            reset: KeyCode::Home,
+        }
+    }
+}
+
+fn read_key_bindings(
+    mut cameras: Query<(&mut PixelCameraController, &PixelCameraKeyBindings)>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    let axes = |[up, down, left, right]: [KeyCode; 4]| {
+        let axis = |negative, positive| {
+            (keys.pressed(positive) as i8 - keys.pressed(negative) as i8) as f32
+        };
+        Vec2::new(axis(left, right), axis(down, up)).normalize_or_zero()
+    };
+
+    for (mut controller, bindings) in &mut cameras {
+        controller.actions = PixelCameraActions {
+            pan: axes(bindings.pan),
+            peek: axes(bindings.peek),
+            zoom_in: keys.just_pressed(bindings.zoom_in),
+            zoom_out: keys.just_pressed(bindings.zoom_out),
+            reset: keys.just_pressed(bindings.reset),
+        };
+    }
+}
+
+fn control_pixel_cameras(
+    mut cameras: Query<(Entity, &mut PixelCamera, &mut PixelCameraController)>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_seconds();
+
+    for (entity, mut camera, mut controller) in &mut cameras {
+        let actions = controller.actions;
+        if actions.reset {
+            camera.subpixel_pos = controller.home + controller.peek_offset;
+        }
+
+        let pan = actions.pan.clamp_length_max(1.0);
+        if pan != Vec2::ZERO {
+            camera.subpixel_pos += pan * controller.pan_speed * delta;
+        }
+
+        // The peek is applied as an offset on top of the panned position.
+        let target_offset = actions.peek.clamp_length_max(1.0) * controller.peek_distance;
+        let new_offset = controller
+            .peek_offset
+            .lerp(target_offset, (controller.peek_speed * delta).min(1.0));
+        if new_offset != controller.peek_offset {
+            camera.subpixel_pos += new_offset - controller.peek_offset;
+            controller.peek_offset = new_offset;
+        }
+
+        let zoom = match (actions.zoom_in, actions.zoom_out) {
+            (true, false) => 1,
+            (false, true) => -1,
+            _ => continue,
+        };
+        let ViewportSize::PixelFixed(scale) = camera.viewport_size else {
+            warn_once!(
+                "PixelCameraController on {entity:?} can only zoom with `ViewportSize::PixelFixed`"
+            );
+            continue;
+        };
+        let new_scale = scale
+            .saturating_add_signed(zoom)
+            .min(controller.max_scale)
+            .max(controller.min_scale)
+            .max(1);
+        if new_scale != scale {
+            camera.viewport_size = ViewportSize::PixelFixed(new_scale);
+        }
+    }
+}
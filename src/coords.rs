@@ -0,0 +1,117 @@
+//! Conversions between window and world coordinates through a pixel camera's viewport.
+
+use bevy::prelude::*;
+use bevy::render::render_resource::Extent3d;
+
+/// Everything needed to map a position through the viewport of a [`PixelCamera`](crate::components::PixelCamera).
+pub(crate) struct ViewportChain<'a> {
+    /// The camera that renders the world to the low resolution target.
+    pub camera: (&'a Camera, &'a GlobalTransform),
+    /// The camera that renders the viewport sprite to the window.
+    pub viewport_camera: (&'a Camera, &'a GlobalTransform),
+    /// The sprite that displays the low resolution target.
+    pub sprite: (&'a Sprite, &'a GlobalTransform),
+    /// The size of the low resolution target, including the smoothing margin.
+    pub size: Extent3d,
+}
+
+impl ViewportChain<'_> {
+    /// Returns the area of the target that the viewport sprite displays, in texels.
+    fn sprite_rect(&self) -> Rect {
+        self.sprite.0.rect.unwrap_or(Rect {
+            min: Vec2::ZERO,
+            max: Vec2::new(self.size.width as f32, self.size.height as f32),
+        })
+    }
+
+    /// Converts a logical position in the window into a position in the low resolution target,
+    /// in texels with the origin at the top left.
+    ///
+    /// Returns [`None`] if the position is outside of the viewport sprite (e.g. on the letterbox bars).
+    pub fn window_to_texel(&self, window_position: Vec2) -> Option<Vec2> {
+        let (viewport_camera, viewport_camera_transform) = self.viewport_camera;
+        let (_, sprite_transform) = self.sprite;
+
+        // Split-screen viewport cameras only cover part of the window.
+        let viewport_rect = viewport_camera.logical_viewport_rect()?;
+        if !viewport_rect.contains(window_position) {
+            return None;
+        }
+        let window_position = window_position - viewport_rect.min;
+
+        // The viewport entities are children of the camera, so they can be rotated in the world
+        // (e.g. with a 3D camera). The sprite faces the viewport camera, so the ray's origin
+        // lands on the right spot of the sprite in its local space.
+        let viewport_ray =
+            viewport_camera.viewport_to_world(viewport_camera_transform, window_position)?;
+        let local = sprite_transform
+            .affine()
+            .inverse()
+            .transform_point3(viewport_ray.origin)
+            .truncate();
+
+        let rect = self.sprite_rect();
+        let half_size = rect.half_size();
+        if local.x.abs() > half_size.x || local.y.abs() > half_size.y {
+            return None;
+        }
+
+        // Sprites are centered, and their texture's y axis points down.
+        Some(Vec2::new(
+            rect.min.x + half_size.x + local.x,
+            rect.min.y + half_size.y - local.y,
+        ))
+    }
+
+    /// Converts a position in the world into a logical position in the window.
+    ///
+    /// The position is rounded to the low resolution pixel grid first, like everything that
+    /// the camera renders. Returns [`None`] if the position isn't visible on the viewport sprite.
+    pub fn world_to_window(&self, world_position: Vec3) -> Option<Vec2> {
+        let (camera, camera_transform) = self.camera;
+        let (viewport_camera, viewport_camera_transform) = self.viewport_camera;
+        let (_, sprite_transform) = self.sprite;
+
+        let texel = camera
+            .world_to_viewport(camera_transform, world_position)?
+            .round();
+
+        let rect = self.sprite_rect();
+        let half_size = rect.half_size();
+        let local = Vec2::new(
+            texel.x - rect.min.x - half_size.x,
+            rect.min.y + half_size.y - texel.y,
+        );
+        if local.x.abs() > half_size.x || local.y.abs() > half_size.y {
+            return None;
+        }
+
+        let viewport_position = viewport_camera.world_to_viewport(
+            viewport_camera_transform,
+            sprite_transform.transform_point(local.extend(0.0)),
+        )?;
+        // Split-screen viewport cameras only cover part of the window.
+        let viewport_rect = viewport_camera.logical_viewport_rect()?;
+        Some(viewport_position + viewport_rect.min)
+    }
+
+    /// Converts a logical position in the window into a position in the world.
+    ///
+    /// Returns [`None`] if the position is outside of the viewport sprite (e.g. on the letterbox bars).
+    pub fn window_to_world(&self, window_position: Vec2) -> Option<Vec2> {
+        let (camera, camera_transform) = self.camera;
+        let texel = self.window_to_texel(window_position)?;
+
+        camera.viewport_to_world_2d(camera_transform, texel)
+    }
+
+    /// Converts a logical position in the window into a ray in the world, for 3D cameras.
+    ///
+    /// Returns [`None`] if the position is outside of the viewport sprite (e.g. on the letterbox bars).
+    pub fn window_to_ray(&self, window_position: Vec2) -> Option<Ray3d> {
+        let (camera, camera_transform) = self.camera;
+        let texel = self.window_to_texel(window_position)?;
+
+        camera.viewport_to_world(camera_transform, texel)
+    }
+}
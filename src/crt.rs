@@ -0,0 +1,184 @@
+//! A CRT effect for the upscaled image of a [`PixelCamera`].
+//!
+//! The effect is applied while the viewport is shown on the window, so the scanlines and the
+//! bloom follow the texels of the low resolution target and the mask follows the pixels of the
+//! window. It's built on [`PixelViewportMaterial`].
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{AsBindGroup, ShaderRef};
+use bevy::sprite::Material2d;
+
+use crate::components::PixelCamera;
+use crate::material::{
+    PixelViewportMaterial, ViewportMaterial, ViewportMaterialInput, ViewportMaterialPlugin,
+};
+use crate::CameraSystems;
+
+const CRT_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(0x3b7d_c5e2_91a4_4f08_8e6b_d2c1_57f9_a034);
+
+/// Adds the CRT effect for cameras with [`CrtSettings`].
+pub struct PixelCrtPlugin;
+impl Plugin for PixelCrtPlugin {
+    fn build(&self, app: &mut App) {
+        bevy::asset::load_internal_asset!(app, CRT_SHADER_HANDLE, "crt.wgsl", Shader::from_wgsl);
+
+        app.add_plugins(ViewportMaterialPlugin::<CrtMaterial>::default())
+            .register_type::<CrtSettings>()
+            .add_systems(
+                PostUpdate,
+                apply_crt_settings
+                    .after(CameraSystems::Initialization)
+                    .before(CameraSystems::Update),
+            );
+    }
+}
+
+/// Shows the viewport of a [`PixelCamera`] like an old CRT screen, requires the
+/// [`PixelCrtPlugin`].
+///
+/// Every effect can be turned off by setting it to zero. Remove the component to show the plain
+/// viewport again.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::crt::CrtSettings;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn setup(mut commands: Commands) {
+///     commands.spawn((
+///         Camera2dBundle::default(),
+///         PixelCamera::from_size(ViewportSize::PixelFixed(4)),
+///         CrtSettings {
+///             curvature: 0.0,
+///             ..default()
+///         },
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component, Default)]
+pub struct CrtSettings {
+    /// How much darker the gaps between the rows of texels are, from 0 to 1.
+    pub scanlines: f32,
+    /// How much the aperture grille darkens the other colors of each pixel, from 0 to 1.
+    pub mask: f32,
+    /// How much the screen bulges out, 0 keeps it flat.
+    pub curvature: f32,
+    /// How much bright texels glow.
+    pub bloom: f32,
+}
+
+impl Default for CrtSettings {
+    fn default() -> Self {
+        Self {
+            scanlines: 0.3,
+            mask: 0.2,
+            curvature: 0.05,
+            bloom: 0.15,
+        }
+    }
+}
+
+/// The [`ViewportMaterial`] of the CRT effect, added to cameras with [`CrtSettings`].
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+pub struct CrtMaterial {
+    #[uniform(0)]
+    uniform: CrtUniform,
+    #[texture(1)]
+    #[sampler(2)]
+    image: Handle<Image>,
+}
+
+use uniform::CrtUniform;
+
+// `ShaderType` generates layout checks that are never called.
+#[allow(dead_code)]
+mod uniform {
+    use bevy::math::{Vec2, Vec4};
+    use bevy::render::render_resource::ShaderType;
+
+    /// The uniform of the CRT shader.
+    #[derive(ShaderType, Debug, Clone, Copy, Default)]
+    pub(crate) struct CrtUniform {
+        /// The visible part of the target, in UV coordinates.
+        pub uv_rect: Vec4,
+        pub target_size: Vec2,
+        pub scanlines: f32,
+        pub mask: f32,
+        pub curvature: f32,
+        pub bloom: f32,
+    }
+}
+
+impl CrtMaterial {
+    fn set_settings(&mut self, settings: &CrtSettings) {
+        self.uniform.scanlines = settings.scanlines;
+        self.uniform.mask = settings.mask;
+        self.uniform.curvature = settings.curvature;
+        self.uniform.bloom = settings.bloom;
+    }
+}
+
+impl Material2d for CrtMaterial {
+    fn fragment_shader() -> ShaderRef {
+        CRT_SHADER_HANDLE.into()
+    }
+}
+
+impl ViewportMaterial for CrtMaterial {
+    fn set_viewport(&mut self, viewport: &ViewportMaterialInput) {
+        let ViewportMaterialInput {
+            image,
+            target_size,
+            uv_rect,
+            ..
+        } = viewport;
+        self.image = image.clone();
+        self.uniform.uv_rect =
+            Vec4::new(uv_rect.min.x, uv_rect.min.y, uv_rect.max.x, uv_rect.max.y);
+        self.uniform.target_size = *target_size;
+    }
+}
+
+/// Adds, updates and removes the [`CrtMaterial`]s of cameras with [`CrtSettings`].
+#[allow(clippy::type_complexity)]
+fn apply_crt_settings(
+    cameras: Query<
+        (
+            Entity,
+            Ref<CrtSettings>,
+            Option<&PixelViewportMaterial<CrtMaterial>>,
+        ),
+        With<PixelCamera>,
+    >,
+    mut removed: RemovedComponents<CrtSettings>,
+    mut materials: ResMut<Assets<CrtMaterial>>,
+    mut commands: Commands,
+) {
+    for (entity, settings, material) in &cameras {
+        match material {
+            None => {
+                let mut material = CrtMaterial {
+                    uniform: CrtUniform::default(),
+                    image: Handle::default(),
+                };
+                material.set_settings(&settings);
+                commands
+                    .entity(entity)
+                    .insert(PixelViewportMaterial(materials.add(material)));
+            }
+            Some(PixelViewportMaterial(material)) if settings.is_changed() => {
+                if let Some(material) = materials.get_mut(material) {
+                    material.set_settings(&settings);
+                }
+            }
+            Some(_) => {}
+        }
+    }
+
+    for entity in removed.read() {
+        if let Some(mut entity) = commands.get_entity(entity) {
+            entity.remove::<PixelViewportMaterial<CrtMaterial>>();
+        }
+    }
+}
@@ -0,0 +1,52 @@
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+struct Crt {
+    uv_rect: vec4<f32>,
+    target_size: vec2<f32>,
+    scanlines: f32,
+    mask: f32,
+    curvature: f32,
+    bloom: f32,
+}
+
+@group(2) @binding(0) var<uniform> crt: Crt;
+@group(2) @binding(1) var image: texture_2d<f32>;
+@group(2) @binding(2) var image_sampler: sampler;
+
+const PI: f32 = 3.14159265;
+
+fn sample_image(uv: vec2<f32>) -> vec3<f32> {
+    return textureSample(image, image_sampler, uv).rgb;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Bend the screen outward from its center, like the glass of a CRT.
+    let centered = in.uv * 2.0 - 1.0;
+    let bent = centered + centered * centered.yx * centered.yx * crt.curvature;
+    let uv = bent * 0.5 + 0.5;
+    let inside = all(uv >= vec2<f32>(0.0)) && all(uv <= vec2<f32>(1.0));
+
+    // The visible part of the target, which moves with the smoothing.
+    let image_uv = mix(crt.uv_rect.xy, crt.uv_rect.zw, uv);
+    let texel = 1.0 / crt.target_size;
+    var color = sample_image(image_uv);
+
+    // Bright texels bleed into their neighbors.
+    let blurred = (sample_image(image_uv + vec2<f32>(texel.x, 0.0))
+        + sample_image(image_uv - vec2<f32>(texel.x, 0.0))
+        + sample_image(image_uv + vec2<f32>(0.0, texel.y))
+        + sample_image(image_uv - vec2<f32>(0.0, texel.y))) * 0.25;
+    color += blurred * blurred * crt.bloom;
+
+    // Darken the gaps between the rows of texels.
+    let row = fract(image_uv.y * crt.target_size.y);
+    color *= mix(1.0 - crt.scanlines, 1.0, sin(row * PI));
+
+    // An aperture grille, with a red, green and blue stripe for every three pixels of the window.
+    var mask = vec3<f32>(1.0 - crt.mask);
+    mask[u32(in.position.x) % 3u] = 1.0;
+    color *= mask;
+
+    return vec4<f32>(color * f32(inside), 1.0);
+}
@@ -0,0 +1,186 @@
+//! Finding what's under the cursor through the viewport of a [`PixelCamera`].
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::render::camera::NormalizedRenderTarget;
+use bevy::window::PrimaryWindow;
+
+use crate::components::{
+    PixelCamera, PixelCamera3d, PixelViewport, PixelViewportReferences, ViewportCamera,
+};
+use crate::coords::ViewportChain;
+
+/// Keeps the [`CursorWorldPos`] resource up to date.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// App::new().add_plugins(PixelCursorPlugin);
+/// ```
+pub struct PixelCursorPlugin;
+impl Plugin for PixelCursorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CursorWorldPos>()
+            .add_systems(PreUpdate, update_cursor_world_pos);
+    }
+}
+
+/// The world position under the cursor, through the viewport of the active 2D [`PixelCamera`]
+/// that the cursor is on. Updated by [`PixelCursorPlugin`] before [`Update`].
+///
+/// [`None`] if the cursor isn't on any viewport, e.g. it's on the letterbox bars or outside
+/// of the window. Use [`PixelCameraCursor`] for a specific camera.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq)]
+pub struct CursorWorldPos(pub Option<Vec2>);
+
+/// Whether another UI (e.g. egui) is using the pointer, in which case the pointer features of
+/// the pixel camera ([`PixelPanCam`](crate::pancam::PixelPanCam), `PixelInteraction` with the
+/// `ui` feature and [`PixelPickingPlugin`](crate::picking::PixelPickingPlugin)) ignore it.
+///
+/// Set it from that UI's systems, as early in the frame as possible:
+///
+/// ```ignore
+/// // With bevy_egui, after `EguiSet::BeginFrame` in `PreUpdate`.
+/// fn capture_pointer(mut contexts: EguiContexts, mut captured: ResMut<PointerCaptured>) {
+///     let ctx = contexts.ctx_mut();
+///     captured.set_if_neq(PointerCaptured(
+///         ctx.wants_pointer_input() || ctx.is_pointer_over_area(),
+///     ));
+/// }
+/// ```
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PointerCaptured(pub bool);
+
+#[allow(clippy::type_complexity)]
+fn update_cursor_world_pos(
+    cursor: PixelCameraCursor,
+    cameras: Query<(Entity, &Camera), (With<PixelCamera>, Without<PixelCamera3d>)>,
+    mut cursor_world_pos: ResMut<CursorWorldPos>,
+) {
+    let position = cameras
+        .iter()
+        .filter(|(_, camera)| camera.is_active)
+        .find_map(|(entity, _)| cursor.cursor_world_position(entity));
+
+    cursor_world_pos.set_if_neq(CursorWorldPos(position));
+}
+
+/// A [`SystemParam`] for casting the window cursor through the viewport of a [`PixelCamera`].
+///
+/// Accounts for the scale, letterboxing and smoothing offset of the viewport.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn hover(cursor: PixelCameraCursor, cameras: Query<Entity, With<PixelCamera>>) {
+///     for camera in &cameras {
+///         if let Some(position) = cursor.cursor_world_position(camera) {
+///             info!("The cursor is at {position} in the world");
+///         }
+///     }
+/// }
+/// ```
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn pick(cursor: PixelCameraCursor, cameras: Query<Entity, With<PixelCamera3d>>) {
+///     for camera in &cameras {
+///         if let Some(ray) = cursor.cursor_ray(camera) {
+///             info!("The cursor points along {ray:?}");
+///         }
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct PixelCameraCursor<'w, 's> {
+    cameras: Query<
+        'w,
+        's,
+        (
+            &'static Camera,
+            &'static GlobalTransform,
+            &'static PixelViewportReferences,
+        ),
+        With<PixelCamera>,
+    >,
+    viewport_cameras:
+        Query<'w, 's, (&'static Camera, &'static GlobalTransform), With<ViewportCamera>>,
+    viewport_sprites:
+        Query<'w, 's, (&'static Sprite, &'static GlobalTransform), With<PixelViewport>>,
+    windows: Query<'w, 's, &'static Window>,
+    primary_window: Query<'w, 's, Entity, With<PrimaryWindow>>,
+}
+
+impl PixelCameraCursor<'_, '_> {
+    /// Returns the world position under the cursor for a 2D [`PixelCamera`].
+    ///
+    /// Returns [`None`] if the cursor isn't on the camera's viewport (e.g. it's on the
+    /// letterbox bars or outside of the window) or the camera isn't initialized yet.
+    pub fn cursor_world_position(&self, camera: Entity) -> Option<Vec2> {
+        let (chain, window) = self.chain(camera)?;
+        let cursor = self.windows.get(window).ok()?.cursor_position()?;
+
+        chain.window_to_world(cursor)
+    }
+
+    /// Converts a logical position in the window that a 2D [`PixelCamera`]'s viewport is
+    /// shown on into a world position.
+    ///
+    /// Unlike [`Camera::viewport_to_world_2d`] on the camera itself, this maps the position
+    /// through the viewport sprite, so it accounts for the viewport's scale, the letterbox bars
+    /// of its [`FitMode`](crate::viewport::FitMode) and the smoothing offset.
+    ///
+    /// Returns [`None`] if the position isn't on the camera's viewport or the camera isn't
+    /// initialized yet.
+    pub fn viewport_to_world_2d(&self, camera: Entity, window_position: Vec2) -> Option<Vec2> {
+        self.chain(camera)?.0.window_to_world(window_position)
+    }
+
+    /// Converts a world position into a logical position in the window that a [`PixelCamera`]'s
+    /// viewport is shown on, e.g. to place full resolution UI over something in the world.
+    ///
+    /// The inverse of [`viewport_to_world_2d`](Self::viewport_to_world_2d): the position is
+    /// snapped to the low resolution pixel grid, then scaled and offset like the viewport.
+    ///
+    /// Returns [`None`] if the position isn't visible on the camera's viewport or the camera
+    /// isn't initialized yet.
+    pub fn world_to_window(&self, camera: Entity, world_position: Vec3) -> Option<Vec2> {
+        self.chain(camera)?.0.world_to_window(world_position)
+    }
+
+    /// Returns the world space ray from the cursor through the low resolution render target
+    /// of a 3D [`PixelCamera`].
+    ///
+    /// Returns [`None`] if the cursor isn't on the camera's viewport (e.g. it's on the
+    /// letterbox bars or outside of the window) or the camera isn't initialized yet.
+    pub fn cursor_ray(&self, camera: Entity) -> Option<Ray3d> {
+        let (chain, window) = self.chain(camera)?;
+        let cursor = self.windows.get(window).ok()?.cursor_position()?;
+
+        chain.window_to_ray(cursor)
+    }
+
+    /// Returns the viewport of a camera, and the window that it's shown on.
+    fn chain(&self, camera: Entity) -> Option<(ViewportChain<'_>, Entity)> {
+        let (camera, camera_transform, viewport) = self.cameras.get(camera).ok()?;
+        let viewport_camera = self.viewport_cameras.get(viewport.camera).ok()?;
+        let sprite = self.viewport_sprites.get(viewport.sprite).ok()?;
+
+        let primary_window = self.primary_window.get_single().ok();
+        let window = match viewport_camera.0.target.normalize(primary_window)? {
+            NormalizedRenderTarget::Window(window) => window.entity(),
+            _ => return None,
+        };
+
+        Some((
+            ViewportChain {
+                camera: (camera, camera_transform),
+                viewport_camera,
+                sprite,
+                size: viewport.size,
+            },
+            window,
+        ))
+    }
+}
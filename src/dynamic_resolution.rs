@@ -0,0 +1,102 @@
+//! Automatic resolution scaling based on frame time.
+
+use bevy::prelude::*;
+
+use crate::components::{PixelCamera, PixelFreeze};
+use crate::viewport::ViewportSize;
+
+/// Automatically changes the pixel scale of a [`PixelCamera`] to hold a target frame rate.
+///
+/// Add this component next to a [`PixelCamera`] that uses [`ViewportSize::PixelFixed`].
+/// When frames take too long, the pixel scale is increased (lowering the internal
+/// resolution), and when there is enough headroom it is decreased again.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component, Default)]
+pub struct DynamicResolution {
+    /// The frame time to hold, in seconds.
+    pub target_frame_time: f32,
+    /// The smallest pixel scale (highest internal resolution) that will be used.
+    pub min_scale: u32,
+    /// The largest pixel scale (lowest internal resolution) that will be used.
+    pub max_scale: u32,
+    /// How far the average frame time may stray from the target before the scale changes,
+    /// as a fraction of [`DynamicResolution::target_frame_time`].
+    pub tolerance: f32,
+    /// The minimum time between two scale changes, in seconds.
+    ///
+    /// Gives the frame time a chance to settle after the render target is resized.
+    pub cooldown: f32,
+    average_frame_time: f32,
+    since_last_change: f32,
+}
+
+impl Default for DynamicResolution {
+    fn default() -> Self {
+        Self::new(60.0, 1, 8)
+    }
+}
+
+impl DynamicResolution {
+    /// Creates a new [`DynamicResolution`] that tries to hold `target_fps`
+    /// by keeping the pixel scale between `min_scale` and `max_scale`.
+    pub fn new(target_fps: f32, min_scale: u32, max_scale: u32) -> Self {
+        Self {
+            target_frame_time: 1.0 / target_fps,
+            min_scale: min_scale.max(1),
+            max_scale: max_scale.max(min_scale).max(1),
+            tolerance: 0.1,
+            cooldown: 1.0,
+            average_frame_time: 1.0 / target_fps,
+            since_last_change: 0.0,
+        }
+    }
+    /// Returns the smoothed frame time that is used to decide the scale, in seconds.
+    pub fn average_frame_time(&self) -> f32 {
+        self.average_frame_time
+    }
+}
+
+/// Steps the pixel scale of cameras with [`DynamicResolution`] up or down.
+pub(crate) fn update_dynamic_resolution(
+    mut cameras: Query<(Entity, &mut PixelCamera, &mut DynamicResolution), Without<PixelFreeze>>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_seconds();
+    if delta <= 0.0 {
+        return;
+    }
+
+    for (entity, mut camera, mut dynamic) in &mut cameras {
+        // Exponential moving average so single frame spikes don't change the resolution.
+        dynamic.average_frame_time += (delta - dynamic.average_frame_time) * 0.1;
+        dynamic.since_last_change += delta;
+
+        let ViewportSize::PixelFixed(scale) = camera.viewport_size else {
+            warn_once!(
+                "DynamicResolution on {entity:?} only works with `ViewportSize::PixelFixed`"
+            );
+            continue;
+        };
+        if dynamic.since_last_change < dynamic.cooldown {
+            continue;
+        }
+
+        let upper = dynamic.target_frame_time * (1.0 + dynamic.tolerance);
+        // Only step back up if the frame time would likely stay under the target
+        // after rendering more pixels, to avoid bouncing between two scales.
+        let lower = dynamic.target_frame_time * (1.0 - dynamic.tolerance) * 0.75;
+
+        let new_scale = if dynamic.average_frame_time > upper {
+            (scale + 1).min(dynamic.max_scale)
+        } else if dynamic.average_frame_time < lower {
+            scale.saturating_sub(1).max(dynamic.min_scale)
+        } else {
+            scale
+        };
+
+        if new_scale != scale {
+            camera.viewport_size = ViewportSize::PixelFixed(new_scale);
+            dynamic.since_last_change = 0.0;
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Errors reported by [`PixelCameraPlugin`](crate::PixelCameraPlugin).
+
+use std::fmt;
+
+use bevy::prelude::*;
+
+/// An [`Event`] sent whenever a [`PixelCamera`](crate::components::PixelCamera)
+/// fails to initialize or update.
+///
+/// Every error is also logged, reading these events is only necessary
+/// if you want to react to them (e.g. by showing a diagnostic or falling back to a normal camera).
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct PixelCameraError {
+    /// The entity with the [`PixelCamera`](crate::components::PixelCamera) component.
+    pub camera: Entity,
+    /// What went wrong.
+    pub kind: PixelCameraErrorKind,
+}
+
+/// The cause of a [`PixelCameraError`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PixelCameraErrorKind {
+    /// The render layers of the world intersect with the render layers of the viewport camera.
+    LayerConflict,
+    /// The viewport camera has no render layers and would be rendered on the world.
+    EmptyViewportLayer,
+    /// The camera renders later or at the same time as the viewport camera.
+    /// (`camera.order >= viewport_order`)
+    BadOrder,
+    /// The window that the camera renders to doesn't exist.
+    MissingWindow(Option<Entity>),
+    /// The image that the camera renders to doesn't exist.
+    MissingImage,
+    /// The viewport sprite or viewport camera spawned for the camera no longer exists.
+    MissingViewport,
+    /// The camera's render target is not supported.
+    UnsupportedTarget,
+    /// The camera's [`ViewportSize`](crate::viewport::ViewportSize) has a scale of zero
+    /// or results in a viewport without any pixels. The viewport is clamped to 1×1 pixels.
+    InvalidViewportSize,
+    /// The render target would be larger than the device supports.
+    /// The target is clamped to the largest supported size.
+    TargetTooLarge,
+}
+
+impl fmt::Display for PixelCameraError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let camera = self.camera;
+        match &self.kind {
+            PixelCameraErrorKind::LayerConflict => write!(f, "PixelCamera {camera:?}: The render layers of the world intersect with the render layers of the viewport camera"),
+            PixelCameraErrorKind::EmptyViewportLayer => write!(f, "PixelCamera {camera:?}: The viewport camera has no render layers and will be rendered on the world"),
+            PixelCameraErrorKind::BadOrder => write!(f, "PixelCamera {camera:?}: The camera is configured to render later or at the same time as of the viewport camera. (camera.order >= viewport_camera.order)"),
+            PixelCameraErrorKind::MissingWindow(None) => write!(f, "PixelCamera {camera:?}: The primary window that the PixelCamera is pointing to doesn't exist."),
+            PixelCameraErrorKind::MissingWindow(Some(window)) => write!(f, "PixelCamera {camera:?}: Window {window:?} that the PixelCamera is pointing to doesn't exist."),
+            PixelCameraErrorKind::MissingImage => write!(f, "PixelCamera {camera:?}: The render target image doesn't exist."),
+            PixelCameraErrorKind::MissingViewport => write!(f, "PixelCamera {camera:?}: The viewport sprite or camera no longer exists."),
+            PixelCameraErrorKind::UnsupportedTarget => write!(f, "PixelCamera {camera:?}: RenderTarget::TextureView is not yet supported for `bevy_smooth_pixel_camera`"),
+            PixelCameraErrorKind::InvalidViewportSize => write!(f, "PixelCamera {camera:?}: The viewport size has a scale of zero or results in a viewport without any pixels."),
+            PixelCameraErrorKind::TargetTooLarge => write!(f, "PixelCamera {camera:?}: The render target is larger than the device supports and has been clamped."),
+        }
+    }
+}
+
+impl std::error::Error for PixelCameraError {}
+
+impl PixelCameraError {
+    /// Creates a new [`PixelCameraError`] for the `camera` entity.
+    pub fn new(camera: Entity, kind: PixelCameraErrorKind) -> Self {
+        Self { camera, kind }
+    }
+}
+
+/// Logs the error and sends it as a [`PixelCameraError`] event.
+pub(crate) fn report(
+    errors: &mut EventWriter<PixelCameraError>,
+    camera: Entity,
+    kind: PixelCameraErrorKind,
+) {
+    let error = PixelCameraError::new(camera, kind);
+    error!("{error}");
+    errors.send(error);
+}
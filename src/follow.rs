@@ -0,0 +1,501 @@
+//! Making a [`PixelCamera`] follow an entity.
+
+use bevy::ecs::entity::{EntityMapper, MapEntities};
+use bevy::ecs::reflect::ReflectMapEntities;
+use bevy::prelude::*;
+use bevy::transform::helper::TransformHelper;
+use bevy::utils::HashMap;
+
+use crate::components::{PixelCamera, PixelCamera3d, PixelFreeze};
+
+/// Moves the [`PixelCamera::subpixel_pos`] of a 2D [`PixelCamera`] toward an entity every frame.
+///
+/// The position of the target is taken from its [`Transform`] (and those of its ancestors) in
+/// the same frame, so the camera doesn't lag a frame behind a target that moved in [`Update`].
+/// The followed position is still clamped to the camera's [`CameraBounds`](crate::bounds::CameraBounds).
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn setup(mut commands: Commands) {
+///     let player = commands.spawn(SpriteBundle::default()).id();
+///     commands.spawn((
+///         Camera2dBundle::default(),
+///         PixelCamera::default(),
+///         // A platformer camera, that only moves once the player leaves the middle of the screen.
+///         PixelCameraFollow {
+///             dead_zone: Vec2::new(48.0, 32.0),
+///             ..PixelCameraFollow::new(player)
+///         },
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component, MapEntities)]
+pub struct PixelCameraFollow {
+    /// The entity to follow. Nothing happens while it doesn't exist.
+    pub target: Entity,
+    /// How quickly the camera catches up with the target.
+    ///
+    /// The distance to the target shrinks by a factor of e every `1 / lerp_speed` seconds,
+    /// independent of the frame rate. [`f32::INFINITY`] keeps the target centered.
+    pub lerp_speed: f32,
+    /// The size of a rectangle around the center of the view that the target can move in
+    /// without moving the camera, in texels. Once the target leaves it, the camera moves just
+    /// far enough to bring the target back to its edge.
+    ///
+    /// Defaults to [`Vec2::ZERO`], which keeps following the target's exact position.
+    pub dead_zone: Vec2,
+    /// Looks ahead in the direction that the target moves in, see [`LookAhead`].
+    pub look_ahead: Option<LookAhead>,
+}
+
+/// Offsets a [`PixelCameraFollow`] camera in the direction that its target moves in,
+/// so that more of what's ahead of a fast target is visible.
+///
+/// The offset ramps up while the target moves, and back down once it stops.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+pub struct LookAhead {
+    /// How far ahead of the target the camera looks, in texels.
+    pub distance: f32,
+    /// How many seconds the offset takes to ramp from the center to the full `distance`.
+    pub ramp_time: f32,
+    /// The axes to look ahead along, e.g. only horizontally in a platformer.
+    pub axes: BVec2,
+}
+
+impl Default for LookAhead {
+    fn default() -> Self {
+        Self {
+            distance: 24.0,
+            ramp_time: 0.5,
+            axes: BVec2::TRUE,
+        }
+    }
+}
+
+impl PixelCameraFollow {
+    /// Follows `target` with a `lerp_speed` of 8, without a dead zone or look ahead.
+    pub fn new(target: Entity) -> Self {
+        Self {
+            target,
+            lerp_speed: 8.0,
+            dead_zone: Vec2::ZERO,
+            look_ahead: None,
+        }
+    }
+}
+
+impl MapEntities for PixelCameraFollow {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.target = entity_mapper.map_entity(self.target);
+    }
+}
+
+/// Keeps a group of entities in view of a 2D [`PixelCamera`], e.g. the players of a local
+/// co-op game, by moving the camera to their center and picking a [`PixelCamera::zoom`]
+/// that fits all of them.
+///
+/// Only cameras with a [`ViewportSize::PixelFixed`](crate::viewport::ViewportSize::PixelFixed)
+/// viewport are zoomed, others are only moved. Don't combine it with a [`PixelCameraFollow`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn setup(mut commands: Commands) {
+///     let players = vec![
+///         commands.spawn(SpriteBundle::default()).id(),
+///         commands.spawn(SpriteBundle::default()).id(),
+///     ];
+///     commands.spawn((
+///         Camera2dBundle::default(),
+///         PixelCamera::from_size(ViewportSize::PixelFixed(4)),
+///         PixelCameraGroupFollow::new(players),
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone, PartialEq)]
+#[reflect(Component, MapEntities)]
+pub struct PixelCameraGroupFollow {
+    /// The entities to keep in view. The ones that don't exist are ignored.
+    pub targets: Vec<Entity>,
+    /// How quickly the camera catches up with the center of the targets,
+    /// see [`PixelCameraFollow::lerp_speed`].
+    pub lerp_speed: f32,
+    /// The space to keep around the targets, in texels.
+    pub margin: f32,
+    /// The smallest zoom to zoom out to, even if the targets don't fit.
+    pub min_zoom: u32,
+    /// The largest zoom to zoom in to.
+    pub max_zoom: u32,
+    /// How much more room the targets need before zooming in, as a fraction of their size.
+    ///
+    /// Keeps the zoom from switching back and forth while the targets are right at the edge
+    /// of fitting the next zoom. Zooming out happens as soon as the targets don't fit.
+    pub hysteresis: f32,
+}
+
+impl PixelCameraGroupFollow {
+    /// Keeps `targets` in view with a zoom between 1 and 8.
+    pub fn new(targets: Vec<Entity>) -> Self {
+        Self {
+            targets,
+            lerp_speed: 8.0,
+            margin: 16.0,
+            min_zoom: 1,
+            max_zoom: 8,
+            hysteresis: 0.1,
+        }
+    }
+}
+
+impl MapEntities for PixelCameraGroupFollow {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        for target in &mut self.targets {
+            *target = entity_mapper.map_entity(*target);
+        }
+    }
+}
+
+/// The look ahead state of a [`PixelCameraFollow`] camera.
+#[derive(Default)]
+pub(crate) struct LookAheadState {
+    /// The position of the target in the last frame.
+    last_target: Option<Vec2>,
+    /// The current offset, in texels.
+    offset: Vec2,
+}
+
+/// Moves cameras with a [`PixelCameraFollow`] toward their target.
+#[allow(clippy::type_complexity)]
+pub(crate) fn follow_targets(
+    mut cameras: Query<
+        (
+            Entity,
+            &mut PixelCamera,
+            &PixelCameraFollow,
+            Option<&OrthographicProjection>,
+        ),
+        (Without<PixelCamera3d>, Without<PixelFreeze>),
+    >,
+    transforms: TransformHelper,
+    time: Res<Time>,
+    mut look_ahead_states: Local<HashMap<Entity, LookAheadState>>,
+) {
+    look_ahead_states.retain(|entity, _| {
+        cameras
+            .get(*entity)
+            .is_ok_and(|(_, _, follow, _)| follow.look_ahead.is_some())
+    });
+
+    for (entity, mut camera, follow, projection) in &mut cameras {
+        let Ok(target) = transforms.compute_global_transform(follow.target) else {
+            continue;
+        };
+        let mut target = target.translation().truncate();
+        // A texel covers `scale` world units.
+        let scale = projection.map_or(1.0, |projection| projection.scale);
+
+        if let Some(look_ahead) = follow.look_ahead {
+            let state = look_ahead_states.entry(entity).or_default();
+            let offset = look_ahead_offset(&look_ahead, state, target, scale, time.delta_seconds());
+            target += offset * scale;
+        }
+        let target = dead_zone_target(camera.subpixel_pos, target, follow.dead_zone * scale / 2.0);
+
+        let amount = if follow.lerp_speed.is_infinite() {
+            1.0
+        } else {
+            1.0 - (-follow.lerp_speed * time.delta_seconds()).exp()
+        };
+        let position = camera.subpixel_pos.lerp(target, amount);
+
+        if position != camera.subpixel_pos {
+            camera.subpixel_pos = position;
+        }
+    }
+}
+
+/// Returns the position that the camera has to move to so that `target` is inside the dead zone
+/// with the given half size around it.
+fn dead_zone_target(position: Vec2, target: Vec2, half_size: Vec2) -> Vec2 {
+    let axis = |position: f32, target: f32, half_size: f32| {
+        if target > position + half_size {
+            target - half_size
+        } else if target < position - half_size {
+            target + half_size
+        } else {
+            position
+        }
+    };
+
+    Vec2::new(
+        axis(position.x, target.x, half_size.x),
+        axis(position.y, target.y, half_size.y),
+    )
+}
+
+/// Advances the look ahead offset of a camera, and returns it in texels.
+fn look_ahead_offset(
+    look_ahead: &LookAhead,
+    state: &mut LookAheadState,
+    target: Vec2,
+    scale: f32,
+    delta_seconds: f32,
+) -> Vec2 {
+    let last_target = state.last_target.replace(target);
+    if delta_seconds <= 0.0 {
+        return state.offset;
+    }
+    // The velocity of the target in texels per second, moving less than a texel
+    // per second counts as standing still.
+    let velocity = last_target.map_or(Vec2::ZERO, |last_target| {
+        (target - last_target) / scale / delta_seconds
+    });
+    let direction = |velocity: f32, enabled: bool| {
+        if enabled && velocity.abs() >= 1.0 {
+            velocity.signum()
+        } else {
+            0.0
+        }
+    };
+    let goal = Vec2::new(
+        direction(velocity.x, look_ahead.axes.x),
+        direction(velocity.y, look_ahead.axes.y),
+    ) * look_ahead.distance;
+
+    let max_step = if look_ahead.ramp_time > 0.0 {
+        look_ahead.distance / look_ahead.ramp_time * delta_seconds
+    } else {
+        f32::INFINITY
+    };
+    let step = |offset: f32, goal: f32| offset + (goal - offset).clamp(-max_step, max_step);
+    state.offset = Vec2::new(step(state.offset.x, goal.x), step(state.offset.y, goal.y));
+
+    state.offset
+}
+
+/// Moves and zooms cameras with a [`PixelCameraGroupFollow`] to keep their targets in view.
+#[allow(clippy::type_complexity)]
+pub(crate) fn follow_groups(
+    mut cameras: Query<
+        (
+            &mut PixelCamera,
+            &PixelCameraGroupFollow,
+            &OrthographicProjection,
+        ),
+        (Without<PixelCamera3d>, Without<PixelFreeze>),
+    >,
+    transforms: TransformHelper,
+    time: Res<Time>,
+) {
+    for (mut camera, group, projection) in &mut cameras {
+        let Some(area) = group
+            .targets
+            .iter()
+            .filter_map(|target| transforms.compute_global_transform(*target).ok())
+            .map(|target| Rect::from_center_size(target.translation().truncate(), Vec2::ZERO))
+            .reduce(|area, target| area.union(target))
+        else {
+            continue;
+        };
+
+        let amount = if group.lerp_speed.is_infinite() {
+            1.0
+        } else {
+            1.0 - (-group.lerp_speed * time.delta_seconds()).exp()
+        };
+        let position = camera.subpixel_pos.lerp(area.center(), amount);
+        if position != camera.subpixel_pos {
+            camera.subpixel_pos = position;
+        }
+
+        let Some(zoom) = camera.zoom() else {
+            continue;
+        };
+        // The size of the targets in texels, and the size of the window in the pixels that a
+        // texel covers `zoom` of.
+        let needed = area.size() / projection.scale + group.margin * 2.0;
+        let available = camera.visible_rect(projection).size() / projection.scale * zoom as f32;
+        if available.min_element() <= 0.0 {
+            continue;
+        }
+        let fitting_zoom = |needed: Vec2| (available / needed.max(Vec2::ONE)).min_element() as u32;
+
+        let fits = fitting_zoom(needed);
+        let new_zoom = if fits < zoom {
+            fits
+        } else {
+            zoom.max(fitting_zoom(needed * (1.0 + group.hysteresis)))
+        };
+        camera.set_zoom(new_zoom.clamp(group.min_zoom.max(1), group.max_zoom.max(1)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dead_zone_target_inside() {
+        let half_size = Vec2::new(16.0, 8.0);
+        assert_eq!(
+            dead_zone_target(Vec2::ZERO, Vec2::new(10.0, -5.0), half_size),
+            Vec2::ZERO
+        );
+        // The edges of the dead zone are still inside it.
+        assert_eq!(
+            dead_zone_target(Vec2::ZERO, Vec2::new(-16.0, 8.0), half_size),
+            Vec2::ZERO
+        );
+    }
+
+    #[test]
+    fn dead_zone_target_outside() {
+        let position = Vec2::new(100.0, 50.0);
+        let half_size = Vec2::new(16.0, 8.0);
+        // Only the axes where the target left the dead zone move, just far enough to bring it
+        // back to the edge.
+        assert_eq!(
+            dead_zone_target(position, Vec2::new(120.0, 50.0), half_size),
+            Vec2::new(104.0, 50.0)
+        );
+        assert_eq!(
+            dead_zone_target(position, Vec2::new(80.0, 30.0), half_size),
+            Vec2::new(96.0, 38.0)
+        );
+    }
+
+    #[test]
+    fn dead_zone_target_without_dead_zone() {
+        let target = Vec2::new(12.5, -3.25);
+        assert_eq!(dead_zone_target(Vec2::ZERO, target, Vec2::ZERO), target);
+        assert_eq!(dead_zone_target(target, target, Vec2::ZERO), target);
+    }
+
+    /// Moves the target by `step` every frame for `frames` frames of `delta_seconds`, and
+    /// returns the look ahead offset after the last one.
+    fn look_ahead_after(
+        look_ahead: &LookAhead,
+        state: &mut LookAheadState,
+        step: Vec2,
+        frames: u32,
+        delta_seconds: f32,
+    ) -> Vec2 {
+        let mut offset = state.offset;
+        for _ in 0..frames {
+            let target = state.last_target.unwrap_or_default() + step;
+            offset = look_ahead_offset(look_ahead, state, target, 1.0, delta_seconds);
+        }
+        offset
+    }
+
+    #[test]
+    fn look_ahead_ramps_up_and_down() {
+        // Ramps by 4 texels every 0.125 second frame.
+        let look_ahead = LookAhead {
+            distance: 16.0,
+            ramp_time: 0.5,
+            axes: BVec2::TRUE,
+        };
+        let mut state = LookAheadState::default();
+        // The first frame has no velocity to look ahead with.
+        assert_eq!(
+            look_ahead_after(&look_ahead, &mut state, Vec2::X, 1, 0.125),
+            Vec2::ZERO
+        );
+        assert_eq!(
+            look_ahead_after(&look_ahead, &mut state, Vec2::X, 1, 0.125),
+            Vec2::new(4.0, 0.0)
+        );
+        assert_eq!(
+            look_ahead_after(&look_ahead, &mut state, Vec2::X, 10, 0.125),
+            Vec2::new(16.0, 0.0)
+        );
+        assert_eq!(
+            look_ahead_after(&look_ahead, &mut state, Vec2::ZERO, 1, 0.125),
+            Vec2::new(12.0, 0.0)
+        );
+        // Turning around ramps through the center to the other side.
+        assert_eq!(
+            look_ahead_after(&look_ahead, &mut state, Vec2::NEG_X, 5, 0.125),
+            Vec2::new(-8.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn look_ahead_zero_ramp_time() {
+        let look_ahead = LookAhead {
+            distance: 16.0,
+            ramp_time: 0.0,
+            axes: BVec2::TRUE,
+        };
+        let mut state = LookAheadState::default();
+        assert_eq!(
+            look_ahead_after(&look_ahead, &mut state, Vec2::new(1.0, -1.0), 2, 0.125),
+            Vec2::new(16.0, -16.0)
+        );
+        assert_eq!(
+            look_ahead_after(&look_ahead, &mut state, Vec2::ZERO, 1, 0.125),
+            Vec2::ZERO
+        );
+    }
+
+    #[test]
+    fn look_ahead_disabled_axis() {
+        let look_ahead = LookAhead {
+            distance: 16.0,
+            ramp_time: 0.0,
+            axes: BVec2::new(true, false),
+        };
+        let mut state = LookAheadState::default();
+        assert_eq!(
+            look_ahead_after(&look_ahead, &mut state, Vec2::ONE, 2, 0.125),
+            Vec2::new(16.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn look_ahead_ignores_slow_targets() {
+        let look_ahead = LookAhead {
+            distance: 16.0,
+            ramp_time: 0.0,
+            axes: BVec2::TRUE,
+        };
+        let mut state = LookAheadState::default();
+        // Half a texel per second.
+        assert_eq!(
+            look_ahead_after(&look_ahead, &mut state, Vec2::splat(0.0625), 2, 0.125),
+            Vec2::ZERO
+        );
+        // Velocity is in texels, so the same movement is slower at a larger scale.
+        let mut state = LookAheadState::default();
+        look_ahead_offset(&look_ahead, &mut state, Vec2::ZERO, 4.0, 0.125);
+        assert_eq!(
+            look_ahead_offset(&look_ahead, &mut state, Vec2::splat(0.25), 4.0, 0.125),
+            Vec2::ZERO
+        );
+        assert_eq!(
+            look_ahead_offset(&look_ahead, &mut state, Vec2::splat(0.75), 4.0, 0.125),
+            Vec2::splat(16.0)
+        );
+    }
+
+    #[test]
+    fn look_ahead_paused() {
+        let look_ahead = LookAhead {
+            distance: 16.0,
+            ramp_time: 0.5,
+            axes: BVec2::TRUE,
+        };
+        let mut state = LookAheadState::default();
+        let offset = look_ahead_after(&look_ahead, &mut state, Vec2::X, 3, 0.125);
+        assert_eq!(offset, Vec2::new(8.0, 0.0));
+        // A frame without any time passing keeps the offset, but still tracks the target.
+        assert_eq!(
+            look_ahead_after(&look_ahead, &mut state, Vec2::new(100.0, 0.0), 1, 0.0),
+            offset
+        );
+        assert_eq!(state.last_target, Some(Vec2::new(103.0, 0.0)));
+    }
+}
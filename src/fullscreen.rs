@@ -0,0 +1,190 @@
+//! Exclusive fullscreen with perfect integer scaling.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowMode};
+use bevy::winit::WinitWindows;
+
+use crate::components::{PixelCamera, PixelViewportReferences, ViewportCamera};
+use crate::systems::viewport_window;
+use crate::viewport::ViewportSize;
+
+/// A [`SystemParam`] for putting the window of a [`PixelCamera`] into exclusive fullscreen,
+/// using the video mode of its monitor that fits the viewport best.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn enter_fullscreen(
+///     mut fullscreen: PixelFullscreen,
+///     cameras: Query<Entity, With<PixelCamera>>,
+/// ) {
+///     for camera in &cameras {
+///         if fullscreen.enter(camera).is_none() {
+///             warn!("No video mode fits the viewport of {camera:?}");
+///         }
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct PixelFullscreen<'w, 's> {
+    winit_windows: NonSend<'w, WinitWindows>,
+    windows: Query<'w, 's, &'static mut Window>,
+    primary_window: Query<'w, 's, Entity, With<PrimaryWindow>>,
+    cameras: Query<'w, 's, (&'static PixelCamera, &'static PixelViewportReferences)>,
+    viewport_cameras: Query<'w, 's, &'static Camera, With<ViewportCamera>>,
+}
+
+impl PixelFullscreen<'_, '_> {
+    /// Returns the physical resolution of the video mode that shows the viewport of the camera
+    /// at the largest integer scale, on the monitor that the camera's window is on.
+    ///
+    /// The size of a [`ViewportSize::Fixed`] viewport is used, for other sizes (which change
+    /// with the window) the current size of the viewport. Among the video modes with the same
+    /// scale, the one with the least unused space and then the highest refresh rate is picked.
+    ///
+    /// Returns `None` if the camera isn't initialized, doesn't render to a window, the monitor
+    /// is unknown or the viewport is larger than every video mode.
+    pub fn video_mode(&self, camera: Entity) -> Option<UVec2> {
+        let (pixel_camera, viewport) = self.cameras.get(camera).ok()?;
+        let window = viewport_window(
+            viewport,
+            &self.viewport_cameras,
+            self.primary_window.get_single().ok(),
+        )?;
+        let monitor = self.winit_windows.get_window(window)?.current_monitor()?;
+
+        let viewport_size = match pixel_camera.viewport_size {
+            ViewportSize::Fixed { width, height, .. } => UVec2::new(width, height),
+            _ => {
+                let margin = if pixel_camera.smoothing { 2 } else { 0 };
+                UVec2::new(viewport.size.width, viewport.size.height)
+                    .saturating_sub(UVec2::splat(margin))
+            }
+        };
+
+        best_integer_mode(
+            monitor.video_modes().map(|mode| {
+                let size = mode.size();
+                (
+                    UVec2::new(size.width, size.height),
+                    mode.refresh_rate_millihertz(),
+                )
+            }),
+            viewport_size,
+        )
+    }
+
+    /// Puts the camera's window into exclusive fullscreen with the [`PixelFullscreen::video_mode`]
+    /// of the camera, and returns the resolution of that video mode.
+    ///
+    /// Use a [`FitMode`](crate::viewport::FitMode) with [`ViewportSize::Fixed`] to
+    /// show the viewport without stretching it on video modes with another aspect ratio.
+    pub fn enter(&mut self, camera: Entity) -> Option<UVec2> {
+        let resolution = self.video_mode(camera)?;
+        let (_, viewport) = self.cameras.get(camera).ok()?;
+        let window = viewport_window(
+            viewport,
+            &self.viewport_cameras,
+            self.primary_window.get_single().ok(),
+        )?;
+        let mut window = self.windows.get_mut(window).ok()?;
+
+        // Bevy picks the video mode closest to the window's size.
+        window
+            .resolution
+            .set(resolution.x as f32, resolution.y as f32);
+        window.mode = WindowMode::SizedFullscreen;
+
+        Some(resolution)
+    }
+}
+
+/// Picks the video mode (a resolution and refresh rate) that fits `viewport_size` at the largest
+/// integer scale, then with the least unused space, then with the highest refresh rate.
+fn best_integer_mode(
+    modes: impl IntoIterator<Item = (UVec2, u32)>,
+    viewport_size: UVec2,
+) -> Option<UVec2> {
+    if viewport_size.x == 0 || viewport_size.y == 0 {
+        return None;
+    }
+
+    modes
+        .into_iter()
+        .filter_map(|(size, refresh_rate)| {
+            let scale = (size / viewport_size).min_element();
+            if scale == 0 {
+                return None;
+            }
+            let used = viewport_size * scale;
+            let unused = size.x as u64 * size.y as u64 - used.x as u64 * used.y as u64;
+            Some((scale, std::cmp::Reverse(unused), refresh_rate, size))
+        })
+        .max_by_key(|&(scale, unused, refresh_rate, _)| (scale, unused, refresh_rate))
+        .map(|(.., size)| size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn largest_integer_scale_wins() {
+        let modes = [
+            (UVec2::new(1280, 720), 60),
+            (UVec2::new(1920, 1080), 60),
+            (UVec2::new(2560, 1440), 60),
+        ];
+        assert_eq!(
+            best_integer_mode(modes, UVec2::new(320, 180)),
+            Some(UVec2::new(2560, 1440))
+        );
+    }
+
+    #[test]
+    fn least_unused_space_breaks_ties() {
+        // Both fit 320×180 three times, 1920×1080 wastes less of the screen.
+        let modes = [(UVec2::new(1920, 1200), 60), (UVec2::new(1920, 1080), 60)];
+        assert_eq!(
+            best_integer_mode(modes, UVec2::new(320, 180)),
+            Some(UVec2::new(1920, 1080))
+        );
+    }
+
+    #[test]
+    fn refresh_rate_never_beats_scale() {
+        let modes = [(UVec2::new(1280, 720), 240), (UVec2::new(1920, 1080), 30)];
+        assert_eq!(
+            best_integer_mode(modes, UVec2::new(320, 180)),
+            Some(UVec2::new(1920, 1080))
+        );
+    }
+
+    #[test]
+    fn no_mode_fits() {
+        let modes = [(UVec2::new(640, 480), 60), (UVec2::new(800, 600), 60)];
+        assert_eq!(best_integer_mode(modes, UVec2::new(1024, 768)), None);
+        assert_eq!(best_integer_mode([], UVec2::new(320, 180)), None);
+        // A mode that fits only on one axis doesn't fit.
+        let modes = [(UVec2::new(3840, 160), 60)];
+        assert_eq!(best_integer_mode(modes, UVec2::new(320, 180)), None);
+    }
+
+    #[test]
+    fn empty_viewport() {
+        let modes = [(UVec2::new(1920, 1080), 60)];
+        assert_eq!(best_integer_mode(modes, UVec2::ZERO), None);
+        assert_eq!(best_integer_mode(modes, UVec2::new(320, 0)), None);
+        assert_eq!(best_integer_mode(modes, UVec2::new(0, 180)), None);
+    }
+
+    #[test]
+    fn empty_mode() {
+        let modes = [(UVec2::ZERO, 60), (UVec2::new(1280, 720), 60)];
+        assert_eq!(
+            best_integer_mode(modes, UVec2::new(320, 180)),
+            Some(UVec2::new(1280, 720))
+        );
+    }
+}
@@ -0,0 +1,192 @@
+//! Drawing Bevy's gizmos with a [`PixelCamera`].
+//!
+//! Gizmos are drawn by every camera that shares their render layers. Unless those match the
+//! world's layers, gizmos either don't show up at all or end up on the viewport camera, where
+//! they're drawn in the viewport's coordinates instead of the world's. Add [`PixelGizmos`] to
+//! a [`PixelCamera`] to choose how the default gizmos are drawn, and [`PixelGizmoGroupPlugin`]
+//! to draw the gizmos of other groups (e.g. a physics crate's debug rendering) the same way.
+
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+use bevy::render::camera::ClearColorConfig;
+use bevy::render::view::RenderLayers;
+
+use crate::components::{PixelCamera, PixelViewportReferences, ViewportCamera};
+use crate::high_res::sync_overlay_camera;
+
+/// Draws the gizmos of the config group `T` like the default gizmos, as picked by
+/// [`PixelGizmos`], e.g. the debug rendering of a physics crate.
+///
+/// The group has to be added with `init_gizmo_group`, usually by the crate it belongs to.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::gizmos::PixelGizmoGroupPlugin;
+/// #[derive(Default, Reflect, GizmoConfigGroup)]
+/// struct ColliderGizmos;
+///
+/// App::new()
+///     .init_gizmo_group::<ColliderGizmos>()
+///     .add_plugins(PixelGizmoGroupPlugin::<ColliderGizmos>::default());
+/// ```
+pub struct PixelGizmoGroupPlugin<T>(PhantomData<T>);
+
+impl<T> Default for PixelGizmoGroupPlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: GizmoConfigGroup> Plugin for PixelGizmoGroupPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            route_gizmos::<T>.after(crate::CameraSystems::Initialization),
+        );
+    }
+}
+
+/// How the default gizmos are drawn with a [`PixelCamera`].
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub enum PixelGizmos {
+    /// Draw the gizmos into the low resolution target, so they're pixelated along with the world.
+    Pixelated {
+        /// The width of lines, in texels.
+        line_width: f32,
+    },
+    /// Draw the gizmos at native resolution on top of the viewport, lined up with the world.
+    ///
+    /// An extra camera that follows the viewport draws the gizmos, on top of the
+    /// [`HighRes`](crate::high_res::HighRes) entities.
+    Native {
+        /// The render layer of the gizmos. Nothing else should be on it.
+        layer: RenderLayers,
+    },
+}
+
+impl Default for PixelGizmos {
+    fn default() -> Self {
+        Self::Pixelated { line_width: 1.0 }
+    }
+}
+
+/// The camera that draws [`PixelGizmos::Native`] gizmos for a [`PixelCamera`].
+#[derive(Component)]
+pub(crate) struct GizmoOverlay(Entity);
+/// Marks an overlay camera, and points back to its [`PixelCamera`].
+#[derive(Component)]
+pub(crate) struct GizmoOverlayCamera(Entity);
+
+/// Moves the gizmos of the group `T` onto the render layers picked by [`PixelGizmos`].
+#[allow(clippy::type_complexity)]
+pub(crate) fn route_gizmos<T: GizmoConfigGroup>(
+    cameras: Query<
+        (&PixelGizmos, Option<&RenderLayers>),
+        (
+            With<PixelCamera>,
+            Or<(Changed<PixelGizmos>, Changed<RenderLayers>)>,
+        ),
+    >,
+    mut config_store: ResMut<GizmoConfigStore>,
+) {
+    let Some((gizmos, world_layer)) = cameras.iter().last() else {
+        return;
+    };
+    let (config, _) = config_store.config_mut::<T>();
+
+    match gizmos {
+        PixelGizmos::Pixelated { line_width } => {
+            config.render_layers = world_layer.copied().unwrap_or_default();
+            config.line_width = *line_width;
+        }
+        PixelGizmos::Native { layer } => {
+            config.render_layers = *layer;
+        }
+    }
+}
+
+/// Spawns and despawns the overlay cameras of [`PixelGizmos::Native`].
+#[allow(clippy::type_complexity)]
+pub(crate) fn manage_gizmo_overlays(
+    cameras: Query<
+        (Entity, Option<Ref<PixelGizmos>>, Option<&GizmoOverlay>),
+        (With<PixelCamera>, With<PixelViewportReferences>),
+    >,
+    overlays: Query<(Entity, &GizmoOverlayCamera)>,
+    pixel_cameras: Query<(), With<PixelCamera>>,
+    mut commands: Commands,
+) {
+    for (entity, gizmos, overlay) in &cameras {
+        let changed = gizmos.as_ref().is_some_and(|gizmos| gizmos.is_changed());
+        match (gizmos.as_deref(), overlay) {
+            (Some(PixelGizmos::Native { layer }), None) => {
+                let overlay = commands
+                    .spawn((
+                        Camera2dBundle {
+                            camera: Camera {
+                                clear_color: ClearColorConfig::None,
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        GizmoOverlayCamera(entity),
+                        *layer,
+                    ))
+                    .id();
+                commands.entity(entity).insert(GizmoOverlay(overlay));
+            }
+            (Some(PixelGizmos::Native { layer }), Some(GizmoOverlay(overlay))) => {
+                if changed {
+                    commands.entity(*overlay).insert(*layer);
+                }
+            }
+            (_, Some(GizmoOverlay(overlay))) => {
+                commands.entity(*overlay).despawn();
+                commands.entity(entity).remove::<GizmoOverlay>();
+            }
+            (_, None) => {}
+        }
+    }
+
+    // Overlays of pixel cameras that were despawned.
+    for (overlay, GizmoOverlayCamera(owner)) in &overlays {
+        if !pixel_cameras.contains(*owner) {
+            commands.entity(overlay).despawn();
+        }
+    }
+}
+
+/// Lines the overlay cameras up with the viewport, so that they map the world onto the window
+/// just like the viewport does, at native resolution.
+#[allow(clippy::type_complexity)]
+pub(crate) fn sync_gizmo_overlays(
+    cameras: Query<
+        (
+            &PixelCamera,
+            &OrthographicProjection,
+            &PixelViewportReferences,
+            &GizmoOverlay,
+        ),
+        Without<GizmoOverlayCamera>,
+    >,
+    viewport_cameras: Query<
+        (&Camera, &OrthographicProjection, &Transform),
+        (With<ViewportCamera>, Without<GizmoOverlayCamera>),
+    >,
+    mut overlays: Query<
+        (&mut Camera, &mut OrthographicProjection, &mut Transform),
+        With<GizmoOverlayCamera>,
+    >,
+) {
+    for (pixel_camera, projection, viewport, GizmoOverlay(overlay)) in &cameras {
+        let (Ok(viewport_camera), Ok(overlay)) = (
+            viewport_cameras.get(viewport.camera),
+            overlays.get_mut(*overlay),
+        ) else {
+            continue;
+        };
+        // Right after the overlay of `HighResOverlay`, so the gizmos are drawn on top of it.
+        sync_overlay_camera(pixel_camera, projection, viewport_camera, overlay, 2);
+    }
+}
@@ -0,0 +1,145 @@
+//! Presets for handheld PCs like the Steam Deck.
+//!
+//! Handhelds have a 1280×800 or 1280×720 screen, which only divides evenly into a few
+//! viewport sizes. [`PixelHandheldPlugin`] detects them and switches every [`PixelCamera`]
+//! to a viewport that scales to the screen by a whole number.
+
+use bevy::prelude::*;
+use bevy::render::camera::ClearColorConfig;
+use bevy::window::{PrimaryWindow, WindowMode};
+
+use crate::components::PixelCamera;
+use crate::viewport::{FitMode, ViewportSize};
+
+/// Detects handhelds and gives every [`PixelCamera`] the [`Handheld::viewport_size`] of the
+/// detected one, with the [`PixelHandheldPlugin::scale`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// App::new().add_plugins(PixelHandheldPlugin::default());
+/// ```
+///
+/// The detected handheld is stored in the [`DetectedHandheld`] resource. Viewport sizes that
+/// were changed are kept when the handheld is no longer detected (e.g. when it's docked).
+pub struct PixelHandheldPlugin {
+    /// How many screen pixels a texel covers on the handheld, see [`Handheld::RECOMMENDED_SCALES`].
+    pub scale: u32,
+}
+
+impl Default for PixelHandheldPlugin {
+    fn default() -> Self {
+        Self { scale: 4 }
+    }
+}
+
+impl Plugin for PixelHandheldPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(HandheldScale(self.scale))
+            .init_resource::<DetectedHandheld>()
+            .add_systems(
+                PostUpdate,
+                (detect_handheld, apply_handheld_preset)
+                    .chain()
+                    .before(crate::CameraSystems::Initialization),
+            );
+    }
+}
+
+/// A handheld PC screen with presets for pixel perfect scaling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Handheld {
+    /// A 1280×800 (16:10) screen, like the one of the Steam Deck.
+    SteamDeck,
+    /// A 1280×720 (16:9) screen, like the one of many other handhelds.
+    Hd,
+}
+
+impl Handheld {
+    /// The scales that divide both handheld resolutions evenly, from sharp to chunky.
+    ///
+    /// A scale of 2 gives a 640×400 (or 640×360) viewport, 4 gives 320×200 (or 320×180)
+    /// and 5 gives 256×160 (or 256×144).
+    pub const RECOMMENDED_SCALES: [u32; 3] = [2, 4, 5];
+
+    /// Returns the physical resolution of the handheld's screen.
+    pub fn resolution(self) -> UVec2 {
+        match self {
+            Handheld::SteamDeck => UVec2::new(1280, 800),
+            Handheld::Hd => UVec2::new(1280, 720),
+        }
+    }
+
+    /// Returns a viewport that fills the handheld's screen with texels of `scale` × `scale`
+    /// pixels, and scales by whole numbers on other screens (e.g. when docked).
+    pub fn viewport_size(self, scale: u32) -> ViewportSize {
+        let size = self.resolution() / scale.max(1);
+
+        ViewportSize::Fixed {
+            width: size.x,
+            height: size.y,
+            fit: FitMode::IntegerFit(ClearColorConfig::Default),
+        }
+    }
+
+    /// Detects the handheld that a window is shown on.
+    ///
+    /// The Steam Deck is detected through the `SteamDeck` environment variable that Steam
+    /// sets in gaming mode. Other handhelds are detected by a fullscreen window with the
+    /// resolution of their screen.
+    pub fn detect(window: &Window) -> Option<Handheld> {
+        if std::env::var("SteamDeck").is_ok_and(|value| value == "1") {
+            return Some(Handheld::SteamDeck);
+        }
+        if window.mode == WindowMode::Windowed {
+            return None;
+        }
+
+        let resolution = UVec2::new(window.physical_width(), window.physical_height());
+        [Handheld::SteamDeck, Handheld::Hd]
+            .into_iter()
+            .find(|handheld| handheld.resolution() == resolution)
+    }
+}
+
+/// The handheld that the primary window is shown on, detected by [`PixelHandheldPlugin`].
+///
+/// Use this to adapt the rest of the game to handhelds, e.g. the UI scale.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DetectedHandheld(pub Option<Handheld>);
+
+/// The [`PixelHandheldPlugin::scale`].
+#[derive(Resource)]
+struct HandheldScale(u32);
+
+/// Detects the handheld again whenever the primary window changes.
+fn detect_handheld(
+    primary_window: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
+    mut detected: ResMut<DetectedHandheld>,
+) {
+    let Ok(window) = primary_window.get_single() else {
+        return;
+    };
+
+    let handheld = Handheld::detect(window);
+    if detected.0 != handheld {
+        detected.0 = handheld;
+    }
+}
+
+/// Applies the viewport of the detected handheld to new cameras, and to all cameras when it changes.
+fn apply_handheld_preset(
+    mut cameras: Query<&mut PixelCamera>,
+    detected: Res<DetectedHandheld>,
+    scale: Res<HandheldScale>,
+) {
+    let Some(handheld) = detected.0 else {
+        return;
+    };
+
+    for mut pixel_camera in &mut cameras {
+        if detected.is_changed() || pixel_camera.is_added() {
+            pixel_camera.viewport_size = handheld.viewport_size(scale.0);
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! Rendering entities at native resolution on top of the pixelated world.
+//!
+//! Mark particles, text or a cursor with [`HighRes`] to move them onto the [`HIGH_RES_LAYER`],
+//! and add [`HighResOverlay`] to a 2D [`PixelCamera`]. The overlay camera follows the viewport,
+//! so the entities line up with the pixelated world but keep their full resolution.
+
+use bevy::prelude::*;
+use bevy::render::camera::{ClearColorConfig, Viewport};
+use bevy::render::view::RenderLayers;
+
+use crate::components::{PixelCamera, PixelViewportReferences, ViewportCamera};
+
+/// The render layer of [`HighRes`] entities.
+///
+/// The plugin never picks it as a [`PixelCamera::viewport_layer`], keep the world of the pixel
+/// cameras off of it too.
+pub const HIGH_RES_LAYER: u8 = RenderLayers::TOTAL_LAYERS as u8 - 1;
+
+/// The orders after a viewport camera's order that are kept free for its overlay cameras:
+/// the [`HighResOverlay`] at `+1` and the native gizmos at `+2`.
+pub(crate) const OVERLAY_ORDERS: isize = 2;
+
+/// Renders an entity at native resolution on top of the viewports of [`PixelCamera`]s with a
+/// [`HighResOverlay`], instead of in their low resolution world.
+///
+/// Puts the entity on the [`HIGH_RES_LAYER`], replacing its [`RenderLayers`]. Removing the
+/// component puts it back on the default layer.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+///     commands.spawn((
+///         Camera2dBundle::default(),
+///         PixelCamera::default(),
+///         HighResOverlay,
+///     ));
+///     // A crisp cursor over the pixel art.
+///     commands.spawn((
+///         SpriteBundle {
+///             texture: asset_server.load("cursor.png"),
+///             ..default()
+///         },
+///         HighRes,
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub struct HighRes;
+
+/// Gives a 2D [`PixelCamera`] an overlay camera that draws the [`HighRes`] entities at native
+/// resolution on top of its viewport, lined up with the world.
+///
+/// The overlay camera renders right after the viewport camera, to the same part of the same
+/// window. It's despawned along with this component or the camera.
+#[derive(Component, Reflect, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub struct HighResOverlay;
+
+/// The overlay camera of a [`PixelCamera`] with a [`HighResOverlay`].
+#[derive(Component)]
+pub(crate) struct HighResCamera(Entity);
+/// Marks an overlay camera, and points back to its [`PixelCamera`].
+#[derive(Component)]
+pub(crate) struct HighResCameraOwner(Entity);
+
+/// Moves [`HighRes`] entities onto the [`HIGH_RES_LAYER`], and back off of it.
+pub(crate) fn apply_high_res_layer(
+    added: Query<Entity, Added<HighRes>>,
+    mut removed: RemovedComponents<HighRes>,
+    entities: Query<(), Without<HighRes>>,
+    mut commands: Commands,
+) {
+    for entity in &added {
+        commands
+            .entity(entity)
+            .insert(RenderLayers::layer(HIGH_RES_LAYER));
+    }
+    for entity in removed.read() {
+        if entities.contains(entity) {
+            commands.entity(entity).remove::<RenderLayers>();
+        }
+    }
+}
+
+/// Spawns and despawns the overlay cameras of [`HighResOverlay`]s.
+#[allow(clippy::type_complexity)]
+pub(crate) fn manage_high_res_overlays(
+    cameras: Query<
+        (Entity, Has<HighResOverlay>, Option<&HighResCamera>),
+        (With<PixelCamera>, With<PixelViewportReferences>),
+    >,
+    overlays: Query<(Entity, &HighResCameraOwner)>,
+    pixel_cameras: Query<(), With<PixelCamera>>,
+    mut commands: Commands,
+) {
+    for (entity, enabled, overlay) in &cameras {
+        match (enabled, overlay) {
+            (true, None) => {
+                let overlay = commands
+                    .spawn((
+                        Camera2dBundle {
+                            camera: Camera {
+                                clear_color: ClearColorConfig::None,
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        HighResCameraOwner(entity),
+                        RenderLayers::layer(HIGH_RES_LAYER),
+                    ))
+                    .id();
+                commands.entity(entity).insert(HighResCamera(overlay));
+            }
+            (false, Some(HighResCamera(overlay))) => {
+                commands.entity(*overlay).despawn();
+                commands.entity(entity).remove::<HighResCamera>();
+            }
+            _ => {}
+        }
+    }
+
+    // Overlays of pixel cameras that were despawned.
+    for (overlay, HighResCameraOwner(owner)) in &overlays {
+        if !pixel_cameras.contains(*owner) {
+            commands.entity(overlay).despawn();
+        }
+    }
+}
+
+/// Lines the overlay cameras up with the viewport.
+#[allow(clippy::type_complexity)]
+pub(crate) fn sync_high_res_overlays(
+    cameras: Query<
+        (
+            &PixelCamera,
+            &OrthographicProjection,
+            &PixelViewportReferences,
+            &HighResCamera,
+        ),
+        Without<HighResCameraOwner>,
+    >,
+    viewport_cameras: Query<
+        (&Camera, &OrthographicProjection, &Transform),
+        (With<ViewportCamera>, Without<HighResCameraOwner>),
+    >,
+    mut overlays: Query<
+        (&mut Camera, &mut OrthographicProjection, &mut Transform),
+        With<HighResCameraOwner>,
+    >,
+) {
+    for (pixel_camera, projection, viewport, HighResCamera(overlay)) in &cameras {
+        let (Ok(viewport_camera), Ok(overlay)) = (
+            viewport_cameras.get(viewport.camera),
+            overlays.get_mut(*overlay),
+        ) else {
+            continue;
+        };
+        sync_overlay_camera(pixel_camera, projection, viewport_camera, overlay, 1);
+    }
+}
+
+/// Makes an overlay camera render right after a viewport camera (`order_offset` orders later),
+/// to the same part of the same window, and map the world onto it just like the viewport does.
+pub(crate) fn sync_overlay_camera(
+    pixel_camera: &PixelCamera,
+    projection: &OrthographicProjection,
+    (viewport_camera, viewport_projection, viewport_transform): (
+        &Camera,
+        &OrthographicProjection,
+        &Transform,
+    ),
+    (mut camera, mut overlay_projection, mut transform): (
+        Mut<Camera>,
+        Mut<OrthographicProjection>,
+        Mut<Transform>,
+    ),
+    order_offset: isize,
+) {
+    let order = viewport_camera.order + order_offset;
+    if camera.is_active != viewport_camera.is_active
+        || camera.order != order
+        || camera.target.normalize(None) != viewport_camera.target.normalize(None)
+        || !same_viewport(camera.viewport.as_ref(), viewport_camera.viewport.as_ref())
+    {
+        camera.is_active = viewport_camera.is_active;
+        camera.order = order;
+        camera.target = viewport_camera.target.clone();
+        camera.viewport = viewport_camera.viewport.clone();
+    }
+
+    // One unit of the viewport camera is one texel, which covers `projection.scale` world units.
+    let new_projection = OrthographicProjection {
+        scale: viewport_projection.scale * projection.scale,
+        ..viewport_projection.clone()
+    };
+    if overlay_projection.reflect_partial_eq(&new_projection) != Some(true) {
+        *overlay_projection = new_projection;
+    }
+
+    // The smoothed viewport shows the world centered on the subpixel position. The viewport
+    // camera is a child of the camera, so its own transform is relative to the camera.
+    let translation =
+        pixel_camera.subpixel_pos + viewport_transform.translation.truncate() * projection.scale;
+    if transform.translation.truncate() != translation {
+        transform.translation.x = translation.x;
+        transform.translation.y = translation.y;
+    }
+}
+
+/// Returns whether two viewports cover the same part of the target.
+pub(crate) fn same_viewport(a: Option<&Viewport>, b: Option<&Viewport>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            a.physical_position == b.physical_position
+                && a.physical_size == b.physical_size
+                && a.depth == b.depth
+        }
+        (a, b) => a.is_none() && b.is_none(),
+    }
+}
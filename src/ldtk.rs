@@ -0,0 +1,91 @@
+//! Camera bounds for [LDtk](https://ldtk.io/) levels.
+//!
+//! This works with any LDtk crate: add [`LdtkLevelBounds`] to the entity of each level, and the
+//! level becomes a [`CameraRoom`]. Cameras with [`PixelCameraRooms`](crate::rooms::PixelCameraRooms)
+//! then stay on the level that their follow target is in, and pan over to the next level when
+//! the target walks into it.
+
+use bevy::prelude::*;
+
+use crate::rooms::CameraRoom;
+use crate::CameraSystems;
+
+/// Keeps a [`CameraRoom`] on every level with [`LdtkLevelBounds`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::ldtk::{LdtkLevelBounds, PixelCameraLdtkPlugin};
+/// fn spawn_level(mut commands: Commands) {
+///     // The `pxWid` and `pxHei` of the level, e.g. from `bevy_ecs_ldtk`'s `LevelEvent::Spawned`.
+///     commands.spawn((
+///         SpatialBundle::from_transform(Transform::from_xyz(256.0, 0.0, 0.0)),
+///         LdtkLevelBounds::new(256, 144),
+///     ));
+/// }
+///
+/// App::new()
+///     .add_plugins(PixelCameraLdtkPlugin)
+///     .add_systems(Startup, spawn_level);
+/// ```
+pub struct PixelCameraLdtkPlugin;
+impl Plugin for PixelCameraLdtkPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<LdtkLevelBounds>()
+            .add_systems(PostUpdate, update_level_rooms.before(CameraSystems::Follow));
+    }
+}
+
+/// The size of an LDtk level in pixels, its `pxWid` and `pxHei`.
+///
+/// Levels span from the origin of their entity to their size, growing up and to the right.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct LdtkLevelBounds {
+    /// The size of the level, in pixels.
+    pub size: UVec2,
+}
+
+impl LdtkLevelBounds {
+    /// Creates the bounds of a level with the given `pxWid` and `pxHei`.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            size: UVec2::new(width, height),
+        }
+    }
+    /// Returns the area of the world covered by the level, for a level entity with the given
+    /// transform.
+    pub fn rect(&self, transform: &GlobalTransform) -> Rect {
+        Rect::from_corners(
+            transform.translation().truncate(),
+            transform
+                .transform_point(self.size.as_vec2().extend(0.0))
+                .truncate(),
+        )
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_level_rooms(
+    mut levels: Query<
+        (
+            Entity,
+            &LdtkLevelBounds,
+            &GlobalTransform,
+            Option<&mut CameraRoom>,
+        ),
+        Or<(Changed<LdtkLevelBounds>, Changed<GlobalTransform>)>,
+    >,
+    mut commands: Commands,
+) {
+    for (entity, bounds, transform, room) in &mut levels {
+        let new_room = CameraRoom(bounds.rect(transform));
+        match room {
+            Some(mut room) => {
+                room.set_if_neq(new_room);
+            }
+            None => {
+                commands.entity(entity).insert(new_room);
+            }
+        }
+    }
+}
@@ -1,11 +1,66 @@
 #![doc = include_str!("../README.md")]
 
 use bevy::prelude::*;
+use bevy::render::camera::CameraUpdateSystem;
+use bevy::render::view::VisibilitySystems;
+use bevy::window::ApplicationLifetime;
 
+#[cfg(feature = "audio")]
+pub mod audio;
+pub mod billboard;
+pub mod bounds;
 pub mod components;
+pub mod composite;
+pub mod controller;
+mod coords;
+#[cfg(feature = "crt")]
+pub mod crt;
+pub mod cursor;
+pub mod dynamic_resolution;
+pub mod error;
+pub mod follow;
+#[cfg(feature = "fullscreen")]
+pub mod fullscreen;
+#[cfg(feature = "gizmos")]
+pub mod gizmos;
+pub mod handheld;
+pub mod high_res;
+pub mod ldtk;
+pub mod material;
+pub mod outline;
+pub mod pancam;
+pub mod parallax;
+pub mod path;
+#[cfg(feature = "perf_ui")]
+pub mod perf_ui;
+pub mod picking;
 pub mod prelude;
+pub mod profile;
+pub mod propagate;
+#[cfg(feature = "ps1")]
+pub mod ps1;
+pub mod readback;
+pub mod renderers;
+pub mod rooms;
+#[cfg(feature = "serialize")]
+pub mod save;
+pub mod shared;
+pub mod state;
 mod systems;
+#[cfg(feature = "text")]
+pub mod text;
+#[cfg(feature = "tiled")]
+pub mod tiled;
+pub mod tilemap;
+pub mod tweening;
+#[cfg(feature = "ui")]
+pub mod ui;
 pub mod viewport;
+pub mod viewport_info;
+pub mod virtual_camera;
+pub mod window_snap;
+pub mod zones;
+pub mod zoom;
 
 /// A [`SystemSet`] for [`PixelCameraPlugin`]'s systems.
 #[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone, Copy)]
@@ -14,7 +69,68 @@ pub enum CameraSystems {
     /// component when it's added to an entity.
     Initialization,
     /// The systems that update the pixel camera's position after every frame.
+    ///
+    /// Contains all of the more specific update sets below.
     Update,
+    /// Updates the viewport camera's projection and the size of the render target
+    /// when the window is resized.
+    ///
+    /// Together with [`CameraSystems::TargetResize`] and [`CameraSystems::Smoothing`], everything
+    /// that depends on the viewport size is updated in the same frame, before Bevy updates the
+    /// cameras, so resizing never shows a frame with mismatched sizes.
+    ViewportSize,
+    /// Resizes the render target images.
+    ///
+    /// This is the only set that needs mutable access to [`Assets<Image>`],
+    /// and it only runs when a render target actually changed size.
+    TargetResize,
+    /// Moves the camera's subpixel position toward its [`PixelCameraFollow`](follow::PixelCameraFollow) target
+    /// or along its [`CameraPath`](path::CameraPath), and its bounds to the [`CameraRoom`](rooms::CameraRoom) that the target is in.
+    Follow,
+    /// Clamps the camera's subpixel position to its [`CameraBounds`](bounds::CameraBounds).
+    Bounds,
+    /// Smooths the viewport sprite by the subpixel remainder of the camera's position.
+    Smoothing,
+    /// Snaps the camera's [`Transform`] to its subpixel position.
+    Positioning,
+    /// Expands the camera's culling frustum by [`PixelCamera::culling_margin`](components::PixelCamera::culling_margin).
+    ///
+    /// Runs between [`VisibilitySystems::UpdateOrthographicFrusta`] and [`VisibilitySystems::CheckVisibility`].
+    Culling,
+}
+
+/// Global configuration for [`PixelCameraPlugin`].
+///
+/// Insert this resource to change how pixel cameras are set up.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PixelCameraConfig {
+    /// When a [`PixelCamera`](components::PixelCamera)'s viewport layer conflicts with the
+    /// render layers of the world, pick a free layer for the viewport automatically
+    /// (and log a warning) instead of refusing to initialize the camera.
+    pub auto_resolve_layers: bool,
+    /// When a camera renders later or at the same time as its viewport camera,
+    /// render the viewport camera right after it (and log a warning) instead of
+    /// refusing to initialize the camera.
+    pub auto_fix_order: bool,
+    /// How many render target images of removed or despawned [`PixelCamera`](components::PixelCamera)s
+    /// are kept for reuse by new cameras with the same target format and usage, e.g. when a level
+    /// is restarted.
+    ///
+    /// Defaults to 0, which frees the images as soon as their camera is gone. Images that don't
+    /// keep a CPU copy (see [`PixelCamera::target_usage`](components::PixelCamera::target_usage))
+    /// are never reused.
+    pub target_image_pool: usize,
+    /// How the scale factor of a window affects the size of its viewports.
+    ///
+    /// On the web the scale factor is the canvas's `devicePixelRatio`, which is often fractional
+    /// (e.g. 1.25 or 1.5) and changes with the browser's zoom, which makes
+    /// [`ViewportSize::PixelFixed`](viewport::ViewportSize::PixelFixed) blurry. The viewport is
+    /// resized whenever the scale factor changes.
+    ///
+    /// Defaults to [`DpiPolicy::Logical`](viewport::DpiPolicy::Logical) on every platform. Use
+    /// [`DpiPolicy::Rounded`](viewport::DpiPolicy::Rounded) for crisp web builds, or
+    /// [`DpiPolicy::Physical`](viewport::DpiPolicy::Physical) for sizes in device pixels.
+    pub dpi_policy: viewport::DpiPolicy,
 }
 
 /// The [`PixelCameraPlugin`] handles initialization and updates of the [`PixelCamera`](components::PixelCamera).
@@ -25,13 +141,253 @@ impl Plugin for PixelCameraPlugin {
     fn build(&self, app: &mut App) {
         use systems::*;
 
-        app.insert_resource(Msaa::Off).add_systems(
+        app.add_plugins((composite::DirectCompositePlugin, readback::ReadbackPlugin));
+
+        app.insert_resource(Msaa::Off)
+            .add_event::<error::PixelCameraError>()
+            .init_resource::<PixelCameraConfig>()
+            .init_resource::<TargetImagePool>()
+            .init_resource::<profile::ViewportProfiles>()
+            .init_resource::<cursor::PointerCaptured>()
+            .register_type::<bounds::CameraBounds>()
+            .register_type::<follow::PixelCameraFollow>()
+            .register_type::<follow::LookAhead>()
+            .register_type::<follow::PixelCameraGroupFollow>()
+            .register_type::<rooms::CameraRoom>()
+            .register_type::<rooms::RoomTransition>()
+            .register_type::<virtual_camera::VirtualPixelCamera>()
+            .register_type::<zones::CameraZone>()
+            .register_type::<path::CameraPath>()
+            .register_type::<components::PixelCamera>()
+            .register_type::<components::PixelCamera3d>()
+            .register_type::<components::PixelBypass>()
+            .register_type::<components::PixelFreeze>()
+            .register_type::<components::ViewportTarget>()
+            .register_type::<components::ViewportRect>()
+            .register_type::<components::CustomViewport>()
+            .register_type::<components::PixelViewport>()
+            .register_type::<components::ViewportCamera>()
+            .register_type::<components::ViewportMirror>()
+            .register_type::<dynamic_resolution::DynamicResolution>()
+            .register_type::<high_res::HighRes>()
+            .register_type::<high_res::HighResOverlay>()
+            .register_type::<shared::SharedPixelTarget>()
+            .register_type::<viewport::ViewportSize>()
+            .register_type::<viewport::FitMode>()
+            .register_type::<viewport::DpiPolicy>()
+            .register_type::<window_snap::SnapWindowSize>()
+            .register_type::<zoom::PixelZoom>()
+            .configure_sets(
+                PostUpdate,
+                (
+                    (
+                        CameraSystems::ViewportSize,
+                        CameraSystems::TargetResize,
+                        CameraSystems::Follow,
+                        CameraSystems::Bounds,
+                        CameraSystems::Smoothing,
+                        CameraSystems::Positioning,
+                        CameraSystems::Culling,
+                    )
+                        .in_set(CameraSystems::Update),
+                    CameraSystems::Culling
+                        .after(VisibilitySystems::UpdateOrthographicFrusta)
+                        .before(VisibilitySystems::CheckVisibility),
+                    // Initialize (and repair) the viewports before updating them,
+                    // so that the update systems see the spawned entities.
+                    CameraSystems::Initialization.before(CameraSystems::Update),
+                    CameraSystems::TargetResize.after(CameraSystems::ViewportSize),
+                    // Bevy updates the projections of the cameras for their new (target) size
+                    // in the same frame, e.g. when toggling fullscreen.
+                    (CameraSystems::ViewportSize, CameraSystems::TargetResize)
+                        .before(CameraUpdateSystem),
+                    // Group follows fit the targets into the visible area of the projection,
+                    // which Bevy updates for a new zoom in `CameraUpdateSystem`.
+                    CameraSystems::Follow
+                        .after(CameraSystems::ViewportSize)
+                        .after(CameraUpdateSystem)
+                        .before(CameraSystems::Bounds),
+                    CameraSystems::Bounds
+                        .after(CameraSystems::ViewportSize)
+                        .before(CameraSystems::Smoothing)
+                        .before(CameraSystems::Positioning),
+                    // The sprite rect is sized for the resized target.
+                    CameraSystems::Smoothing
+                        .after(CameraSystems::ViewportSize)
+                        .after(CameraSystems::TargetResize),
+                ),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    (
+                        init_camera.run_if(cameras_to_initialize),
+                        link_custom_viewports.run_if(custom_viewports_unlinked),
+                        repair_viewport,
+                        revalidate_layers,
+                        revalidate_order,
+                        cleanup_removed_cameras,
+                    )
+                        .in_set(CameraSystems::Initialization),
+                    // Release the images of despawned cameras before new cameras look for one,
+                    // and track the images of new cameras once they're initialized.
+                    release_target_images
+                        .in_set(CameraSystems::Initialization)
+                        .before(init_camera),
+                    // Pick a supported target format before any targets are created.
+                    fallback_target_formats
+                        .in_set(CameraSystems::Initialization)
+                        .before(init_camera)
+                        .before(composite::init_direct_composites),
+                    track_target_images
+                        .in_set(CameraSystems::Initialization)
+                        .after(init_camera),
+                    // Snap the window first, so that the viewport is sized for the snapped window.
+                    window_snap::snap_window_sizes
+                        .in_set(CameraSystems::ViewportSize)
+                        .before(update_viewport_size),
+                    // Switch to the zoom of a finished transition before sizing the viewport for it.
+                    zoom::animate_zoom
+                        .in_set(CameraSystems::ViewportSize)
+                        .before(update_viewport_size)
+                        .before(bevy::transform::TransformSystem::TransformPropagate),
+                    (
+                        apply_viewport_targets,
+                        apply_bypass,
+                        apply_sub_viewports,
+                        apply_viewport_rects,
+                        handle_target_changes,
+                        handle_closed_windows,
+                        sync_active_state,
+                        thaw_cameras,
+                        refresh_resumed_cameras,
+                        refresh_rescaled_cameras,
+                        dynamic_resolution::update_dynamic_resolution,
+                        update_viewport_size,
+                    )
+                        .chain()
+                        .in_set(CameraSystems::ViewportSize),
+                    (
+                        recreate_resumed_targets.run_if(on_event::<ApplicationLifetime>()),
+                        recreate_removed_targets.run_if(target_images_removed),
+                        resize_target_image.run_if(target_resized),
+                    )
+                        .chain()
+                        .in_set(CameraSystems::TargetResize),
+                    // Zones apply their overrides on top of everything else that moves the camera,
+                    // and virtual cameras take over from the follow modes while one is live.
+                    (
+                        zones::unapply_zones,
+                        (
+                            follow::follow_targets,
+                            follow::follow_groups,
+                            path::follow_paths,
+                        ),
+                        virtual_camera::drive_brains,
+                        (rooms::enter_rooms, rooms::transition_room_bounds).chain(),
+                        zones::apply_zones,
+                    )
+                        .chain()
+                        .in_set(CameraSystems::Follow),
+                    bounds::clamp_to_bounds.in_set(CameraSystems::Bounds),
+                    (smooth_camera, sync_viewport_mirrors)
+                        .chain()
+                        .in_set(CameraSystems::Smoothing),
+                    set_camera_position.in_set(CameraSystems::Positioning),
+                    snap_3d_cameras
+                        .in_set(CameraSystems::Positioning)
+                        .before(CameraSystems::Smoothing)
+                        .before(bevy::transform::TransformSystem::TransformPropagate),
+                    expand_culling_frustum.in_set(CameraSystems::Culling),
+                    shared::sync_shared_targets
+                        .in_set(CameraSystems::Update)
+                        .after(CameraSystems::TargetResize)
+                        .after(CameraSystems::Positioning)
+                        .before(bevy::transform::TransformSystem::TransformPropagate),
+                    billboard::snap_billboards
+                        .after(bevy::transform::TransformSystem::TransformPropagate)
+                        .before(VisibilitySystems::CheckVisibility),
+                    (
+                        high_res::apply_high_res_layer.before(VisibilitySystems::CheckVisibility),
+                        high_res::manage_high_res_overlays.after(CameraSystems::Initialization),
+                        high_res::sync_high_res_overlays
+                            .after(CameraSystems::Smoothing)
+                            .in_set(CameraSystems::Update),
+                    ),
+                ),
+            );
+
+        #[cfg(feature = "audio")]
+        app.add_systems(
             PostUpdate,
             (
-                init_camera.in_set(CameraSystems::Initialization),
-                (update_viewport_size, smooth_camera, set_camera_position)
+                audio::spawn_smooth_listeners.after(CameraSystems::Initialization),
+                audio::sync_smooth_listeners
+                    .after(CameraSystems::Positioning)
+                    .before(bevy::transform::TransformSystem::TransformPropagate),
+            ),
+        );
+
+        #[cfg(feature = "gizmos")]
+        app.add_systems(
+            PostUpdate,
+            (
+                gizmos::route_gizmos::<DefaultGizmoConfigGroup>
+                    .after(CameraSystems::Initialization),
+                gizmos::manage_gizmo_overlays.after(CameraSystems::Initialization),
+                gizmos::sync_gizmo_overlays
+                    .after(CameraSystems::Smoothing)
                     .in_set(CameraSystems::Update),
             ),
         );
+
+        #[cfg(feature = "serialize")]
+        app.register_type::<save::PixelCameraSnapshot>()
+            .add_systems(
+                PostUpdate,
+                save::sync_snapshots
+                    .after(CameraSystems::Initialization)
+                    .before(CameraSystems::Update),
+            );
+
+        #[cfg(feature = "text")]
+        app.add_systems(
+            PostUpdate,
+            (
+                text::scale_pixel_text.before(bevy::text::update_text2d_layout),
+                text::resnap_changed_text
+                    .after(bevy::text::update_text2d_layout)
+                    .before(bevy::transform::TransformSystem::TransformPropagate),
+                text::snap_pixel_text
+                    .after(bevy::transform::TransformSystem::TransformPropagate)
+                    .before(VisibilitySystems::CheckVisibility),
+            ),
+        );
+
+        #[cfg(feature = "ui")]
+        app.register_type::<ui::PixelInteraction>()
+            .register_type::<ui::PixelUiViewport>()
+            .register_type::<ui::PixelUi>()
+            .add_systems(
+                PreUpdate,
+                ui::pixel_ui_interaction.after(bevy::ui::UiSystem::Focus),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    ui::sync_ui_scale
+                        .after(CameraSystems::ViewportSize)
+                        .before(bevy::ui::UiSystem::Layout),
+                    // Sized for the layout of the last frame, since the layout is only
+                    // calculated once the viewport is sized.
+                    ui::update_ui_viewports
+                        .after(CameraSystems::Initialization)
+                        .before(CameraSystems::ViewportSize),
+                    ui::manage_pixel_ui.after(CameraSystems::Initialization),
+                    ui::sync_pixel_ui
+                        .in_set(CameraSystems::Update)
+                        .after(CameraSystems::TargetResize),
+                ),
+            );
     }
 }
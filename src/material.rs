@@ -0,0 +1,255 @@
+//! Showing the viewport of a [`PixelCamera`] with a custom [`Material2d`], e.g. for post
+//! processing the upscaled image.
+
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
+use bevy::sprite::{Material2d, Material2dPlugin, MaterialMesh2dBundle, Mesh2dHandle};
+
+use crate::components::{PixelCamera, PixelViewport, PixelViewportReferences};
+use crate::CameraSystems;
+
+/// A [`Material2d`] that the viewport of a [`PixelCamera`] can be shown with, see
+/// [`PixelViewportMaterial`].
+pub trait ViewportMaterial: Material2d {
+    /// Updates the material for the current state of the viewport.
+    ///
+    /// Called whenever the image, its size or the smoothing offset changes.
+    fn set_viewport(&mut self, viewport: &ViewportMaterialInput);
+}
+
+/// What a [`ViewportMaterial`] needs to show the viewport.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewportMaterialInput {
+    /// The image that the camera renders to.
+    pub image: Handle<Image>,
+    /// The size of the image, in texels. Includes the smoothing margin.
+    pub target_size: Vec2,
+    /// The part of the image to show, in UV coordinates.
+    pub uv_rect: Rect,
+    /// How far the visible part of the image is moved for the smoothing, in texels.
+    pub subpixel_offset: Vec2,
+}
+
+/// Adds [`PixelViewportMaterial<M>`] for the [`ViewportMaterial`] `M`.
+///
+/// Also adds the [`Material2dPlugin`] of `M` if it hasn't been added yet.
+pub struct ViewportMaterialPlugin<M>(PhantomData<M>);
+
+impl<M> Default for ViewportMaterialPlugin<M> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<M: ViewportMaterial> Plugin for ViewportMaterialPlugin<M>
+where
+    M::Data: PartialEq + Eq + Hash + Clone,
+{
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<Material2dPlugin<M>>() {
+            app.add_plugins(Material2dPlugin::<M>::default());
+        }
+
+        app.add_systems(
+            PostUpdate,
+            (
+                manage_viewport_materials::<M>
+                    .after(CameraSystems::Initialization)
+                    .before(CameraSystems::Update),
+                sync_viewport_materials::<M>
+                    .in_set(CameraSystems::Update)
+                    .after(CameraSystems::Smoothing),
+            ),
+        );
+    }
+}
+
+/// Shows the viewport of a [`PixelCamera`] on a mesh with the material `M` instead of on the
+/// plain viewport sprite, requires the [`ViewportMaterialPlugin`] of `M`.
+///
+/// The plugin keeps feeding the image and the smoothing to the material through
+/// [`ViewportMaterial::set_viewport`]. The viewport sprite is kept for everything that maps
+/// positions through it, but it's made transparent while the material is shown.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy::render::render_resource::{AsBindGroup, ShaderRef};
+/// # use bevy::sprite::Material2d;
+/// # use bevy_smooth_pixel_camera::material::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// #[derive(Asset, TypePath, AsBindGroup, Clone)]
+/// struct Scanlines {
+///     #[texture(0)]
+///     #[sampler(1)]
+///     image: Handle<Image>,
+///     #[uniform(2)]
+///     uv_rect: Vec4,
+/// }
+///
+/// impl Material2d for Scanlines {
+///     fn fragment_shader() -> ShaderRef {
+///         "scanlines.wgsl".into()
+///     }
+/// }
+///
+/// impl ViewportMaterial for Scanlines {
+///     fn set_viewport(&mut self, viewport: &ViewportMaterialInput) {
+///         self.image = viewport.image.clone();
+///         self.uv_rect = Vec4::new(
+///             viewport.uv_rect.min.x,
+///             viewport.uv_rect.min.y,
+///             viewport.uv_rect.max.x,
+///             viewport.uv_rect.max.y,
+///         );
+///     }
+/// }
+///
+/// fn setup(mut commands: Commands, mut materials: ResMut<Assets<Scanlines>>) {
+///     let material = materials.add(Scanlines {
+///         image: Handle::default(),
+///         uv_rect: Vec4::ZERO,
+///     });
+///     commands.spawn((
+///         Camera2dBundle::default(),
+///         PixelCamera::default(),
+///         PixelViewportMaterial(material),
+///     ));
+/// }
+///
+/// fn build(app: &mut App) {
+///     app.add_plugins(ViewportMaterialPlugin::<Scanlines>::default())
+///         .add_systems(Startup, setup);
+/// }
+/// ```
+#[derive(Component, Debug, Clone)]
+pub struct PixelViewportMaterial<M: ViewportMaterial>(pub Handle<M>);
+
+/// The mesh that shows the viewport of a [`PixelCamera`] with the material `M`.
+#[derive(Component)]
+pub(crate) struct ViewportMaterialMesh<M> {
+    mesh: Entity,
+    /// The input that was last fed to the material.
+    input: Option<ViewportMaterialInput>,
+    marker: PhantomData<fn() -> M>,
+}
+
+/// Spawns and despawns the meshes of [`PixelViewportMaterial`]s.
+#[allow(clippy::type_complexity)]
+fn manage_viewport_materials<M: ViewportMaterial>(
+    cameras: Query<(
+        Entity,
+        &PixelCamera,
+        &PixelViewportReferences,
+        Option<Ref<PixelViewportMaterial<M>>>,
+        Option<&ViewportMaterialMesh<M>>,
+    )>,
+    meshes: Query<(), With<Handle<M>>>,
+    mut sprites: Query<&mut Sprite, With<PixelViewport>>,
+    mut mesh_assets: ResMut<Assets<Mesh>>,
+    mut commands: Commands,
+) {
+    for (entity, pixel_camera, viewport, material, mesh) in &cameras {
+        // The mesh is despawned along with a respawned viewport sprite.
+        let mesh = mesh.filter(|mesh| meshes.contains(mesh.mesh));
+        match (material, mesh) {
+            (Some(material), None) => {
+                let mesh = commands
+                    .spawn((
+                        MaterialMesh2dBundle::<M> {
+                            mesh: Mesh2dHandle(mesh_assets.add(Rectangle::new(1.0, 1.0))),
+                            material: material.0.clone(),
+                            ..default()
+                        },
+                        pixel_camera.viewport_layer,
+                    ))
+                    .set_parent(viewport.sprite)
+                    .id();
+                commands.entity(entity).insert(ViewportMaterialMesh::<M> {
+                    mesh,
+                    input: None,
+                    marker: PhantomData,
+                });
+                if let Ok(mut sprite) = sprites.get_mut(viewport.sprite) {
+                    sprite.color = Color::NONE;
+                }
+            }
+            (Some(material), Some(mesh)) => {
+                if material.is_changed() {
+                    commands.entity(mesh.mesh).insert(material.0.clone());
+                    // Feed the new material too.
+                    commands.entity(entity).insert(ViewportMaterialMesh::<M> {
+                        mesh: mesh.mesh,
+                        input: None,
+                        marker: PhantomData,
+                    });
+                }
+            }
+            (None, Some(mesh)) => {
+                commands.entity(mesh.mesh).despawn();
+                commands.entity(entity).remove::<ViewportMaterialMesh<M>>();
+                if let Ok(mut sprite) = sprites.get_mut(viewport.sprite) {
+                    sprite.color = Color::WHITE;
+                }
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+/// Feeds the viewport to the materials, and sizes their meshes like the viewport sprite.
+#[allow(clippy::type_complexity)]
+fn sync_viewport_materials<M: ViewportMaterial>(
+    mut cameras: Query<(
+        &PixelCamera,
+        &PixelViewportReferences,
+        &PixelViewportMaterial<M>,
+        &mut ViewportMaterialMesh<M>,
+    )>,
+    sprites: Query<&Sprite, With<PixelViewport>>,
+    mut meshes: Query<(&mut Transform, &mut RenderLayers), With<Handle<M>>>,
+    mut materials: ResMut<Assets<M>>,
+) {
+    for (pixel_camera, viewport, PixelViewportMaterial(material), mut mesh) in &mut cameras {
+        let Ok(sprite) = sprites.get(viewport.sprite) else {
+            continue;
+        };
+        let target_size = Vec2::new(viewport.size.width as f32, viewport.size.height as f32);
+        let rect = sprite.rect.unwrap_or(Rect {
+            min: Vec2::ZERO,
+            max: target_size,
+        });
+        let subpixel_offset = if pixel_camera.smoothing {
+            rect.min - Vec2::ONE
+        } else {
+            Vec2::ZERO
+        };
+        let input = ViewportMaterialInput {
+            image: viewport.image.clone(),
+            target_size,
+            uv_rect: Rect {
+                min: rect.min / target_size,
+                max: rect.max / target_size,
+            },
+            subpixel_offset,
+        };
+
+        if let Ok((mut transform, mut layer)) = meshes.get_mut(mesh.mesh) {
+            // The sprite is as large as its rect.
+            let scale = rect.size().extend(1.0);
+            if transform.scale != scale {
+                transform.scale = scale;
+            }
+            layer.set_if_neq(pixel_camera.viewport_layer);
+        }
+
+        if mesh.input.as_ref() != Some(&input) {
+            if let Some(material) = materials.get_mut(material) {
+                material.set_viewport(&input);
+                mesh.input = Some(input);
+            }
+        }
+    }
+}
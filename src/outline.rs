@@ -0,0 +1,296 @@
+//! Texel wide outlines for 3D [`PixelCamera`](crate::components::PixelCamera)s.
+//!
+//! A post processing pass on the low resolution target draws outlines where the depth or the
+//! normals of neighboring texels differ, a staple of 3D scenes rendered as pixel art.
+//! Because it runs on the low resolution target, the outlines are exactly one texel wide.
+
+use bevy::core_pipeline::core_3d::graph::{Core3d, Node3d};
+use bevy::core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state;
+use bevy::core_pipeline::prepass::{DepthPrepass, NormalPrepass, ViewPrepassTextures};
+use bevy::ecs::query::QueryItem;
+use bevy::prelude::*;
+use bevy::render::extract_component::{
+    ComponentUniforms, DynamicUniformIndex, ExtractComponent, ExtractComponentPlugin,
+    UniformComponentPlugin,
+};
+use bevy::render::render_graph::{
+    NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner,
+};
+use bevy::render::render_resource::binding_types::{texture_2d, texture_depth_2d, uniform_buffer};
+use bevy::render::render_resource::*;
+use bevy::render::renderer::{RenderContext, RenderDevice};
+use bevy::render::view::ViewTarget;
+use bevy::render::{Render, RenderApp, RenderSet};
+
+use crate::components::PixelCamera3d;
+
+const OUTLINE_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(0x8e4f_12a9_6c3b_4d70_b915_2f6e_a0d8_37c1);
+
+/// Adds the outline pass for 3D cameras with [`PixelOutline`].
+pub struct PixelOutlinePlugin;
+impl Plugin for PixelOutlinePlugin {
+    fn build(&self, app: &mut App) {
+        bevy::asset::load_internal_asset!(
+            app,
+            OUTLINE_SHADER_HANDLE,
+            "outline.wgsl",
+            Shader::from_wgsl
+        );
+
+        app.add_plugins((
+            ExtractComponentPlugin::<PixelOutline>::default(),
+            UniformComponentPlugin::<PixelOutlineUniform>::default(),
+        ))
+        .add_systems(PostUpdate, add_outline_prepasses);
+
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .init_resource::<SpecializedRenderPipelines<OutlinePipeline>>()
+            .add_systems(Render, prepare_outline_pipelines.in_set(RenderSet::Prepare))
+            .add_render_graph_node::<ViewNodeRunner<OutlineNode>>(Core3d, PixelOutlineLabel)
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    Node3d::Tonemapping,
+                    PixelOutlineLabel,
+                    Node3d::EndMainPassPostProcessing,
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app.init_resource::<OutlinePipeline>();
+    }
+}
+
+/// Draws texel wide outlines on the render target of a 3D pixel camera.
+///
+/// Add this next to a [`PixelCamera3d`]. The [`DepthPrepass`] and [`NormalPrepass`] that the
+/// outlines are detected from are added to the camera automatically.
+///
+/// Outlines are drawn on the texel closer to the camera, so objects are outlined on their
+/// own silhouette rather than on the background.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct PixelOutline {
+    /// The color of the outlines.
+    pub color: Color,
+    /// How much farther away a neighboring texel has to be for an outline to be drawn,
+    /// in units of the depth buffer. The depth buffer is only linear for orthographic cameras.
+    pub depth_threshold: f32,
+    /// How different the normals of neighboring texels have to be for an outline to be drawn,
+    /// as `1 - cos(angle)`. Values of 2 or more disable normal outlines.
+    pub normal_threshold: f32,
+}
+
+impl Default for PixelOutline {
+    fn default() -> Self {
+        Self {
+            color: Color::BLACK,
+            depth_threshold: 0.01,
+            normal_threshold: 0.5,
+        }
+    }
+}
+
+impl ExtractComponent for PixelOutline {
+    type QueryData = &'static Self;
+    type QueryFilter = With<PixelCamera3d>;
+    type Out = PixelOutlineUniform;
+
+    fn extract_component(outline: QueryItem<'_, Self::QueryData>) -> Option<Self::Out> {
+        Some(PixelOutlineUniform {
+            color: outline.color.as_linear_rgba_f32().into(),
+            depth_threshold: outline.depth_threshold,
+            normal_threshold: outline.normal_threshold,
+        })
+    }
+}
+
+use uniform::PixelOutlineUniform;
+
+// `ShaderType` generates layout checks that are never called, and the fields are only read
+// by the shader.
+#[allow(dead_code)]
+mod uniform {
+    use bevy::prelude::*;
+    use bevy::render::render_resource::ShaderType;
+
+    /// The uniform of the outline shader.
+    #[derive(Component, ShaderType, Debug, Clone, Copy)]
+    pub struct PixelOutlineUniform {
+        /// The color of the outlines, in linear RGBA.
+        pub color: Vec4,
+        pub depth_threshold: f32,
+        pub normal_threshold: f32,
+    }
+}
+
+/// Adds the prepasses that outlines are detected from.
+#[allow(clippy::type_complexity)]
+fn add_outline_prepasses(
+    cameras: Query<
+        (Entity, Has<DepthPrepass>, Has<NormalPrepass>),
+        (
+            With<PixelOutline>,
+            Or<(Without<DepthPrepass>, Without<NormalPrepass>)>,
+        ),
+    >,
+    mut commands: Commands,
+) {
+    for (entity, has_depth, has_normal) in &cameras {
+        let mut entity = commands.entity(entity);
+        if !has_depth {
+            entity.insert(DepthPrepass);
+        }
+        if !has_normal {
+            entity.insert(NormalPrepass);
+        }
+    }
+}
+
+#[derive(Resource)]
+struct OutlinePipeline {
+    layout: BindGroupLayout,
+}
+
+impl FromWorld for OutlinePipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let layout = render_device.create_bind_group_layout(
+            "pixel_outline_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: false }),
+                    texture_depth_2d(),
+                    texture_2d(TextureSampleType::Float { filterable: false }),
+                    uniform_buffer::<PixelOutlineUniform>(true),
+                ),
+            ),
+        );
+
+        Self { layout }
+    }
+}
+
+impl SpecializedRenderPipeline for OutlinePipeline {
+    /// The format of the view's main texture.
+    type Key = TextureFormat;
+
+    fn specialize(&self, format: Self::Key) -> RenderPipelineDescriptor {
+        RenderPipelineDescriptor {
+            label: Some("pixel_outline_pipeline".into()),
+            layout: vec![self.layout.clone()],
+            push_constant_ranges: Vec::new(),
+            vertex: fullscreen_shader_vertex_state(),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            fragment: Some(FragmentState {
+                shader: OUTLINE_SHADER_HANDLE,
+                shader_defs: Vec::new(),
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+        }
+    }
+}
+
+/// The outline pipeline of a view, specialized for its main texture format.
+#[derive(Component)]
+struct OutlinePipelineId(CachedRenderPipelineId);
+
+fn prepare_outline_pipelines(
+    views: Query<(Entity, &ViewTarget), With<PixelOutlineUniform>>,
+    outline_pipeline: Res<OutlinePipeline>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<OutlinePipeline>>,
+    pipeline_cache: Res<PipelineCache>,
+    mut commands: Commands,
+) {
+    for (entity, view_target) in &views {
+        let id = pipelines.specialize(
+            &pipeline_cache,
+            &outline_pipeline,
+            view_target.main_texture_format(),
+        );
+        commands.entity(entity).insert(OutlinePipelineId(id));
+    }
+}
+
+#[derive(RenderLabel, Debug, Hash, PartialEq, Eq, Clone)]
+struct PixelOutlineLabel;
+
+#[derive(Default)]
+struct OutlineNode;
+impl ViewNode for OutlineNode {
+    type ViewQuery = (
+        &'static ViewTarget,
+        &'static ViewPrepassTextures,
+        &'static OutlinePipelineId,
+        &'static DynamicUniformIndex<PixelOutlineUniform>,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (view_target, prepass_textures, pipeline_id, uniform_index): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let outline_pipeline = world.resource::<OutlinePipeline>();
+        let Some(pipeline) = world
+            .resource::<PipelineCache>()
+            .get_render_pipeline(pipeline_id.0)
+        else {
+            return Ok(());
+        };
+        let Some(uniforms) = world
+            .resource::<ComponentUniforms<PixelOutlineUniform>>()
+            .uniforms()
+            .binding()
+        else {
+            return Ok(());
+        };
+        let (Some(depth), Some(normal)) = (
+            prepass_textures.depth_view(),
+            prepass_textures.normal_view(),
+        ) else {
+            return Ok(());
+        };
+
+        let post_process = view_target.post_process_write();
+        let bind_group = render_context.render_device().create_bind_group(
+            "pixel_outline_bind_group",
+            &outline_pipeline.layout,
+            &BindGroupEntries::sequential((post_process.source, depth, normal, uniforms)),
+        );
+
+        let mut pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("pixel_outline_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        pass.set_render_pipeline(pipeline);
+        pass.set_bind_group(0, &bind_group, &[uniform_index.index()]);
+        pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
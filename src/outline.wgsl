@@ -0,0 +1,46 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+struct PixelOutline {
+    color: vec4<f32>,
+    depth_threshold: f32,
+    normal_threshold: f32,
+}
+
+@group(0) @binding(0) var screen_texture: texture_2d<f32>;
+@group(0) @binding(1) var depth_texture: texture_depth_2d;
+@group(0) @binding(2) var normal_texture: texture_2d<f32>;
+@group(0) @binding(3) var<uniform> outline: PixelOutline;
+
+fn normal_at(texel: vec2<i32>) -> vec3<f32> {
+    return textureLoad(normal_texture, texel, 0).xyz * 2.0 - 1.0;
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let texel = vec2<i32>(in.position.xy);
+    let last = vec2<i32>(textureDimensions(depth_texture)) - 1;
+    let depth = textureLoad(depth_texture, texel, 0);
+    let normal = normal_at(texel);
+
+    var offsets = array<vec2<i32>, 4>(
+        vec2<i32>(1, 0),
+        vec2<i32>(0, 1),
+        vec2<i32>(-1, 0),
+        vec2<i32>(0, -1),
+    );
+    for (var i = 0; i < 4; i++) {
+        let neighbor = clamp(texel + offsets[i], vec2<i32>(0), last);
+
+        // The depth buffer is reversed, the neighbor is farther away if its depth is smaller.
+        // Only the closer texel is outlined, which keeps the outline one texel wide.
+        if depth - textureLoad(depth_texture, neighbor, 0) > outline.depth_threshold {
+            return outline.color;
+        }
+        // Normal edges are found from both sides, so only look in one direction per axis.
+        if i < 2 && depth > 0.0 && 1.0 - dot(normal, normal_at(neighbor)) > outline.normal_threshold {
+            return outline.color;
+        }
+    }
+
+    return textureLoad(screen_texture, texel, 0);
+}
@@ -0,0 +1,142 @@
+//! A mouse controller for [`PixelCamera`]s, in the style of `bevy_pancam`.
+//!
+//! `bevy_pancam` and similar controllers write to the camera's [`Transform`] and
+//! [`OrthographicProjection::scale`], both of which a pixel camera overwrites. [`PixelPanCam`]
+//! drags [`PixelCamera::subpixel_pos`] instead, and zooms in whole steps of
+//! [`ViewportSize::PixelFixed`](crate::viewport::ViewportSize::PixelFixed).
+
+use bevy::input::mouse::MouseWheel;
+use bevy::prelude::*;
+use bevy::render::render_resource::Extent3d;
+use bevy::window::PrimaryWindow;
+
+use crate::components::PixelCamera;
+use crate::cursor::PointerCaptured;
+use crate::systems::fit_projection_size;
+
+/// Adds the systems that drive cameras with a [`PixelPanCam`].
+pub struct PixelPanCamPlugin;
+impl Plugin for PixelPanCamPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, pan_pixel_cameras);
+    }
+}
+
+/// Drags a [`PixelCamera`] with the mouse and zooms it with the mouse wheel, on the primary window.
+///
+/// Zooming steps the [`PixelCamera::zoom`], so it only works with `ViewportSize::PixelFixed`.
+#[derive(Component, Debug, Clone)]
+pub struct PixelPanCam {
+    /// The mouse buttons that drag the camera.
+    pub grab_buttons: Vec<MouseButton>,
+    /// Whether the camera can be dragged and zoomed.
+    pub enabled: bool,
+    /// Whether zooming keeps the point under the cursor in place, instead of the center.
+    pub zoom_to_cursor: bool,
+    /// The smallest pixel scale that zooming out goes to.
+    pub min_scale: u32,
+    /// The largest pixel scale that zooming in goes to.
+    pub max_scale: u32,
+    last_cursor: Option<Vec2>,
+}
+
+impl Default for PixelPanCam {
+    fn default() -> Self {
+        Self {
+            grab_buttons: vec![MouseButton::Left, MouseButton::Right, MouseButton::Middle],
+            enabled: true,
+            zoom_to_cursor: true,
+            min_scale: 1,
+            max_scale: 8,
+            last_cursor: None,
+        }
+    }
+}
+
+/// Returns how many world units a logical pixel of `window` covers.
+fn world_per_logical_pixel(
+    camera: &PixelCamera,
+    projection: &OrthographicProjection,
+    window: &Window,
+) -> Option<f32> {
+    if window.width() <= 0.0 || window.height() <= 0.0 {
+        return None;
+    }
+
+    let visible = camera.visible_rect(projection).size() / projection.scale;
+    let shown = fit_projection_size(
+        &camera.viewport_size,
+        Extent3d {
+            width: visible.x as u32,
+            height: visible.y as u32,
+            ..default()
+        },
+        Vec2::new(window.width(), window.height()),
+    );
+
+    Some(projection.scale * shown.x / window.width())
+}
+
+fn pan_pixel_cameras(
+    mut cameras: Query<(
+        Entity,
+        &mut PixelCamera,
+        &mut PixelPanCam,
+        &OrthographicProjection,
+    )>,
+    primary_window: Query<&Window, With<PrimaryWindow>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    mut wheel: EventReader<MouseWheel>,
+    pointer_captured: Option<Res<PointerCaptured>>,
+) {
+    let pointer_captured = pointer_captured.is_some_and(|captured| captured.0);
+    let scroll: f32 = wheel.read().map(|event| event.y).sum();
+    let Ok(window) = primary_window.get_single() else {
+        return;
+    };
+    let cursor = window.cursor_position();
+
+    for (entity, mut camera, mut pancam, projection) in &mut cameras {
+        let last_cursor = std::mem::replace(&mut pancam.last_cursor, cursor);
+        if !pancam.enabled || pointer_captured {
+            continue;
+        }
+        let Some(world_per_pixel) = world_per_logical_pixel(&camera, projection, window) else {
+            continue;
+        };
+
+        let grabbed = mouse_buttons.any_pressed(pancam.grab_buttons.iter().copied());
+        if let (true, Some(cursor), Some(last_cursor)) = (grabbed, cursor, last_cursor) {
+            // The window's y axis points down.
+            let delta = (cursor - last_cursor) * Vec2::new(-1.0, 1.0) * world_per_pixel;
+            if delta != Vec2::ZERO {
+                camera.subpixel_pos += delta;
+            }
+        }
+
+        if scroll == 0.0 {
+            continue;
+        }
+        let Some(scale) = camera.zoom() else {
+            warn_once!("PixelPanCam on {entity:?} can only zoom with `ViewportSize::PixelFixed`");
+            continue;
+        };
+        let new_scale = scale
+            .saturating_add_signed(scroll.signum() as i32)
+            .min(pancam.max_scale)
+            .max(pancam.min_scale)
+            .max(1);
+        if new_scale == scale {
+            continue;
+        }
+
+        if let (true, Some(cursor)) = (pancam.zoom_to_cursor, cursor) {
+            // Keep the world position under the cursor in place, a world unit
+            // covers `new_scale / scale` times as many logical pixels after zooming.
+            let center = Vec2::new(window.width(), window.height()) / 2.0;
+            let offset = (cursor - center) * Vec2::new(1.0, -1.0) * world_per_pixel;
+            camera.subpixel_pos += offset * (1.0 - scale as f32 / new_scale as f32);
+        }
+        camera.set_zoom(new_scale);
+    }
+}
@@ -0,0 +1,103 @@
+//! Smooth parallax layers for a [`PixelCamera`].
+//!
+//! Moving a layer with the camera's [`Transform`] makes it step, since a pixel camera's
+//! transform is snapped to whole pixels. [`PixelParallax`] moves the layer with the smooth
+//! [`PixelCamera::subpixel_pos`] instead, snapped to the texel grid so that the layer's pixels
+//! line up with the rest of the world, and smoothed along with it by the camera's subpixel offset.
+//!
+//! Parallax crates can use [`PixelParallax::offset`] to move their own layers the same way.
+
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+
+use crate::components::{PixelCamera, PixelCamera3d};
+use crate::CameraSystems;
+
+/// Moves [`PixelParallax`] layers with their camera.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::parallax::{PixelParallax, PixelParallaxPlugin};
+/// fn spawn_background(mut commands: Commands) {
+///     // Mountains that scroll past at a quarter of the camera's speed.
+///     commands.spawn((
+///         SpriteBundle::default(),
+///         PixelParallax::new(Vec2::new(0.0, 40.0), Vec2::splat(0.75)),
+///     ));
+/// }
+///
+/// App::new()
+///     .add_plugins(PixelParallaxPlugin)
+///     .add_systems(Startup, spawn_background);
+/// ```
+pub struct PixelParallaxPlugin;
+impl Plugin for PixelParallaxPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PixelParallax>().add_systems(
+            PostUpdate,
+            move_parallax_layers
+                .after(CameraSystems::Positioning)
+                .before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// A layer that moves along with a 2D [`PixelCamera`], so that it looks further away.
+///
+/// The layer's [`Transform`] is set from its `origin` every frame, move the layer through
+/// `origin` instead of the transform.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct PixelParallax {
+    /// The position of the layer while the camera is at the origin.
+    pub origin: Vec2,
+    /// How much the layer moves along with the camera on each axis.
+    ///
+    /// `0` doesn't move at all, like the rest of the world, and `1` moves with the camera,
+    /// like it's infinitely far away.
+    pub factor: Vec2,
+    /// The entity of the [`PixelCamera`] that the layer moves with.
+    ///
+    /// If [`None`], the only 2D [`PixelCamera`] in the world is used.
+    pub camera: Option<Entity>,
+}
+
+impl PixelParallax {
+    /// Creates a layer at `origin` that moves with the only 2D [`PixelCamera`] by `factor`.
+    pub fn new(origin: Vec2, factor: Vec2) -> Self {
+        Self {
+            origin,
+            factor,
+            camera: None,
+        }
+    }
+    /// Returns how far the layer is moved for the given camera, snapped to whole texels.
+    ///
+    /// The camera's subpixel offset smooths the rest of the movement, the same way it does for
+    /// the world.
+    pub fn offset(&self, camera: &PixelCamera) -> Vec2 {
+        (camera.subpixel_pos * self.factor).round()
+    }
+}
+
+fn move_parallax_layers(
+    mut layers: Query<(Entity, &PixelParallax, &mut Transform), Without<PixelCamera>>,
+    cameras: Query<&PixelCamera, Without<PixelCamera3d>>,
+) {
+    for (entity, parallax, mut transform) in &mut layers {
+        let camera = match parallax.camera {
+            Some(camera) => cameras.get(camera).ok(),
+            None => cameras.get_single().ok(),
+        };
+        let Some(camera) = camera else {
+            warn_once!("PixelParallax on {entity:?} doesn't point to a single 2D PixelCamera");
+            continue;
+        };
+
+        let position = parallax.origin + parallax.offset(camera);
+        if transform.translation.truncate() != position {
+            transform.translation.x = position.x;
+            transform.translation.y = position.y;
+        }
+    }
+}
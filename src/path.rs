@@ -0,0 +1,106 @@
+//! Moving a [`PixelCamera`] along a path, e.g. for auto scrollers and fly-bys.
+
+use bevy::prelude::*;
+
+use crate::components::{PixelCamera, PixelCamera3d, PixelFreeze};
+
+/// Puts a 2D [`PixelCamera`] at [`CameraPath::progress`] along a path of waypoints.
+///
+/// Animate the progress (e.g. with a tween or every frame in a system) to move the camera.
+/// The camera is snapped to the pixel grid along the way like it is everywhere else, and
+/// smoothed if [`PixelCamera::smoothing`] is on.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn scroll(mut paths: Query<&mut CameraPath>, time: Res<Time>) {
+///     for mut path in &mut paths {
+///         // Scroll along the whole path in a minute.
+///         path.progress = (path.progress + time.delta_seconds() / 60.0).min(1.0);
+///     }
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone, PartialEq)]
+#[reflect(Component, Default)]
+pub struct CameraPath {
+    /// The points that the path goes through, in world units.
+    pub waypoints: Vec<Vec2>,
+    /// How far along the path the camera is, from 0 at the first waypoint to 1 at the last one
+    /// (or back at the first one, if the path loops). Proportional to the distance traveled,
+    /// so animating it at a constant rate moves the camera at a constant speed.
+    pub progress: f32,
+    /// Whether the path goes from the last waypoint back to the first one.
+    /// Progress past 1 wraps around on looping paths.
+    pub looping: bool,
+}
+
+impl Default for CameraPath {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl CameraPath {
+    /// Creates a path through the `waypoints`, with the camera at the start.
+    pub fn new(waypoints: Vec<Vec2>) -> Self {
+        Self {
+            waypoints,
+            progress: 0.0,
+            looping: false,
+        }
+    }
+
+    /// Returns the position at the [`CameraPath::progress`], or [`None`] if there are no waypoints.
+    pub fn position(&self) -> Option<Vec2> {
+        let first = *self.waypoints.first()?;
+        let segments = || {
+            let closing = self.looping.then_some((*self.waypoints.last()?, first));
+            Some(
+                self.waypoints
+                    .windows(2)
+                    .map(|points| (points[0], points[1]))
+                    .chain(closing),
+            )
+        };
+        let length: f32 = segments()?.map(|(from, to)| from.distance(to)).sum();
+        if length <= 0.0 {
+            return Some(first);
+        }
+
+        let progress = if self.looping {
+            self.progress.rem_euclid(1.0)
+        } else {
+            self.progress.clamp(0.0, 1.0)
+        };
+        let mut remaining = progress * length;
+        let mut position = first;
+        for (from, to) in segments()? {
+            let segment = from.distance(to);
+            position = to;
+            if remaining <= segment {
+                return Some(from.lerp(to, remaining / segment.max(f32::EPSILON)));
+            }
+            remaining -= segment;
+        }
+
+        Some(position)
+    }
+}
+
+/// Moves cameras with a [`CameraPath`] to their position along it.
+#[allow(clippy::type_complexity)]
+pub(crate) fn follow_paths(
+    mut cameras: Query<
+        (&mut PixelCamera, &CameraPath),
+        (Without<PixelCamera3d>, Without<PixelFreeze>),
+    >,
+) {
+    for (mut camera, path) in &mut cameras {
+        let Some(position) = path.position() else {
+            continue;
+        };
+        if position != camera.subpixel_pos {
+            camera.subpixel_pos = position;
+        }
+    }
+}
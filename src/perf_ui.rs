@@ -0,0 +1,222 @@
+//! A `bevy_ui` overlay with performance information about every [`PixelCamera`].
+//!
+//! Shows the internal resolution, the upscaling factor and the memory of each render target,
+//! the active [`ViewportProfile`](crate::profile::ViewportProfile), and the time spent in
+//! [`PixelCameraPlugin`](crate::PixelCameraPlugin)'s systems.
+//! Handy when tuning viewport sizes and profiles for low-end hardware.
+
+use std::fmt::Write;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::utils::Instant;
+
+use crate::components::{PixelCamera, PixelViewportReferences};
+use crate::profile::ViewportProfiles;
+use crate::viewport_info::{PixelViewportInfo, PixelViewportInfoPlugin};
+use crate::CameraSystems;
+
+/// Adds the pixel camera performance overlay.
+///
+/// Adds [`PixelViewportInfoPlugin`] if it hasn't been added yet.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::perf_ui::{PixelCameraPerfUi, PixelCameraPerfUiPlugin};
+/// fn toggle_perf_ui(keys: Res<ButtonInput<KeyCode>>, mut perf_ui: ResMut<PixelCameraPerfUi>) {
+///     if keys.just_pressed(KeyCode::F3) {
+///         perf_ui.open = !perf_ui.open;
+///     }
+/// }
+///
+/// App::new()
+///     .add_plugins(PixelCameraPerfUiPlugin)
+///     .add_systems(Update, toggle_perf_ui);
+/// ```
+pub struct PixelCameraPerfUiPlugin;
+impl Plugin for PixelCameraPerfUiPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<PixelViewportInfoPlugin>() {
+            app.add_plugins(PixelViewportInfoPlugin);
+        }
+
+        app.init_resource::<PixelCameraPerfUi>()
+            .init_resource::<PixelCameraTimings>()
+            .add_systems(
+                PostUpdate,
+                (
+                    start_timing.before(CameraSystems::Initialization),
+                    stop_timing.after(CameraSystems::Update),
+                ),
+            )
+            .add_systems(Update, (show_perf_ui, update_perf_text).chain());
+    }
+}
+
+/// Settings of the performance overlay.
+#[derive(Resource, Debug, Clone)]
+pub struct PixelCameraPerfUi {
+    /// Whether the overlay is shown.
+    pub open: bool,
+}
+
+impl Default for PixelCameraPerfUi {
+    fn default() -> Self {
+        Self { open: true }
+    }
+}
+
+/// The time spent in [`PixelCameraPlugin`](crate::PixelCameraPlugin)'s systems.
+///
+/// This is the wall time from the start of [`CameraSystems::Initialization`] to the end of
+/// [`CameraSystems::Update`], so it includes other systems that run in parallel with them.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PixelCameraTimings {
+    /// The time of the last frame.
+    pub last: Duration,
+    /// A moving average over the last frames.
+    pub average: Duration,
+    start: Option<Instant>,
+}
+
+/// The root node of the overlay.
+#[derive(Component)]
+struct PerfUiRoot;
+
+/// The text of the overlay.
+#[derive(Component)]
+struct PerfUiText;
+
+fn start_timing(mut timings: ResMut<PixelCameraTimings>) {
+    timings.start = Some(Instant::now());
+}
+
+fn stop_timing(mut timings: ResMut<PixelCameraTimings>) {
+    let Some(start) = timings.start.take() else {
+        return;
+    };
+    timings.last = start.elapsed();
+    timings.average = timings.average.mul_f32(0.9) + timings.last.mul_f32(0.1);
+}
+
+/// Spawns and despawns the overlay, and keeps it on the native resolution viewport camera.
+fn show_perf_ui(
+    perf_ui: Res<PixelCameraPerfUi>,
+    mut roots: Query<(Entity, Option<&mut TargetCamera>), With<PerfUiRoot>>,
+    cameras: Query<&PixelViewportReferences, With<PixelCamera>>,
+    mut commands: Commands,
+) {
+    if !perf_ui.open {
+        for (root, _) in &roots {
+            commands.entity(root).despawn_recursive();
+        }
+        return;
+    }
+
+    // The overlay is drawn by a viewport camera, so that it isn't pixelated.
+    let viewport_camera = cameras.iter().map(|references| references.camera).min();
+
+    match roots.get_single_mut() {
+        Ok((root, target)) => match (target, viewport_camera) {
+            (Some(mut target), Some(camera)) => {
+                target.set_if_neq(TargetCamera(camera));
+            }
+            (None, Some(camera)) => {
+                commands.entity(root).insert(TargetCamera(camera));
+            }
+            (Some(_), None) => {
+                commands.entity(root).remove::<TargetCamera>();
+            }
+            (None, None) => {}
+        },
+        Err(_) => {
+            let mut root = commands.spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(4.0),
+                        top: Val::Px(4.0),
+                        padding: UiRect::all(Val::Px(4.0)),
+                        ..default()
+                    },
+                    background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                    z_index: ZIndex::Global(i32::MAX),
+                    ..default()
+                },
+                PerfUiRoot,
+                Name::new("Pixel Camera Perf UI"),
+            ));
+            if let Some(camera) = viewport_camera {
+                root.insert(TargetCamera(camera));
+            }
+            root.with_children(|root| {
+                root.spawn((
+                    TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font_size: 16.0,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    ),
+                    PerfUiText,
+                ));
+            });
+        }
+    }
+}
+
+fn update_perf_text(
+    mut texts: Query<&mut Text, With<PerfUiText>>,
+    timings: Res<PixelCameraTimings>,
+    profiles: Option<Res<ViewportProfiles>>,
+    cameras: Query<(Entity, &PixelViewportInfo)>,
+) {
+    let Ok(mut text) = texts.get_single_mut() else {
+        return;
+    };
+
+    let mut value = format!(
+        "System time: {:.3} ms (average {:.3} ms)",
+        timings.last.as_secs_f64() * 1000.0,
+        timings.average.as_secs_f64() * 1000.0,
+    );
+    if let Some(active) = profiles.as_deref().and_then(ViewportProfiles::active) {
+        let _ = write!(value, "\nProfile: {active}");
+    }
+
+    let mut total_memory = 0;
+    for (entity, info) in &cameras {
+        total_memory += info.memory;
+
+        let _ = write!(
+            value,
+            "\n\nCamera {entity:?}\nResolution: {}×{} ({}×{} with margin)",
+            info.resolution.x, info.resolution.y, info.target_size.x, info.target_size.y
+        );
+        match info.scale {
+            Some(scale) => {
+                let _ = write!(value, "\nScale: {:.2}×{:.2}", scale.x, scale.y);
+            }
+            None => value.push_str("\nScale: unknown"),
+        }
+        let _ = write!(value, "\nTarget memory: {}", format_bytes(info.memory));
+    }
+    let _ = write!(
+        value,
+        "\n\nTotal target memory: {}",
+        format_bytes(total_memory)
+    );
+
+    if text.sections[0].value != value {
+        text.sections[0].value = value;
+    }
+}
+
+fn format_bytes(bytes: usize) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.2} MiB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    }
+}
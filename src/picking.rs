@@ -0,0 +1,156 @@
+//! Picking world sprites through the viewport of a [`PixelCamera`].
+//!
+//! Pointers on the window only ever hit the upscaled viewport sprite. [`PixelPickingPlugin`]
+//! maps the cursor through the viewport with [`PixelCameraCursor`] (scale, letterboxing and
+//! subpixel offset) and hit-tests the sprites behind it, so that your own systems or a picking
+//! crate's backend can react to what's under the cursor.
+
+use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
+
+use crate::components::{PixelCamera, PixelCamera3d, PixelViewportReferences, ViewportCamera};
+use crate::cursor::{PixelCameraCursor, PointerCaptured};
+
+/// Keeps [`PixelPointerHits`] up to date with the [`PixelPickable`] sprites under the cursor.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn hover(hits: Res<PixelPointerHits>, names: Query<&Name>) {
+///     if let Some(name) = hits.top().and_then(|hit| names.get(hit.entity).ok()) {
+///         info!("The cursor is over {name}");
+///     }
+/// }
+///
+/// App::new()
+///     .add_plugins(PixelPickingPlugin)
+///     .add_systems(Update, hover);
+/// ```
+pub struct PixelPickingPlugin;
+impl Plugin for PixelPickingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PixelPointerHits>()
+            .register_type::<PixelPickable>()
+            .add_systems(PreUpdate, pick_sprites);
+    }
+}
+
+/// Makes a sprite pickable through the viewport of the [`PixelCamera`]s that render it.
+#[derive(Component, Reflect, Debug, Clone, Copy, Default)]
+#[reflect(Component, Default)]
+pub struct PixelPickable;
+
+/// A [`PixelPickable`] sprite under the cursor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PixelHit {
+    /// The sprite under the cursor.
+    pub entity: Entity,
+    /// The [`PixelCamera`] that the sprite was picked through.
+    pub camera: Entity,
+    /// The distance from the camera to the sprite, smaller is closer.
+    pub depth: f32,
+    /// The world position under the cursor.
+    pub position: Vec2,
+}
+
+/// The [`PixelPickable`] sprites under the cursor, updated by [`PixelPickingPlugin`]
+/// before [`Update`].
+///
+/// Sorted from front to back: hits through the viewport that's drawn on top come first,
+/// then the closest sprites of each viewport.
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct PixelPointerHits(pub Vec<PixelHit>);
+
+impl PixelPointerHits {
+    /// Returns the frontmost sprite under the cursor.
+    pub fn top(&self) -> Option<&PixelHit> {
+        self.0.first()
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn pick_sprites(
+    cursor: PixelCameraCursor,
+    cameras: Query<
+        (
+            Entity,
+            &Camera,
+            &GlobalTransform,
+            &PixelViewportReferences,
+            Option<&RenderLayers>,
+        ),
+        (With<PixelCamera>, Without<PixelCamera3d>),
+    >,
+    viewport_cameras: Query<&Camera, With<ViewportCamera>>,
+    sprites: Query<
+        (
+            Entity,
+            &Sprite,
+            &Handle<Image>,
+            &GlobalTransform,
+            &ViewVisibility,
+            Option<&RenderLayers>,
+        ),
+        With<PixelPickable>,
+    >,
+    images: Res<Assets<Image>>,
+    pointer_captured: Option<Res<PointerCaptured>>,
+    mut pointer_hits: ResMut<PixelPointerHits>,
+) {
+    if pointer_captured.is_some_and(|captured| captured.0) {
+        pointer_hits.set_if_neq(PixelPointerHits::default());
+        return;
+    }
+
+    // The hits with the order of the viewport camera they're shown through.
+    let mut hits: Vec<(isize, PixelHit)> = Vec::new();
+
+    for (camera_entity, camera, camera_transform, viewport, world_layer) in &cameras {
+        let Ok(viewport_camera) = viewport_cameras.get(viewport.camera) else {
+            continue;
+        };
+        if !camera.is_active || !viewport_camera.is_active {
+            continue;
+        }
+        let Some(position) = cursor.cursor_world_position(camera_entity) else {
+            continue;
+        };
+
+        let world_layer = world_layer.copied().unwrap_or_default();
+        hits.extend(
+            sprites
+                .iter()
+                .filter(|(.., visibility, layer)| {
+                    visibility.get() && layer.copied().unwrap_or_default().intersects(&world_layer)
+                })
+                .filter_map(|(entity, sprite, image, transform, ..)| {
+                    let size = sprite
+                        .custom_size
+                        .or_else(|| sprite.rect.map(|rect| rect.size()))
+                        .or_else(|| images.get(image).map(|image| image.size_f32()))?;
+                    let center = -sprite.anchor.as_vec() * size;
+                    let rect = Rect::from_center_half_size(center, size / 2.0);
+                    let local = transform
+                        .affine()
+                        .inverse()
+                        .transform_point3(position.extend(0.0));
+
+                    rect.contains(local.truncate()).then(|| {
+                        let hit = PixelHit {
+                            entity,
+                            camera: camera_entity,
+                            depth: camera_transform.translation().z - transform.translation().z,
+                            position,
+                        };
+                        (viewport_camera.order, hit)
+                    })
+                }),
+        );
+    }
+
+    hits.sort_by(|(a_order, a), (b_order, b)| {
+        b_order.cmp(a_order).then(a.depth.total_cmp(&b.depth))
+    });
+    let hits = PixelPointerHits(hits.into_iter().map(|(_, hit)| hit).collect());
+    pointer_hits.set_if_neq(hits);
+}
@@ -1,5 +1,47 @@
 //! `use bevy_smooth_pixel_camera::prelude::*;` to import the [`PixelCamera`] and [`PixelCameraPlugin`].
 
-pub use super::components::PixelCamera;
-pub use super::viewport::ViewportSize;
-pub use super::PixelCameraPlugin;
+#[cfg(feature = "audio")]
+pub use super::audio::SmoothSpatialListener;
+pub use super::billboard::PixelBillboard;
+pub use super::bounds::CameraBounds;
+pub use super::components::{
+    CustomViewport, PixelBypass, PixelCamera, PixelCamera3d, PixelFreeze, PixelViewport,
+    ViewportCamera, ViewportMirror, ViewportRect, ViewportTarget,
+};
+pub use super::composite::DirectComposite;
+pub use super::controller::{
+    PixelCameraController, PixelCameraControllerPlugin, PixelCameraKeyBindings,
+};
+pub use super::cursor::{CursorWorldPos, PixelCameraCursor, PixelCursorPlugin, PointerCaptured};
+pub use super::dynamic_resolution::DynamicResolution;
+pub use super::error::{PixelCameraError, PixelCameraErrorKind};
+pub use super::follow::{LookAhead, PixelCameraFollow, PixelCameraGroupFollow};
+#[cfg(feature = "fullscreen")]
+pub use super::fullscreen::PixelFullscreen;
+#[cfg(feature = "gizmos")]
+pub use super::gizmos::{PixelGizmoGroupPlugin, PixelGizmos};
+pub use super::handheld::{DetectedHandheld, Handheld, PixelHandheldPlugin};
+pub use super::high_res::{HighRes, HighResOverlay};
+pub use super::outline::{PixelOutline, PixelOutlinePlugin};
+pub use super::pancam::{PixelPanCam, PixelPanCamPlugin};
+pub use super::path::CameraPath;
+pub use super::picking::{PixelPickable, PixelPickingPlugin, PixelPointerHits};
+pub use super::profile::{PixelCameraProfiles, ViewportProfile, ViewportProfiles};
+pub use super::readback::{PixelFrame, PixelReadback};
+pub use super::renderers::{PixelLayers, PixelRendererPlugin};
+pub use super::rooms::{CameraRoom, PixelCameraRooms, RoomTransition};
+#[cfg(feature = "serialize")]
+pub use super::save::PixelCameraSnapshot;
+pub use super::shared::SharedPixelTarget;
+pub use super::state::{ActiveInState, PixelCameraStatePlugin, StateExit};
+#[cfg(feature = "text")]
+pub use super::text::PixelText;
+#[cfg(feature = "ui")]
+pub use super::ui::{PixelInteraction, PixelUi, PixelUiViewport, SyncUiScale};
+pub use super::viewport::{DpiPolicy, ViewportSize};
+pub use super::viewport_info::{PixelViewportInfo, PixelViewportInfoPlugin};
+pub use super::virtual_camera::{PixelCameraBrain, VirtualPixelCamera};
+pub use super::window_snap::SnapWindowSize;
+pub use super::zones::{CameraZone, PixelCameraZones};
+pub use super::zoom::PixelZoom;
+pub use super::{PixelCameraConfig, PixelCameraPlugin};
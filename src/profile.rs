@@ -0,0 +1,137 @@
+//! Named sets of viewport settings, for switching the quality of pixel cameras at runtime
+//! (e.g. from a graphics settings menu).
+
+use std::fmt;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::components::{PixelCamera, PixelCamera3d};
+use crate::viewport::ViewportSize;
+
+/// The settings of a [`PixelCamera`] that a quality preset changes.
+#[derive(Clone)]
+pub struct ViewportProfile {
+    /// The [`PixelCamera::viewport_size`], including the way it fits the window.
+    pub viewport_size: ViewportSize,
+    /// The [`PixelCamera::smoothing`].
+    pub smoothing: bool,
+}
+
+impl ViewportProfile {
+    /// Applies the profile to a camera.
+    ///
+    /// Smoothing is never turned on for [`PixelCamera3d::Free`] cameras, which don't support it.
+    pub fn apply(&self, camera: &mut PixelCamera, camera_3d: Option<&PixelCamera3d>) {
+        camera.viewport_size = self.viewport_size.clone();
+        camera.smoothing = self.smoothing && camera_3d != Some(&PixelCamera3d::Free);
+    }
+}
+
+/// The named [`ViewportProfile`]s that [`PixelCameraProfiles::apply_profile`] can switch to.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// App::new().insert_resource(
+///     ViewportProfiles::default()
+///         .with(
+///             "quality",
+///             ViewportProfile {
+///                 viewport_size: ViewportSize::PixelFixed(2),
+///                 smoothing: true,
+///             },
+///         )
+///         .with(
+///             "performance",
+///             ViewportProfile {
+///                 viewport_size: ViewportSize::PixelFixed(6),
+///                 smoothing: false,
+///             },
+///         ),
+/// );
+/// ```
+#[derive(Resource, Clone, Default)]
+pub struct ViewportProfiles {
+    profiles: HashMap<String, ViewportProfile>,
+    active: Option<String>,
+}
+
+impl ViewportProfiles {
+    /// Adds a profile, replacing the one with the same name.
+    pub fn with(mut self, name: impl Into<String>, profile: ViewportProfile) -> Self {
+        self.insert(name, profile);
+        self
+    }
+    /// Adds a profile, replacing the one with the same name.
+    pub fn insert(&mut self, name: impl Into<String>, profile: ViewportProfile) {
+        self.profiles.insert(name.into(), profile);
+    }
+    /// Returns the profile with the given name.
+    pub fn get(&self, name: &str) -> Option<&ViewportProfile> {
+        self.profiles.get(name)
+    }
+    /// Returns the names of all profiles, in no particular order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.profiles.keys().map(String::as_str)
+    }
+    /// Returns the name of the profile that was applied last.
+    pub fn active(&self) -> Option<&str> {
+        self.active.as_deref()
+    }
+}
+
+/// The error returned by [`PixelCameraProfiles::apply_profile`] when there is no profile
+/// with the given name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownProfile(pub String);
+
+impl fmt::Display for UnknownProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "there is no viewport profile named {:?}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownProfile {}
+
+/// A [`SystemParam`] for applying [`ViewportProfiles`] to every [`PixelCamera`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn on_settings_changed(mut profiles: PixelCameraProfiles) {
+///     if let Err(error) = profiles.apply_profile("performance") {
+///         warn!("{error}");
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct PixelCameraProfiles<'w, 's> {
+    profiles: ResMut<'w, ViewportProfiles>,
+    cameras: Query<'w, 's, (&'static mut PixelCamera, Option<&'static PixelCamera3d>)>,
+}
+
+impl PixelCameraProfiles<'_, '_> {
+    /// Applies the profile with the given name to every [`PixelCamera`].
+    ///
+    /// All cameras change in the same frame, and their render targets are resized (or
+    /// recreated) to match before the next frame is rendered.
+    /// A [`DynamicResolution`](crate::dynamic_resolution::DynamicResolution) keeps changing
+    /// the pixel scale afterwards.
+    pub fn apply_profile(&mut self, name: &str) -> Result<(), UnknownProfile> {
+        let profile = self
+            .profiles
+            .get(name)
+            .ok_or_else(|| UnknownProfile(name.to_owned()))?;
+        for (mut camera, camera_3d) in &mut self.cameras {
+            profile.apply(&mut camera, camera_3d);
+        }
+        self.profiles.active = Some(name.to_owned());
+        Ok(())
+    }
+    /// Returns the [`ViewportProfiles`].
+    pub fn profiles(&self) -> &ViewportProfiles {
+        &self.profiles
+    }
+}
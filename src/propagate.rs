@@ -0,0 +1,72 @@
+//! Copying components from a [`PixelCamera`] to the entities spawned for its viewport.
+
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+
+use crate::components::{PixelCamera, PixelViewportReferences};
+use crate::CameraSystems;
+
+/// Copies the component `C` from every [`PixelCamera`] to its viewport sprite and viewport camera,
+/// and keeps the copies up to date.
+///
+/// This ties the spawned entities to whatever `C` stands for. For example, with a marker
+/// component that your game uses to despawn everything that belongs to a level or state,
+/// the viewport is cleaned up together with the camera:
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::propagate::PropagateToViewportPlugin;
+/// #[derive(Component, Clone)]
+/// struct DespawnOnExit;
+///
+/// App::new().add_plugins(PropagateToViewportPlugin::<DespawnOnExit>::default());
+/// ```
+pub struct PropagateToViewportPlugin<C>(PhantomData<C>);
+
+impl<C> Default for PropagateToViewportPlugin<C> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<C: Component + Clone> Plugin for PropagateToViewportPlugin<C> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            propagate_to_viewport::<C>
+                .after(CameraSystems::Initialization)
+                .before(CameraSystems::Update),
+        );
+    }
+}
+
+fn propagate_to_viewport<C: Component + Clone>(
+    cameras: Query<(Ref<C>, Ref<PixelViewportReferences>), With<PixelCamera>>,
+    viewports: Query<&PixelViewportReferences, With<PixelCamera>>,
+    mut removed: RemovedComponents<C>,
+    mut commands: Commands,
+) {
+    for (component, viewport) in &cameras {
+        // The viewport references change when the viewport is (re)spawned.
+        if !component.is_changed() && !viewport.is_changed() {
+            continue;
+        }
+        for entity in [viewport.sprite, viewport.camera] {
+            if let Some(mut entity) = commands.get_entity(entity) {
+                entity.insert(component.clone());
+            }
+        }
+    }
+
+    for camera in removed.read() {
+        let Ok(viewport) = viewports.get(camera) else {
+            continue;
+        };
+        for entity in [viewport.sprite, viewport.camera] {
+            if let Some(mut entity) = commands.get_entity(entity) {
+                entity.remove::<C>();
+            }
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! PS1 style vertex snapping and affine texture mapping for 3D [`PixelCamera`]s.
+//!
+//! [`Ps1Material`] is an unlit, textured material that snaps its vertices to the texel grid of
+//! the camera that renders it (the low resolution target of a pixel camera), and can map its
+//! texture without perspective correction. Together they give the typical PS1 wobble.
+
+use bevy::pbr::{MaterialPipeline, MaterialPipelineKey};
+use bevy::prelude::*;
+use bevy::render::mesh::MeshVertexBufferLayout;
+use bevy::render::render_resource::{
+    AsBindGroup, RenderPipelineDescriptor, ShaderRef, SpecializedMeshPipelineError,
+};
+use bevy::render::view::RenderLayers;
+
+use crate::components::{PixelCamera, PixelCamera3d};
+
+const PS1_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(0x5d21_a8f3_0b6e_4c97_8e12_c4f0_9a73_6b58);
+
+/// Adds [`Ps1Material`] and keeps it in sync with the [`Ps1Camera`] settings.
+pub struct Ps1Plugin;
+impl Plugin for Ps1Plugin {
+    fn build(&self, app: &mut App) {
+        bevy::asset::load_internal_asset!(app, PS1_SHADER_HANDLE, "ps1.wgsl", Shader::from_wgsl);
+
+        app.add_plugins(MaterialPlugin::<Ps1Material> {
+            // The prepass wouldn't snap the vertices, so it would disagree with the main pass.
+            prepass_enabled: false,
+            ..default()
+        })
+        .add_systems(PostUpdate, sync_ps1_materials);
+    }
+}
+
+/// The PS1 settings of a 3D pixel camera.
+///
+/// Add this next to a [`PixelCamera3d`]. The settings are applied to every [`Ps1Material`]
+/// the camera renders. Materials that are rendered by several cameras use the settings of
+/// one of them, so give each camera its own materials if their settings differ.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ps1Camera {
+    /// Snaps vertices to the texel grid of the camera's render target.
+    pub vertex_snapping: bool,
+    /// Maps textures without perspective correction.
+    pub affine_textures: bool,
+}
+
+impl Default for Ps1Camera {
+    fn default() -> Self {
+        Self {
+            vertex_snapping: true,
+            affine_textures: true,
+        }
+    }
+}
+
+/// An unlit, textured material with PS1 style vertex snapping and affine texture mapping.
+///
+/// The snapping and texture mapping are set by the [`Ps1Camera`] that renders the material.
+/// The material has no prepass, so it doesn't show up in the depth and normal prepasses
+/// (e.g. for [`PixelOutline`](crate::outline::PixelOutline)).
+#[derive(Asset, AsBindGroup, TypePath, Debug, Clone)]
+#[bind_group_data(Ps1MaterialKey)]
+pub struct Ps1Material {
+    /// The color the texture is multiplied with.
+    #[uniform(0)]
+    pub color: Color,
+    /// The texture of the material, or white if [`None`].
+    #[texture(1)]
+    #[sampler(2)]
+    pub texture: Option<Handle<Image>>,
+    /// Whether vertices are snapped to the texel grid, set by [`Ps1Camera`].
+    pub vertex_snapping: bool,
+    /// Whether textures are mapped without perspective correction, set by [`Ps1Camera`].
+    pub affine_textures: bool,
+}
+
+impl Default for Ps1Material {
+    fn default() -> Self {
+        Self {
+            color: Color::WHITE,
+            texture: None,
+            vertex_snapping: true,
+            affine_textures: true,
+        }
+    }
+}
+
+/// The shader defs of a [`Ps1Material`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ps1MaterialKey {
+    vertex_snapping: bool,
+    affine_textures: bool,
+}
+
+impl From<&Ps1Material> for Ps1MaterialKey {
+    fn from(material: &Ps1Material) -> Self {
+        Self {
+            vertex_snapping: material.vertex_snapping,
+            affine_textures: material.affine_textures,
+        }
+    }
+}
+
+impl Material for Ps1Material {
+    fn vertex_shader() -> ShaderRef {
+        PS1_SHADER_HANDLE.into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        PS1_SHADER_HANDLE.into()
+    }
+
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayout,
+        key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        descriptor.vertex.buffers = vec![layout.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            Mesh::ATTRIBUTE_UV_0.at_shader_location(1),
+        ])?];
+
+        let mut shader_defs = Vec::new();
+        if key.bind_group_data.vertex_snapping {
+            shader_defs.push("VERTEX_SNAPPING".into());
+        }
+        if key.bind_group_data.affine_textures {
+            shader_defs.push("AFFINE_TEXTURES".into());
+        }
+        descriptor
+            .vertex
+            .shader_defs
+            .extend(shader_defs.iter().cloned());
+        if let Some(fragment) = &mut descriptor.fragment {
+            fragment.shader_defs.extend(shader_defs);
+        }
+
+        Ok(())
+    }
+}
+
+/// Applies the [`Ps1Camera`] settings to the materials on the camera's render layers.
+#[allow(clippy::type_complexity)]
+fn sync_ps1_materials(
+    cameras: Query<(&Ps1Camera, Option<&RenderLayers>), (With<PixelCamera>, With<PixelCamera3d>)>,
+    meshes: Query<(&Handle<Ps1Material>, Option<&RenderLayers>)>,
+    mut materials: ResMut<Assets<Ps1Material>>,
+) {
+    for (settings, camera_layers) in &cameras {
+        let camera_layers = camera_layers.copied().unwrap_or_default();
+
+        for (handle, layers) in &meshes {
+            if !layers
+                .copied()
+                .unwrap_or_default()
+                .intersects(&camera_layers)
+            {
+                continue;
+            }
+            // Only take a mutable borrow if something changed, so the material isn't re-uploaded.
+            let Some(material) = materials.get(handle) else {
+                continue;
+            };
+            if material.vertex_snapping == settings.vertex_snapping
+                && material.affine_textures == settings.affine_textures
+            {
+                continue;
+            }
+            if let Some(material) = materials.get_mut(handle) {
+                material.vertex_snapping = settings.vertex_snapping;
+                material.affine_textures = settings.affine_textures;
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+#import bevy_pbr::{
+    mesh_functions,
+    mesh_view_bindings::view,
+    view_transformations::position_world_to_clip,
+}
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(1) uv: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+#ifdef AFFINE_TEXTURES
+    // Interpolated in screen space, without perspective correction.
+    @location(0) @interpolate(linear) uv: vec2<f32>,
+#else
+    @location(0) uv: vec2<f32>,
+#endif
+};
+
+@group(2) @binding(0) var<uniform> color: vec4<f32>;
+@group(2) @binding(1) var base_texture: texture_2d<f32>;
+@group(2) @binding(2) var base_sampler: sampler;
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    let model = mesh_functions::get_model_matrix(vertex.instance_index);
+    let world_position = mesh_functions::mesh_position_local_to_world(model, vec4<f32>(vertex.position, 1.0));
+    var clip = position_world_to_clip(world_position.xyz);
+
+#ifdef VERTEX_SNAPPING
+    // The viewport is the low resolution target, so this snaps to its texels.
+    let half_size = view.viewport.zw / 2.0;
+    let snapped = round(clip.xy / clip.w * half_size) / half_size;
+    clip = vec4<f32>(snapped * clip.w, clip.zw);
+#endif
+
+    var out: VertexOutput;
+    out.position = clip;
+    out.uv = vertex.uv;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(base_texture, base_sampler, in.uv) * color;
+}
@@ -0,0 +1,244 @@
+//! Reading the frames of a [`PixelCamera`] back to the CPU.
+//!
+//! Together with a [`ViewportTarget`](crate::components::ViewportTarget) that points at an
+//! image, this runs the whole pipeline without a window, e.g. on a server that streams
+//! frames or records replays.
+
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Mutex;
+
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+use bevy::render::render_asset::{RenderAssetUsages, RenderAssets};
+use bevy::render::render_graph::{
+    Node, NodeRunError, RenderGraph, RenderGraphContext, RenderLabel,
+};
+use bevy::render::render_resource::*;
+use bevy::render::renderer::{RenderContext, RenderDevice};
+use bevy::render::texture::TextureFormatPixelInfo;
+use bevy::render::{Extract, ExtractSchedule, Render, RenderApp, RenderSet};
+
+use crate::components::{PixelBypass, PixelCamera, PixelViewportReferences};
+
+/// Copies every frame that a [`PixelCamera`] renders to its low resolution target back to
+/// the CPU, and sends it as a [`PixelFrame`] event.
+///
+/// Frames arrive a frame or two after they were rendered. Copying a frame stalls nothing,
+/// but every frame is a new allocation, so only add this to cameras whose frames are needed.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn save_frames(mut frames: EventReader<PixelFrame>) {
+///     for frame in frames.read() {
+///         let size = frame.image.size();
+///         info!("{:?} rendered a {}×{} frame", frame.camera, size.x, size.y);
+///     }
+/// }
+/// ```
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct PixelReadback;
+
+/// A frame of a [`PixelCamera`] with [`PixelReadback`].
+///
+/// The image has the size (including the smoothing margin) and format of the camera's
+/// low resolution target.
+#[derive(Event, Debug, Clone)]
+pub struct PixelFrame {
+    /// The camera that rendered the frame.
+    pub camera: Entity,
+    /// The pixels of the frame.
+    pub image: Image,
+}
+
+/// Reading frames back, added by [`PixelCameraPlugin`](crate::PixelCameraPlugin).
+pub(crate) struct ReadbackPlugin;
+impl Plugin for ReadbackPlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = channel();
+        app.add_event::<PixelFrame>()
+            .insert_resource(FrameReceiver(Mutex::new(receiver)))
+            .add_systems(PreUpdate, receive_frames);
+
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .insert_resource(FrameSender(sender))
+            .init_resource::<ExtractedReadbacks>()
+            .init_resource::<PreparedReadbacks>()
+            .add_systems(ExtractSchedule, extract_readbacks)
+            .add_systems(
+                Render,
+                (
+                    prepare_readbacks.in_set(RenderSet::PrepareResources),
+                    map_readbacks.in_set(RenderSet::Cleanup),
+                ),
+            );
+
+        let mut graph = render_app.world.resource_mut::<RenderGraph>();
+        graph.add_node(ReadbackLabel, ReadbackNode);
+        graph.add_node_edge(bevy::render::graph::CameraDriverLabel, ReadbackLabel);
+    }
+}
+
+#[derive(Resource)]
+struct FrameReceiver(Mutex<Receiver<PixelFrame>>);
+
+#[derive(Resource)]
+struct FrameSender(Sender<PixelFrame>);
+
+/// Sends the frames that were read back since the last frame as events.
+fn receive_frames(receiver: Res<FrameReceiver>, mut frames: EventWriter<PixelFrame>) {
+    let Ok(receiver) = receiver.0.lock() else {
+        return;
+    };
+    frames.send_batch(receiver.try_iter());
+}
+
+#[derive(Resource, Default)]
+struct ExtractedReadbacks(Vec<(Entity, AssetId<Image>)>);
+
+#[allow(clippy::type_complexity)]
+fn extract_readbacks(
+    cameras: Extract<
+        Query<
+            (Entity, &Camera, Option<&PixelViewportReferences>),
+            (With<PixelCamera>, With<PixelReadback>, Without<PixelBypass>),
+        >,
+    >,
+    mut extracted: ResMut<ExtractedReadbacks>,
+) {
+    extracted.0.clear();
+    for (entity, camera, viewport) in &cameras {
+        if !camera.is_active {
+            continue;
+        }
+        // Direct composite cameras don't have a viewport, but render to an image as well.
+        let image = match (viewport, &camera.target) {
+            (Some(viewport), _) => viewport.image.id(),
+            (None, RenderTarget::Image(image)) => image.id(),
+            _ => continue,
+        };
+        extracted.0.push((entity, image));
+    }
+}
+
+struct PreparedReadback {
+    camera: Entity,
+    texture: Texture,
+    buffer: Buffer,
+    size: Extent3d,
+    format: TextureFormat,
+    padded_bytes_per_row: u32,
+}
+
+#[derive(Resource, Default)]
+struct PreparedReadbacks(Vec<PreparedReadback>);
+
+/// Creates the buffers that the frames are copied into.
+fn prepare_readbacks(
+    extracted: Res<ExtractedReadbacks>,
+    images: Res<RenderAssets<Image>>,
+    render_device: Res<RenderDevice>,
+    mut prepared: ResMut<PreparedReadbacks>,
+) {
+    prepared.0.clear();
+    for &(camera, image) in &extracted.0 {
+        let Some(image) = images.get(image) else {
+            continue;
+        };
+        let size = Extent3d {
+            width: image.size.x as u32,
+            height: image.size.y as u32,
+            depth_or_array_layers: 1,
+        };
+        let padded_bytes_per_row = RenderDevice::align_copy_bytes_per_row(
+            size.width as usize * image.texture_format.pixel_size(),
+        ) as u32;
+
+        prepared.0.push(PreparedReadback {
+            camera,
+            texture: image.texture.clone(),
+            buffer: render_device.create_buffer(&BufferDescriptor {
+                label: Some("pixel_readback_buffer"),
+                size: padded_bytes_per_row as u64 * size.height as u64,
+                usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }),
+            size,
+            format: image.texture_format,
+            padded_bytes_per_row,
+        });
+    }
+}
+
+#[derive(RenderLabel, Debug, Hash, PartialEq, Eq, Clone)]
+struct ReadbackLabel;
+
+/// Copies the finished frames into their buffers, after every camera has rendered.
+struct ReadbackNode;
+impl Node for ReadbackNode {
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        for readback in &world.resource::<PreparedReadbacks>().0 {
+            render_context.command_encoder().copy_texture_to_buffer(
+                readback.texture.as_image_copy(),
+                ImageCopyBuffer {
+                    buffer: &readback.buffer,
+                    layout: ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: Some(readback.padded_bytes_per_row),
+                        rows_per_image: None,
+                    },
+                },
+                readback.size,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Maps the buffers once the copies are submitted, and sends the frames to the main world
+/// once they're mapped.
+fn map_readbacks(mut prepared: ResMut<PreparedReadbacks>, sender: Res<FrameSender>) {
+    for readback in prepared.0.drain(..) {
+        let sender = sender.0.clone();
+        let buffer = readback.buffer.clone();
+        // The device is polled every frame when the render queue is submitted.
+        readback
+            .buffer
+            .slice(..)
+            .map_async(MapMode::Read, move |result| {
+                if result.is_err() {
+                    return;
+                }
+                let row_bytes = readback.size.width as usize * readback.format.pixel_size();
+                let data: Vec<u8> = buffer
+                    .slice(..)
+                    .get_mapped_range()
+                    .chunks(readback.padded_bytes_per_row as usize)
+                    .flat_map(|row| &row[..row_bytes])
+                    .copied()
+                    .collect();
+                buffer.unmap();
+
+                // The receiver is only gone when the app is shutting down.
+                let _ = sender.send(PixelFrame {
+                    camera: readback.camera,
+                    image: Image::new(
+                        readback.size,
+                        TextureDimension::D2,
+                        data,
+                        readback.format,
+                        RenderAssetUsages::default(),
+                    ),
+                });
+            });
+    }
+}
@@ -0,0 +1,107 @@
+//! Rendering the entities of other crates (e.g. particle effects) into the low resolution world.
+//!
+//! Renderers draw an entity with every camera that shares its render layers. Entities on the
+//! layer of a [`PixelCamera`]'s viewport are drawn at native resolution on top of the viewport,
+//! and entities that don't share a layer with the world camera aren't drawn into the
+//! low resolution target at all.
+
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
+
+use crate::components::PixelCamera;
+use crate::CameraSystems;
+
+/// Keeps entities with the component `T` (e.g. a particle crate's effect component) and
+/// [`PixelLayers`] on the render layers of their [`PixelCamera`], and warns about entities with
+/// `T` that end up on a viewport layer.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::renderers::{PixelLayers, PixelRendererPlugin};
+/// #[derive(Component)]
+/// struct ParticleEffect;
+///
+/// fn spawn_effect(mut commands: Commands) {
+///     commands.spawn((ParticleEffect, PixelLayers::default()));
+/// }
+///
+/// App::new()
+///     .add_plugins(PixelRendererPlugin::<ParticleEffect>::default())
+///     .add_systems(Startup, spawn_effect);
+/// ```
+pub struct PixelRendererPlugin<T>(PhantomData<T>);
+
+impl<T> Default for PixelRendererPlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: Component> Plugin for PixelRendererPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PixelLayers>().add_systems(
+            PostUpdate,
+            (sync_layers::<T>, check_layers::<T>)
+                .chain()
+                .after(CameraSystems::Initialization),
+        );
+    }
+}
+
+/// Renders an entity with a [`PixelCamera`], so that it's pixelated, snapped and smoothed
+/// along with the rest of the world. Requires a [`PixelRendererPlugin`] for one of the
+/// entity's components.
+///
+/// The entity's [`RenderLayers`] are kept in sync with the camera's.
+#[derive(Component, Reflect, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub struct PixelLayers {
+    /// The entity of the [`PixelCamera`] that renders the entity.
+    ///
+    /// If [`None`], the only [`PixelCamera`] in the world is used.
+    pub camera: Option<Entity>,
+}
+
+fn sync_layers<T: Component>(
+    entities: Query<(Entity, &PixelLayers, Option<&RenderLayers>), With<T>>,
+    cameras: Query<Option<&RenderLayers>, With<PixelCamera>>,
+    mut commands: Commands,
+) {
+    for (entity, pixel_layers, layers) in &entities {
+        let camera = match pixel_layers.camera {
+            Some(camera) => cameras.get(camera).ok(),
+            None => cameras.get_single().ok(),
+        };
+        let Some(world_layer) = camera else {
+            warn_once!("PixelLayers on {entity:?} doesn't point to a single PixelCamera");
+            continue;
+        };
+
+        let world_layer = world_layer.copied().unwrap_or_default();
+        if layers.copied().unwrap_or_default() != world_layer {
+            commands.entity(entity).insert(world_layer);
+        }
+    }
+}
+
+/// Warns about entities that would be drawn by a viewport camera.
+#[allow(clippy::type_complexity)]
+fn check_layers<T: Component>(
+    entities: Query<(Entity, Option<&RenderLayers>), (Added<T>, Without<PixelLayers>)>,
+    cameras: Query<&PixelCamera>,
+) {
+    for (entity, layers) in &entities {
+        let layers = layers.copied().unwrap_or_default();
+        if cameras
+            .iter()
+            .any(|camera| camera.viewport_layer.intersects(&layers))
+        {
+            warn!(
+                "{} {entity:?} is on the render layer of a PixelCamera's viewport and will be drawn at native resolution. Add `PixelLayers` to render it with the pixel camera.",
+                std::any::type_name::<T>()
+            );
+        }
+    }
+}
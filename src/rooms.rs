@@ -0,0 +1,168 @@
+//! Locking a [`PixelCamera`] to the room that its follow target is in.
+
+use bevy::prelude::*;
+use bevy::transform::helper::TransformHelper;
+
+use crate::bounds::CameraBounds;
+use crate::components::PixelCamera;
+use crate::follow::PixelCameraFollow;
+
+/// A rectangular room in the world, in world units.
+///
+/// Cameras with [`PixelCameraRooms`] are kept inside the room that their
+/// [`PixelCameraFollow::target`] is in.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct CameraRoom(pub Rect);
+
+/// How a camera with [`PixelCameraRooms`] moves to the next room.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+pub enum RoomTransition {
+    /// Jump straight to the next room.
+    Cut,
+    /// Pan over to the next room over the given number of seconds.
+    Pan(f32),
+}
+
+/// Sets the [`CameraBounds`] of a [`PixelCamera`] to the [`CameraRoom`] that its
+/// [`PixelCameraFollow::target`] is in, like the rooms of classic adventure games.
+///
+/// When the target enters another room, the bounds move over to it with the [`RoomTransition`].
+/// While the target is outside of every room, the camera stays in the last room.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn setup(mut commands: Commands) {
+///     commands.spawn(CameraRoom(Rect::new(0.0, 0.0, 256.0, 144.0)));
+///     commands.spawn(CameraRoom(Rect::new(256.0, 0.0, 512.0, 144.0)));
+///
+///     let player = commands.spawn(SpriteBundle::default()).id();
+///     commands.spawn((
+///         Camera2dBundle::default(),
+///         PixelCamera::default(),
+///         PixelCameraFollow::new(player),
+///         PixelCameraRooms::new(RoomTransition::Pan(0.5)),
+///     ));
+/// }
+/// ```
+#[derive(Component, Debug, Clone)]
+pub struct PixelCameraRooms {
+    /// How the camera moves to the next room.
+    pub transition: RoomTransition,
+    room: Option<Entity>,
+    from: Option<Rect>,
+    to: Option<Rect>,
+    elapsed: f32,
+}
+
+impl Default for PixelCameraRooms {
+    fn default() -> Self {
+        Self::new(RoomTransition::Pan(0.5))
+    }
+}
+
+impl PixelCameraRooms {
+    /// Creates a new [`PixelCameraRooms`] that moves between rooms with the `transition`.
+    pub fn new(transition: RoomTransition) -> Self {
+        Self {
+            transition,
+            room: None,
+            from: None,
+            to: None,
+            elapsed: 0.0,
+        }
+    }
+    /// Returns the room the camera is moving to (or is in).
+    pub fn room(&self) -> Option<Entity> {
+        self.room
+    }
+    /// Returns whether the camera is moving to another room.
+    pub fn is_transitioning(&self) -> bool {
+        self.to.is_some()
+    }
+}
+
+/// Starts a transition when the follow target of a camera enters another room.
+pub(crate) fn enter_rooms(
+    mut cameras: Query<
+        (
+            &mut PixelCameraRooms,
+            &PixelCameraFollow,
+            Option<&CameraBounds>,
+        ),
+        With<PixelCamera>,
+    >,
+    rooms: Query<(Entity, &CameraRoom)>,
+    transforms: TransformHelper,
+) {
+    for (mut camera_rooms, follow, bounds) in &mut cameras {
+        let Ok(target) = transforms.compute_global_transform(follow.target) else {
+            continue;
+        };
+        let target = target.translation().truncate();
+
+        // Stay in the current room for as long as possible where rooms overlap.
+        let in_room = |room: Entity| {
+            rooms
+                .get(room)
+                .is_ok_and(|(_, CameraRoom(rect))| rect.contains(target))
+        };
+        if camera_rooms.room.is_some_and(in_room) {
+            continue;
+        }
+        let Some((room, CameraRoom(rect))) = rooms.iter().find(|(room, _)| in_room(*room)) else {
+            continue;
+        };
+
+        camera_rooms.room = Some(room);
+        camera_rooms.from = bounds.map(|CameraBounds(bounds)| *bounds);
+        camera_rooms.to = Some(*rect);
+        camera_rooms.elapsed = 0.0;
+    }
+}
+
+/// Moves the [`CameraBounds`] towards the current room.
+pub(crate) fn transition_room_bounds(
+    mut cameras: Query<(Entity, &mut PixelCameraRooms, Option<&mut CameraBounds>)>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (entity, mut camera_rooms, bounds) in &mut cameras {
+        let Some(to) = camera_rooms.to else {
+            continue;
+        };
+        camera_rooms.elapsed += time.delta_seconds();
+
+        let t = match camera_rooms.transition {
+            RoomTransition::Pan(duration) if duration > 0.0 => {
+                (camera_rooms.elapsed / duration).min(1.0)
+            }
+            _ => 1.0,
+        };
+        let rect = match camera_rooms.from {
+            Some(from) => {
+                // Ease in and out, so the camera starts and settles smoothly.
+                let t = t * t * (3.0 - 2.0 * t);
+                Rect {
+                    min: from.min.lerp(to.min, t),
+                    max: from.max.lerp(to.max, t),
+                }
+            }
+            None => to,
+        };
+        if t >= 1.0 {
+            camera_rooms.from = None;
+            camera_rooms.to = None;
+        }
+
+        match bounds {
+            Some(mut bounds) => {
+                bounds.set_if_neq(CameraBounds(rect));
+            }
+            None => {
+                commands.entity(entity).insert(CameraBounds(rect));
+            }
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Saving and loading the state of a [`PixelCamera`].
+//!
+//! [`PixelCamera`] can be saved in scenes through reflection, but it can't be serialized with
+//! `serde` directly ([`ViewportSize::Custom`] holds a function). Its persistent state is mirrored
+//! into a [`PixelCameraSnapshot`] instead, which can be saved with `serde` or with reflection
+//! based tools like `bevy_save`.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::bounds::CameraBounds;
+use crate::components::PixelCamera;
+use crate::follow::PixelCameraFollow;
+use crate::viewport::ViewportSize;
+
+/// The persistent state of a [`PixelCamera`].
+///
+/// Add this component to a [`PixelCamera`] to keep it up to date with the camera, it captures
+/// the camera's state when it's added. Whenever it is changed from outside afterwards (e.g. by
+/// loading a save into it), the camera is updated to match.
+#[derive(Component, Reflect, Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[reflect(Component, Serialize, Deserialize)]
+pub struct PixelCameraSnapshot {
+    /// The [`PixelCamera::subpixel_pos`] of the camera.
+    pub subpixel_pos: Vec2,
+    /// The scale of [`ViewportSize::PixelFixed`], if the camera uses it.
+    pub pixel_scale: Option<u32>,
+    /// The [`CameraBounds`] of the camera, if it has any.
+    pub bounds: Option<Rect>,
+    /// The path of the entity that the camera follows with [`PixelCameraFollow`], if it has one.
+    ///
+    /// Entities get new ids when they're loaded, so the target is found by its path instead:
+    /// the [`Name`]s of its ancestors and itself, joined by `/` (e.g. `"Level 1/Player"`).
+    /// The target needs a name, and so do its ancestors.
+    pub follow_target: Option<String>,
+}
+
+impl PixelCameraSnapshot {
+    /// Captures the state of a camera, `follow_target` is the path of the entity it follows.
+    pub fn capture(
+        camera: &PixelCamera,
+        bounds: Option<&CameraBounds>,
+        follow_target: Option<String>,
+    ) -> Self {
+        Self {
+            subpixel_pos: camera.subpixel_pos,
+            pixel_scale: match camera.viewport_size {
+                ViewportSize::PixelFixed(scale) => Some(scale),
+                _ => None,
+            },
+            bounds: bounds.map(|CameraBounds(bounds)| *bounds),
+            follow_target,
+        }
+    }
+}
+
+/// Returns the path of an entity, the names of its ancestors and itself joined by `/`.
+///
+/// Returns [`None`] if any of them doesn't have a [`Name`].
+fn entity_path(entity: Entity, names: &Query<(Entity, &Name, Option<&Parent>)>) -> Option<String> {
+    let mut segments = Vec::new();
+    let mut current = Some(entity);
+    while let Some(entity) = current {
+        let (_, name, parent) = names.get(entity).ok()?;
+        segments.push(name.as_str());
+        current = parent.map(Parent::get);
+    }
+    segments.reverse();
+    Some(segments.join("/"))
+}
+
+/// Applies snapshots that were changed from outside, then captures the current state.
+///
+/// New snapshots capture the camera instead of being applied. A system doesn't see its own
+/// changes, so the captured snapshots aren't applied again.
+#[allow(clippy::type_complexity)]
+pub(crate) fn sync_snapshots(
+    mut cameras: Query<(
+        Entity,
+        &mut PixelCamera,
+        Option<&mut CameraBounds>,
+        Option<&mut PixelCameraFollow>,
+        &mut PixelCameraSnapshot,
+    )>,
+    names: Query<(Entity, &Name, Option<&Parent>)>,
+    changed_names: Query<(), Or<(Changed<Name>, Changed<Parent>)>>,
+    mut commands: Commands,
+) {
+    let names_changed = !changed_names.is_empty();
+
+    for (entity, mut camera, mut bounds, mut follow, mut snapshot) in &mut cameras {
+        let follow_changed = follow.as_ref().is_some_and(DetectChanges::is_changed);
+        if snapshot.is_changed() && !snapshot.is_added() {
+            camera.subpixel_pos = snapshot.subpixel_pos;
+            if let Some(scale) = snapshot.pixel_scale {
+                camera.viewport_size = ViewportSize::PixelFixed(scale);
+            }
+            match (snapshot.bounds, bounds.as_deref_mut()) {
+                (Some(rect), Some(bounds)) => bounds.0 = rect,
+                (Some(rect), None) => {
+                    commands.entity(entity).insert(CameraBounds(rect));
+                }
+                (None, Some(_)) => {
+                    commands.entity(entity).remove::<CameraBounds>();
+                }
+                (None, None) => {}
+            }
+            match (&snapshot.follow_target, follow.as_deref_mut()) {
+                (Some(path), follow) => {
+                    // The target may not have been loaded (yet), keep following the old one.
+                    let target = names
+                        .iter()
+                        .map(|(target, ..)| target)
+                        .find(|&target| entity_path(target, &names).as_ref() == Some(path));
+                    match (target, follow) {
+                        (Some(target), Some(follow)) => follow.target = target,
+                        (Some(target), None) => {
+                            commands
+                                .entity(entity)
+                                .insert(PixelCameraFollow::new(target));
+                        }
+                        (None, _) => {}
+                    }
+                }
+                (None, Some(_)) => {
+                    commands.entity(entity).remove::<PixelCameraFollow>();
+                }
+                (None, None) => {}
+            }
+            continue;
+        }
+
+        let captured = PixelCameraSnapshot::capture(&camera, bounds.as_deref(), None);
+        if (snapshot.subpixel_pos, snapshot.pixel_scale, snapshot.bounds)
+            != (captured.subpixel_pos, captured.pixel_scale, captured.bounds)
+        {
+            snapshot.subpixel_pos = captured.subpixel_pos;
+            snapshot.pixel_scale = captured.pixel_scale;
+            snapshot.bounds = captured.bounds;
+        }
+
+        // Building the path allocates, so it's only built again when the target or a name on
+        // the way to it may have changed.
+        let follow_target = match follow.as_deref() {
+            Some(follow)
+                if snapshot.is_added()
+                    || follow_changed
+                    || names_changed
+                    || !names.contains(follow.target) =>
+            {
+                entity_path(follow.target, &names)
+            }
+            Some(_) => continue,
+            None => None,
+        };
+        if snapshot.follow_target != follow_target {
+            snapshot.follow_target = follow_target;
+        }
+    }
+}
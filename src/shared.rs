@@ -0,0 +1,123 @@
+//! Rendering more than one camera into the target of a [`PixelCamera`].
+//!
+//! For example a 3D [`PixelCamera`] can render a background, and a 2D camera with
+//! [`SharedPixelTarget`] can draw pixel art characters on top of it. Both end up in the same low
+//! resolution target, which is upscaled (and smoothed) once.
+
+use bevy::ecs::entity::{EntityMapper, MapEntities};
+use bevy::ecs::reflect::ReflectMapEntities;
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+
+use crate::components::{PixelCamera, PixelFreeze};
+
+/// Renders this camera into the render target of another camera's [`PixelCamera`].
+///
+/// The camera has to render after the [`PixelCamera`] and before its viewport, so its
+/// [`Camera::order`] should be between the two. Its [`ClearColorConfig`] is set to
+/// [`ClearColorConfig::None`], so that it draws on top of what the [`PixelCamera`] rendered.
+///
+/// If the [`PixelCamera`] is removed or despawned, the camera renders to the primary window
+/// again, so despawn it together with the [`PixelCamera`] (e.g. as a child).
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn setup(mut commands: Commands) {
+///     let background = commands
+///         .spawn((
+///             Camera3dBundle::default(),
+///             PixelCamera {
+///                 viewport_order: 2,
+///                 ..PixelCamera::from_size(ViewportSize::PixelFixed(4))
+///             },
+///             PixelCamera3d::default(),
+///         ))
+///         .id();
+///     commands.spawn((
+///         Camera2dBundle {
+///             camera: Camera {
+///                 order: 1,
+///                 ..default()
+///             },
+///             ..default()
+///         },
+///         SharedPixelTarget::new(background),
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component, MapEntities)]
+pub struct SharedPixelTarget {
+    /// The entity of the [`PixelCamera`] whose target this camera renders into.
+    pub camera: Entity,
+    /// Whether the [`Transform`] of this camera is moved to the snapped
+    /// [`PixelCamera::subpixel_pos`] of the [`PixelCamera`], so that 2D sprites line up with it.
+    pub follow: bool,
+}
+
+impl SharedPixelTarget {
+    /// Renders into the target of `camera`, following its position.
+    pub fn new(camera: Entity) -> Self {
+        Self {
+            camera,
+            follow: true,
+        }
+    }
+}
+
+impl MapEntities for SharedPixelTarget {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.camera = entity_mapper.map_entity(self.camera);
+    }
+}
+
+/// Points cameras with a [`SharedPixelTarget`] at the current render target of their
+/// [`PixelCamera`].
+#[allow(clippy::type_complexity)]
+pub(crate) fn sync_shared_targets(
+    mut sources: Query<
+        (
+            Entity,
+            &SharedPixelTarget,
+            &mut Camera,
+            Option<&mut Transform>,
+        ),
+        Without<PixelCamera>,
+    >,
+    cameras: Query<(&PixelCamera, &Camera, Has<PixelFreeze>)>,
+) {
+    for (entity, shared, mut camera, transform) in &mut sources {
+        let Ok((pixel_camera, main_camera, frozen)) = cameras.get(shared.camera) else {
+            warn_once!("SharedPixelTarget on {entity:?} doesn't point to a PixelCamera");
+            // Don't keep the target image of a removed pixel camera alive.
+            if matches!(camera.target, RenderTarget::Image(_)) {
+                camera.target = RenderTarget::default();
+            }
+            continue;
+        };
+        // The target is an image once the pixel camera is initialized.
+        let RenderTarget::Image(image) = &main_camera.target else {
+            continue;
+        };
+
+        if !matches!(&camera.target, RenderTarget::Image(current) if current == image) {
+            camera.target = RenderTarget::Image(image.clone());
+        }
+        if !matches!(camera.clear_color, ClearColorConfig::None) {
+            camera.clear_color = ClearColorConfig::None;
+        }
+        if camera.order <= main_camera.order || camera.order >= pixel_camera.viewport_order {
+            warn_once!("SharedPixelTarget on {entity:?} should render after its PixelCamera and before the viewport camera");
+        }
+
+        // Frozen cameras stay where they are, and so do the cameras that follow them.
+        if let (true, false, Some(mut transform)) = (shared.follow, frozen, transform) {
+            let snapped = pixel_camera.subpixel_pos.trunc();
+            if transform.translation.truncate() != snapped {
+                transform.translation.x = snapped.x;
+                transform.translation.y = snapped.y;
+            }
+        }
+    }
+}
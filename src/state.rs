@@ -0,0 +1,143 @@
+//! Activating pixel cameras with the [`States`] of the app.
+
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+
+use crate::components::{
+    PixelCamera, PixelViewportReferences, Suspended, TargetClosed, ViewportCamera,
+};
+use crate::CameraSystems;
+
+/// Activates and deactivates cameras with an [`ActiveInState<S>`] when the state `S` changes.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// #[derive(States, Default, Debug, Clone, PartialEq, Eq, Hash)]
+/// enum GameState {
+///     #[default]
+///     Menu,
+///     Playing,
+/// }
+///
+/// fn setup(mut commands: Commands) {
+///     commands.spawn((
+///         Camera2dBundle::default(),
+///         PixelCamera::default(),
+///         ActiveInState {
+///             state: GameState::Playing,
+///             on_exit: StateExit::Release,
+///         },
+///     ));
+/// }
+///
+/// App::new()
+///     .init_state::<GameState>()
+///     .add_plugins(PixelCameraStatePlugin::<GameState>::default())
+///     .add_systems(Startup, setup);
+/// ```
+pub struct PixelCameraStatePlugin<S>(PhantomData<S>);
+
+impl<S> Default for PixelCameraStatePlugin<S> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<S: States> Plugin for PixelCameraStatePlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            apply_camera_states::<S>.before(CameraSystems::Initialization),
+        );
+    }
+}
+
+/// Binds a camera to a value of the state `S`.
+///
+/// The camera is active while the state has that value, and is deactivated in the way
+/// set by [`ActiveInState::on_exit`] when it has any other value.
+/// Requires [`PixelCameraStatePlugin<S>`].
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct ActiveInState<S: States> {
+    /// The state in which the camera is active.
+    pub state: S,
+    /// What happens to the camera while the state has any other value.
+    pub on_exit: StateExit,
+}
+
+/// What happens to a camera with [`ActiveInState`] when its state is left.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StateExit {
+    /// The camera and its viewport are deactivated, but keep their render target.
+    #[default]
+    Deactivate,
+    /// The camera is deactivated, and its viewport entities and render target are freed.
+    /// The camera is initialized again when the state is entered again.
+    Release,
+    /// The camera is despawned, along with its children.
+    Despawn,
+}
+
+/// Applies the state of cameras with [`ActiveInState`] when the state or the component changes.
+#[allow(clippy::type_complexity)]
+fn apply_camera_states<S: States>(
+    state: Res<State<S>>,
+    mut cameras: Query<
+        (
+            Entity,
+            Ref<ActiveInState<S>>,
+            &mut Camera,
+            Option<&PixelViewportReferences>,
+            Option<&mut TargetClosed>,
+            Has<Suspended>,
+        ),
+        (With<PixelCamera>, Without<ViewportCamera>),
+    >,
+    viewport_cameras: Query<&Camera, With<ViewportCamera>>,
+    mut commands: Commands,
+) {
+    for (entity, binding, mut camera, viewport, closed, suspended) in &mut cameras {
+        if !state.is_changed() && !binding.is_changed() {
+            continue;
+        }
+
+        if *state.get() == binding.state {
+            if suspended {
+                // Initializes the camera again.
+                commands.entity(entity).remove::<Suspended>();
+            }
+            // A camera whose window was closed is activated once the window exists again.
+            match closed {
+                Some(mut closed) => closed.reactivate = true,
+                None => camera.is_active = true,
+            }
+            continue;
+        }
+
+        if let Some(mut closed) = closed {
+            closed.reactivate = false;
+        }
+        match binding.on_exit {
+            StateExit::Deactivate => camera.is_active = false,
+            StateExit::Release => {
+                camera.is_active = false;
+                if let Some(viewport) = viewport {
+                    // Let go of the render target, the viewport entities are despawned
+                    // once they no longer belong to the camera.
+                    if let Ok(viewport_camera) = viewport_cameras.get(viewport.camera) {
+                        camera.target = viewport_camera.target.clone();
+                    }
+                    commands
+                        .entity(entity)
+                        .remove::<(PixelViewportReferences, TargetClosed)>();
+                }
+                if !suspended {
+                    commands.entity(entity).insert(Suspended);
+                }
+            }
+            StateExit::Despawn => commands.entity(entity).despawn_recursive(),
+        }
+    }
+}
@@ -1,285 +1,1996 @@
+use bevy::ecs::query::QueryEntityError;
 use bevy::prelude::*;
-use bevy::render::camera::{RenderTarget, ScalingMode};
+use bevy::render::camera::{RenderTarget, ScalingMode, Viewport};
+use bevy::render::primitives::{Frustum, HalfSpace};
+use bevy::render::render_asset::RenderAssetUsages;
 use bevy::render::render_resource::*;
+use bevy::render::renderer::{RenderAdapter, RenderAdapterInfo, RenderDevice};
+use bevy::render::texture::TextureFormatPixelInfo;
 use bevy::render::view::RenderLayers;
-use bevy::window::{PrimaryWindow, WindowRef};
+use bevy::utils::HashMap;
+use bevy::window::{
+    ApplicationLifetime, PrimaryWindow, WindowBackendScaleFactorChanged, WindowRef,
+    WindowResolution,
+};
 
 use crate::components::*;
+use crate::composite::{DirectComposite, DirectCompositeTarget};
+use crate::error::{report, PixelCameraError, PixelCameraErrorKind};
+use crate::high_res::{same_viewport, OVERLAY_ORDERS};
 use crate::prelude::ViewportSize;
 use crate::viewport::FitMode;
+use crate::PixelCameraConfig;
 
+/// Run condition for [`init_camera`], so that it only claims mutable access to
+/// [`Assets<Image>`] when a camera may need to be initialized.
+#[allow(clippy::type_complexity)]
+pub(crate) fn cameras_to_initialize(
+    cameras: Query<
+        (),
+        (
+            With<PixelCamera>,
+            Without<PixelViewportReferences>,
+            Without<ViewportCamera>,
+            Without<DirectComposite>,
+            Without<CustomViewport>,
+            Without<Suspended>,
+            Or<(Changed<PixelCamera>, Changed<Camera>, Changed<RenderLayers>)>,
+        ),
+    >,
+    added_windows: Query<(), Added<Window>>,
+) -> bool {
+    !cameras.is_empty() || !added_windows.is_empty()
+}
+
+/// Initializes newly added [`PixelCamera`]s.
+///
+/// Cameras that fail validation are skipped and retried whenever their
+/// [`PixelCamera`], [`Camera`] or [`RenderLayers`] change, or a window is added.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 pub(crate) fn init_camera(
     mut query: Query<
-        (&PixelCamera, &mut Camera, Option<&RenderLayers>, Entity),
-        Added<PixelCamera>,
+        (
+            &mut PixelCamera,
+            &mut Camera,
+            Option<Ref<RenderLayers>>,
+            Option<&PixelCamera3d>,
+            Option<&ViewportTarget>,
+            Has<InheritedVisibility>,
+            Entity,
+        ),
+        (
+            Without<PixelViewportReferences>,
+            Without<ViewportCamera>,
+            Without<DirectComposite>,
+            Without<CustomViewport>,
+            Without<Suspended>,
+        ),
+    >,
+    initialized: Query<
+        (
+            &PixelCamera,
+            Option<&RenderLayers>,
+            &PixelViewportReferences,
+        ),
+        Without<CustomViewport>,
     >,
-    window_query: Query<&Window>,
+    viewport_cameras: Query<&Camera, With<ViewportCamera>>,
+    windows: Query<&Window>,
+    added_windows: Query<(), Added<Window>>,
+    primary_window: Query<(Entity, &Window), With<PrimaryWindow>>,
+    config: Res<PixelCameraConfig>,
+    render_device: Option<Res<RenderDevice>>,
     mut images: ResMut<Assets<Image>>,
+    mut pool: ResMut<TargetImagePool>,
     mut commands: Commands,
+    mut errors: EventWriter<PixelCameraError>,
 ) {
-    let window = window_query.single();
+    // The layers and the window and order of the viewport cameras of the other pixel cameras,
+    // including the ones initialized below.
+    let primary = primary_window.get_single().ok().map(|(entity, _)| entity);
+    let mut taken_layers = RenderLayers::none();
+    let mut taken_orders = Vec::new();
+    for (pixel_camera, world_layer, viewport) in &initialized {
+        taken_layers = layer_union(&taken_layers, &pixel_camera.viewport_layer);
+        taken_layers = layer_union(&taken_layers, &world_layer.copied().unwrap_or_default());
+        if let Ok(viewport_camera) = viewport_cameras.get(viewport.camera) {
+            taken_orders.push((
+                viewport_camera.target.normalize(primary),
+                viewport_camera.order,
+            ));
+        }
+    }
 
     for (
-        PixelCamera {
-            viewport_order,
-            viewport_size,
-            viewport_layer,
-            smoothing,
-            ..
-        },
+        mut pixel_camera,
         mut camera,
         world_layer,
+        camera_3d,
+        viewport_target,
+        has_visibility,
         entity,
     ) in &mut query
     {
-        if let Some(world_layer) = world_layer {
-            if world_layer.intersects(viewport_layer) {
-                error!("The render layers of the world intersect with the render layers of the viewport camera");
-                return;
+        // A new window may be the one that a camera was missing.
+        let changed = pixel_camera.is_changed()
+            || camera.is_changed()
+            || world_layer.as_ref().is_some_and(DetectChanges::is_changed);
+        if !changed && added_windows.is_empty() {
+            continue;
+        }
+        let world_layer = world_layer.as_deref();
+
+        // The viewport is shown on its `ViewportTarget`, on the window the camera was rendering
+        // to, or on the primary window if it was rendering to something else (e.g. an image).
+        let output = match (viewport_target, &camera.target) {
+            (Some(ViewportTarget(target)), _) => target.clone(),
+            (None, &RenderTarget::Window(window_ref)) => RenderTarget::Window(window_ref),
+            (None, _) => RenderTarget::Window(WindowRef::Primary),
+        };
+        let resolution = match &output {
+            RenderTarget::Window(window_ref) => {
+                let window = match window_ref {
+                    WindowRef::Primary => {
+                        primary_window.get_single().ok().map(|(_, window)| window)
+                    }
+                    &WindowRef::Entity(window_entity) => windows.get(window_entity).ok(),
+                };
+                let Some(window) = window else {
+                    let window_entity = match window_ref {
+                        WindowRef::Primary => None,
+                        &WindowRef::Entity(window_entity) => Some(window_entity),
+                    };
+                    report(
+                        &mut errors,
+                        entity,
+                        PixelCameraErrorKind::MissingWindow(window_entity),
+                    );
+                    continue;
+                };
+                sizing_resolution(window, &config)
+            }
+            // Without a window (e.g. on a headless server), the viewport is sized for its image.
+            RenderTarget::Image(image) => {
+                let Some(size) = images.get(image).map(|image| image.size()) else {
+                    report(&mut errors, entity, PixelCameraErrorKind::MissingImage);
+                    continue;
+                };
+                WindowResolution::new(size.x.max(1) as f32, size.y.max(1) as f32)
+            }
+            RenderTarget::TextureView(_) => {
+                report(&mut errors, entity, PixelCameraErrorKind::UnsupportedTarget);
+                continue;
+            }
+        };
+        // Split-screen cameras only take up their `Camera::viewport` of the window.
+        let resolution = sub_viewport_resolution(resolution, camera.viewport.as_ref());
+
+        if let Err(kind) = validate_layers(world_layer, &pixel_camera.viewport_layer) {
+            match free_layer(world_layer) {
+                Some(layer) if config.auto_resolve_layers => {
+                    warn!("PixelCamera {entity:?}: The viewport layer conflicts with the world layer, using {layer:?} instead");
+                    pixel_camera.viewport_layer = layer;
+                }
+                _ => {
+                    report(&mut errors, entity, kind);
+                    continue;
+                }
+            }
+        }
+
+        if camera.order >= pixel_camera.viewport_order {
+            if config.auto_fix_order {
+                let order = camera.order + 1;
+                warn!("PixelCamera {entity:?}: The camera renders later or at the same time as the viewport camera, using viewport order {order} instead");
+                pixel_camera.viewport_order = order;
+            } else {
+                report(&mut errors, entity, PixelCameraErrorKind::BadOrder);
+                continue;
+            }
+        }
+
+        // Every viewport needs a layer of its own, or the viewport cameras would also render
+        // the viewport sprites of the other pixel cameras.
+        if taken_layers.intersects(&pixel_camera.viewport_layer) {
+            let own_layer = world_layer.copied().unwrap_or_default();
+            let Some(layer) = free_layer(Some(&layer_union(&taken_layers, &own_layer))) else {
+                report(&mut errors, entity, PixelCameraErrorKind::LayerConflict);
+                continue;
+            };
+            info!("PixelCamera {entity:?}: The viewport layer is used by another PixelCamera, using {layer:?} instead");
+            pixel_camera.viewport_layer = layer;
+        }
+        // Viewport cameras that share a window need distinct orders, so that bevy knows which
+        // one to render first. The orders of their overlay cameras are kept free as well.
+        let output_key = output.normalize(primary);
+        let order_taken = |order: isize| {
+            taken_orders.iter().any(|(key, taken): &(_, isize)| {
+                *key == output_key && (order - taken).abs() <= OVERLAY_ORDERS
+            })
+        };
+        if order_taken(pixel_camera.viewport_order) {
+            let mut order = pixel_camera.viewport_order;
+            while order_taken(order) {
+                order += 1;
+            }
+            info!("PixelCamera {entity:?}: The viewport order (or an order of its overlays) is used by another PixelCamera on the same window, using {order} instead");
+            pixel_camera.viewport_order = order;
+        }
+
+        if camera_3d == Some(&PixelCamera3d::Free) && pixel_camera.smoothing {
+            warn!("PixelCamera {entity:?}: Smoothing isn't supported for free 3D cameras, turning it off");
+            pixel_camera.smoothing = false;
+        }
+
+        let PixelCamera {
+            viewport_layer,
+            target_format,
+            target_usage,
+            ..
+        } = &*pixel_camera;
+
+        let size = target_size(
+            &pixel_camera,
+            &resolution,
+            render_device.as_deref(),
+            &mut errors,
+            entity,
+        );
+
+        // This is the texture that will be rendered to.
+        let (image_handle, owned_image) = initial_target_image(
+            &camera.target,
+            &mut images,
+            &mut pool,
+            size,
+            *target_format,
+            *target_usage,
+        );
+
+        camera.target = RenderTarget::Image(image_handle.clone());
+        // The viewport camera shows the viewport on the part of the window that the camera
+        // would've rendered to, the camera itself renders to all of the image.
+        let sub_viewport = camera.viewport.take();
+
+        let viewport_sprite =
+            spawn_viewport_sprite(&mut commands, entity, image_handle.clone(), viewport_layer);
+        let viewport_camera = spawn_viewport_camera(
+            &mut commands,
+            entity,
+            &pixel_camera,
+            size,
+            output.clone(),
+            sub_viewport.clone(),
+        );
+        taken_layers = layer_union(&taken_layers, &pixel_camera.viewport_layer);
+        taken_orders.push((output_key, pixel_camera.viewport_order));
+
+        commands.entity(entity).insert(PixelViewportReferences {
+            sprite: viewport_sprite,
+            camera: viewport_camera,
+            image: image_handle,
+            owned_image,
+            output,
+            sub_viewport,
+            size,
+            sized_for: None,
+        });
+        // The viewport entities are children of the camera, and the viewport sprite
+        // would never become visible under a parent without visibility components.
+        if !has_visibility {
+            commands.entity(entity).insert(VisibilityBundle::default());
+        }
+    }
+}
+
+/// Returns the resolution of a window that viewport sizes are calculated from.
+///
+/// The scale factor is picked by the [`PixelCameraConfig::dpi_policy`].
+pub(crate) fn sizing_resolution(window: &Window, config: &PixelCameraConfig) -> WindowResolution {
+    let scale_factor = config.dpi_policy.scale_factor(window.scale_factor());
+    if scale_factor == window.scale_factor() {
+        return window.resolution.clone();
+    }
+
+    WindowResolution::new(
+        window.physical_width() as f32,
+        window.physical_height() as f32,
+    )
+    .with_scale_factor_override(scale_factor)
+}
+
+/// Returns the resolution of the part of a window that a [`Camera::viewport`] covers, which
+/// split-screen viewports are sized for.
+pub(crate) fn sub_viewport_resolution(
+    resolution: WindowResolution,
+    viewport: Option<&Viewport>,
+) -> WindowResolution {
+    let Some(viewport) = viewport else {
+        return resolution;
+    };
+
+    WindowResolution::new(
+        viewport.physical_size.x.max(1) as f32,
+        viewport.physical_size.y.max(1) as f32,
+    )
+    .with_scale_factor_override(resolution.scale_factor())
+}
+
+/// Calculates the size of the render target of a [`PixelCamera`] on a window,
+/// including the smoothing margin.
+///
+/// Invalid and too large sizes are reported and clamped.
+pub(crate) fn target_size(
+    pixel_camera: &PixelCamera,
+    resolution: &WindowResolution,
+    render_device: Option<&RenderDevice>,
+    errors: &mut EventWriter<PixelCameraError>,
+    entity: Entity,
+) -> Extent3d {
+    let viewport_size = &pixel_camera.viewport_size;
+    let mut size = match viewport_size.try_calculate(resolution) {
+        Ok(size) => size,
+        // Minimized windows have no size, the viewport is sized again once they're restored.
+        Err(_) if resolution.physical_width() == 0 || resolution.physical_height() == 0 => {
+            viewport_size.calculate(resolution)
+        }
+        Err(_) => {
+            report(errors, entity, PixelCameraErrorKind::InvalidViewportSize);
+            viewport_size.calculate(resolution)
+        }
+    };
+    if pixel_camera.smoothing {
+        size.width += 2;
+        size.height += 2;
+    }
+
+    clamp_to_device_limits(size, render_device).unwrap_or_else(|size| {
+        report(errors, entity, PixelCameraErrorKind::TargetTooLarge);
+        size
+    })
+}
+
+/// Spawns the sprite that displays the render target of a [`PixelCamera`], as a child of
+/// the camera.
+///
+/// The viewport sprite and camera move along with the camera, which doesn't change what the
+/// viewport camera sees, and despawning the camera recursively cleans them up.
+fn spawn_viewport_sprite(
+    commands: &mut Commands,
+    owner: Entity,
+    image_handle: Handle<Image>,
+    viewport_layer: &RenderLayers,
+) -> Entity {
+    commands
+        .spawn((
+            SpriteBundle {
+                texture: image_handle,
+                transform: Transform::from_scale(Vec3::splat(1.0)),
+                ..default()
+            },
+            *viewport_layer,
+            PixelViewport(owner),
+        ))
+        .set_parent(owner)
+        .id()
+}
+
+/// Spawns the camera that renders the viewport sprite of a [`PixelCamera`] to the `target` window,
+/// as a child of the camera.
+///
+/// `size` is the size of the render target, including the smoothing margin. The viewport is
+/// shown on the `viewport` of the window if there is one, e.g. for split-screen.
+fn spawn_viewport_camera(
+    commands: &mut Commands,
+    owner: Entity,
+    pixel_camera: &PixelCamera,
+    mut size: Extent3d,
+    target: RenderTarget,
+    viewport: Option<Viewport>,
+) -> Entity {
+    if pixel_camera.smoothing {
+        size.width -= 2;
+        size.height -= 2;
+    }
+
+    commands
+        .spawn((
+            Camera2dBundle {
+                camera: Camera {
+                    order: pixel_camera.viewport_order,
+                    clear_color: pixel_camera.viewport_size.clear_color(),
+                    target,
+                    viewport,
+                    ..default()
+                },
+                projection: OrthographicProjection {
+                    far: 1000.,
+                    near: -1000.,
+                    scaling_mode: ScalingMode::Fixed {
+                        width: size.width as f32,
+                        height: size.height as f32,
+                    },
+                    ..default()
+                },
+
+                ..default()
+            },
+            ViewportCamera(owner),
+            pixel_camera.viewport_layer,
+        ))
+        .set_parent(owner)
+        .id()
+}
+
+/// Returns the image that a newly initialized [`PixelCamera`] renders to, and whether it was
+/// created by the plugin.
+///
+/// An existing image that the camera was pointed at is used as is, and resized to fit along
+/// with the other targets. The image of a default handle is shared by everything that doesn't
+/// set one, so it's never used.
+fn initial_target_image(
+    target: &RenderTarget,
+    images: &mut Assets<Image>,
+    pool: &mut TargetImagePool,
+    size: Extent3d,
+    format: TextureFormat,
+    usage: RenderAssetUsages,
+) -> (Handle<Image>, bool) {
+    match target {
+        RenderTarget::Image(image)
+            if images.contains(image) && image.id() != AssetId::default() =>
+        {
+            (image.clone(), false)
+        }
+        _ => {
+            let image = pool
+                .take(images, size, format, usage)
+                .unwrap_or_else(|| images.add(create_target_image(size, format, usage)));
+            (image, true)
+        }
+    }
+}
+
+/// Run condition for [`link_custom_viewports`], so that it only claims mutable access to
+/// [`Assets<Image>`] while a [`CustomViewport`] is missing its viewport sprite or camera.
+#[allow(clippy::type_complexity)]
+pub(crate) fn custom_viewports_unlinked(
+    cameras: Query<
+        Option<&PixelViewportReferences>,
+        (
+            With<PixelCamera>,
+            With<CustomViewport>,
+            Without<ViewportCamera>,
+            Without<Suspended>,
+        ),
+    >,
+    sprites: Query<(), With<PixelViewport>>,
+    viewport_cameras: Query<(), With<ViewportCamera>>,
+) -> bool {
+    cameras.iter().any(|viewport| {
+        viewport.is_none_or(|viewport| {
+            !sprites.contains(viewport.sprite) || !viewport_cameras.contains(viewport.camera)
+        })
+    })
+}
+
+/// Initializes [`PixelCamera`]s with a [`CustomViewport`] once the user's viewport sprite and
+/// camera exist, and links them to new ones if they're despawned.
+#[allow(clippy::type_complexity)]
+pub(crate) fn link_custom_viewports(
+    mut cameras: Query<
+        (
+            Entity,
+            &mut PixelCamera,
+            &mut Camera,
+            Option<&mut PixelViewportReferences>,
+        ),
+        (
+            With<CustomViewport>,
+            Without<ViewportCamera>,
+            Without<Suspended>,
+        ),
+    >,
+    mut sprites: Query<(Entity, &PixelViewport, &mut Handle<Image>)>,
+    viewport_cameras: Query<(Entity, &ViewportCamera, &Camera)>,
+    mut images: ResMut<Assets<Image>>,
+    mut pool: ResMut<TargetImagePool>,
+    mut commands: Commands,
+) {
+    for (entity, mut pixel_camera, mut camera, viewport) in &mut cameras {
+        if let Some(viewport) = &viewport {
+            if sprites.contains(viewport.sprite) && viewport_cameras.contains(viewport.camera) {
+                continue;
+            }
+        }
+        let sprite = sprites
+            .iter()
+            .find(|(_, PixelViewport(owner), _)| *owner == entity)
+            .map(|(sprite, ..)| sprite);
+        let viewport_camera = viewport_cameras
+            .iter()
+            .find(|(_, ViewportCamera(owner), _)| *owner == entity)
+            .map(|(viewport_camera, _, camera)| {
+                (
+                    viewport_camera,
+                    camera.target.clone(),
+                    camera.viewport.clone(),
+                )
+            });
+        let (Some(sprite), Some((viewport_camera, output, sub_viewport))) =
+            (sprite, viewport_camera)
+        else {
+            continue;
+        };
+
+        let image = match viewport {
+            Some(mut viewport) => {
+                viewport.sprite = sprite;
+                viewport.camera = viewport_camera;
+                viewport.output = output;
+                viewport.sub_viewport = sub_viewport;
+                viewport.image.clone()
+            }
+            None => {
+                // The image is resized to fit the viewport camera's target right away.
+                let size = Extent3d {
+                    width: 1,
+                    height: 1,
+                    ..default()
+                };
+                let (image, owned_image) = initial_target_image(
+                    &camera.target,
+                    &mut images,
+                    &mut pool,
+                    size,
+                    pixel_camera.target_format,
+                    pixel_camera.target_usage,
+                );
+                camera.target = RenderTarget::Image(image.clone());
+                commands.entity(entity).insert(PixelViewportReferences {
+                    sprite,
+                    camera: viewport_camera,
+                    image: image.clone(),
+                    owned_image,
+                    output,
+                    sub_viewport,
+                    size,
+                    sized_for: None,
+                });
+                image
+            }
+        };
+        if let Ok((_, _, mut sprite_image)) = sprites.get_mut(sprite) {
+            *sprite_image = image;
+        }
+        // Recalculate the viewport size for the (new) viewport camera.
+        pixel_camera.set_changed();
+    }
+}
+
+/// Respawns the viewport sprite and camera of a [`PixelCamera`] if they were despawned
+/// (for example by a scene cleanup that despawned the wrong entities).
+///
+/// The viewport camera is respawned on the target (and the part of it) that it showed the
+/// viewport on.
+pub(crate) fn repair_viewport(
+    mut cameras: Query<
+        (Entity, &PixelCamera, &mut PixelViewportReferences),
+        Without<CustomViewport>,
+    >,
+    sprites: Query<(), With<PixelViewport>>,
+    viewport_cameras: Query<Ref<Camera>, With<ViewportCamera>>,
+    mut commands: Commands,
+) {
+    for (entity, pixel_camera, mut viewport) in &mut cameras {
+        if !sprites.contains(viewport.sprite) {
+            warn!("PixelCamera {entity:?}'s viewport sprite no longer exists, respawning it.");
+            viewport.sprite = spawn_viewport_sprite(
+                &mut commands,
+                entity,
+                viewport.image.clone(),
+                &pixel_camera.viewport_layer,
+            );
+        }
+        match viewport_cameras.get(viewport.camera) {
+            Ok(viewport_camera) => {
+                if !viewport_camera.is_changed() {
+                    continue;
+                }
+                // Remember where the viewport is shown, to respawn the camera there.
+                if viewport.output.normalize(None) != viewport_camera.target.normalize(None) {
+                    viewport.output = viewport_camera.target.clone();
+                }
+                if !same_viewport(
+                    viewport.sub_viewport.as_ref(),
+                    viewport_camera.viewport.as_ref(),
+                ) {
+                    viewport.sub_viewport = viewport_camera.viewport.clone();
+                }
+            }
+            Err(_) => {
+                warn!("PixelCamera {entity:?}'s viewport camera no longer exists, respawning it.");
+                viewport.camera = spawn_viewport_camera(
+                    &mut commands,
+                    entity,
+                    pixel_camera,
+                    viewport.size,
+                    viewport.output.clone(),
+                    viewport.sub_viewport.clone(),
+                );
+            }
+        }
+    }
+}
+
+/// Cleans up after [`PixelCamera`]s that were removed or despawned.
+///
+/// Cameras that lost their [`PixelCamera`] component render to the window of their viewport
+/// again. The viewport entities are despawned, which frees the render target image once
+/// nothing else holds it. So are viewport entities that don't belong to their camera's
+/// current viewport, e.g. the ones loaded from a scene. The entities of a [`CustomViewport`]
+/// are never despawned.
+#[allow(clippy::type_complexity)]
+pub(crate) fn cleanup_removed_cameras(
+    mut removed: Query<
+        (
+            Entity,
+            &mut Camera,
+            &PixelViewportReferences,
+            Option<&TargetClosed>,
+        ),
+        (Without<PixelCamera>, Without<ViewportCamera>),
+    >,
+    viewport_cameras: Query<(Entity, &Camera, &ViewportCamera, Option<&Parent>)>,
+    sprites: Query<(Entity, &PixelViewport, Option<&Parent>)>,
+    references: Query<(Option<&PixelViewportReferences>, Has<CustomViewport>), With<PixelCamera>>,
+    mut commands: Commands,
+) {
+    for (entity, mut camera, viewport, closed) in &mut removed {
+        camera.target = viewport_cameras.get(viewport.camera).map_or_else(
+            |_| RenderTarget::default(),
+            |(_, viewport_camera, ..)| viewport_camera.target.clone(),
+        );
+        if closed.is_some_and(|closed| closed.reactivate) {
+            camera.is_active = true;
+        }
+        commands
+            .entity(entity)
+            .remove::<(PixelViewportReferences, TargetClosed)>();
+    }
+
+    // The viewport entities of cameras that were removed above or despawned, and viewport
+    // entities that were loaded from a scene, which are replaced when their camera is initialized.
+    // Only the children of the camera were spawned by the plugin, the others belong to a
+    // `CustomViewport` and are left alone.
+    let is_stale =
+        |entity: Entity, owner: Entity, linked: fn(&PixelViewportReferences) -> Entity| {
+            match references.get(owner) {
+                Ok((Some(viewport), _)) => linked(viewport) != entity,
+                // Custom viewports are linked by `link_custom_viewports`.
+                Ok((None, custom)) => !custom,
+                Err(_) => true,
             }
-        } else if viewport_layer.intersects(&RenderLayers::layer(0)) {
-            error!("The render layers of the viewport camera intersect with the default render layer of the world");
-            return;
-        } else if *viewport_layer == RenderLayers::none() {
-            error!("The viewport camera has no render layers and will be rendered on the world");
-            return;
+        };
+    for (viewport_camera, _, ViewportCamera(owner), parent) in &viewport_cameras {
+        if parent.is_some_and(|parent| parent.get() == *owner)
+            && is_stale(viewport_camera, *owner, |viewport| viewport.camera)
+        {
+            commands.entity(viewport_camera).despawn_recursive();
+        }
+    }
+    for (sprite, PixelViewport(owner), parent) in &sprites {
+        if parent.is_some_and(|parent| parent.get() == *owner)
+            && is_stale(sprite, *owner, |viewport| viewport.sprite)
+        {
+            commands.entity(sprite).despawn_recursive();
+        }
+    }
+}
+
+/// Validates the render layers of initialized [`PixelCamera`]s again whenever the
+/// render layers of the camera or its viewport entities change.
+///
+/// Also moves the viewport entities to [`PixelCamera::viewport_layer`] when it's changed.
+#[allow(clippy::type_complexity)]
+pub(crate) fn revalidate_layers(
+    mut cameras: Query<
+        (
+            Entity,
+            &mut PixelCamera,
+            Option<Ref<RenderLayers>>,
+            &PixelViewportReferences,
+        ),
+        (Without<ViewportCamera>, Without<CustomViewport>),
+    >,
+    mut viewport_layers: Query<
+        &mut RenderLayers,
+        (
+            Or<(With<ViewportCamera>, With<PixelViewport>)>,
+            Without<PixelCamera>,
+        ),
+    >,
+    config: Res<PixelCameraConfig>,
+    mut errors: EventWriter<PixelCameraError>,
+) {
+    for (entity, mut pixel_camera, world_layer, viewport) in &mut cameras {
+        // Move the viewport entities over when `PixelCamera::viewport_layer` was changed.
+        let moved = pixel_camera.is_changed()
+            && [viewport.camera, viewport.sprite]
+                .into_iter()
+                .any(|entity| {
+                    viewport_layers
+                        .get(entity)
+                        .is_ok_and(|layer| *layer != pixel_camera.viewport_layer)
+                });
+        if moved {
+            for entity in [viewport.camera, viewport.sprite] {
+                if let Ok(mut viewport_layer) = viewport_layers.get_mut(entity) {
+                    *viewport_layer = pixel_camera.viewport_layer;
+                }
+            }
+        }
+
+        let changed = moved
+            || world_layer.as_ref().is_some_and(DetectChanges::is_changed)
+            || [viewport.camera, viewport.sprite]
+                .into_iter()
+                .any(|entity| {
+                    // `get_mut` only marks the component as changed when it's written to.
+                    viewport_layers
+                        .get_mut(entity)
+                        .is_ok_and(|layer| layer.is_changed() && !layer.is_added())
+                });
+        if !changed {
+            continue;
+        }
+
+        let world_layer = world_layer.as_deref();
+        let result = [viewport.camera, viewport.sprite]
+            .into_iter()
+            .filter_map(|entity| viewport_layers.get(entity).ok())
+            .try_for_each(|layer| validate_layers(world_layer, layer));
+        let Err(kind) = result else {
+            continue;
+        };
+
+        match free_layer(world_layer) {
+            Some(layer) if config.auto_resolve_layers => {
+                warn!("PixelCamera {entity:?}: The viewport layer conflicts with the world layer, using {layer:?} instead");
+                pixel_camera.viewport_layer = layer;
+                for entity in [viewport.camera, viewport.sprite] {
+                    if let Ok(mut viewport_layer) = viewport_layers.get_mut(entity) {
+                        *viewport_layer = layer;
+                    }
+                }
+            }
+            _ => report(&mut errors, entity, kind),
+        }
+    }
+}
+
+/// Checks the order of initialized [`PixelCamera`]s again whenever the camera's order or
+/// [`PixelCamera::viewport_order`] changes, and updates the order of the viewport camera.
+#[allow(clippy::type_complexity)]
+pub(crate) fn revalidate_order(
+    mut cameras: Query<
+        (
+            Entity,
+            &mut PixelCamera,
+            Ref<Camera>,
+            &PixelViewportReferences,
+        ),
+        (
+            Without<ViewportCamera>,
+            Without<CustomViewport>,
+            Or<(Changed<PixelCamera>, Changed<Camera>)>,
+        ),
+    >,
+    mut viewport_cameras: Query<&mut Camera, With<ViewportCamera>>,
+    config: Res<PixelCameraConfig>,
+    mut errors: EventWriter<PixelCameraError>,
+) {
+    for (entity, mut pixel_camera, camera, viewport) in &mut cameras {
+        let Ok(mut viewport_camera) = viewport_cameras.get_mut(viewport.camera) else {
+            continue;
+        };
+        // `PixelCamera` also changes whenever the camera moves.
+        if !camera.is_changed() && viewport_camera.order == pixel_camera.viewport_order {
+            continue;
+        }
+        if camera.order >= pixel_camera.viewport_order {
+            if config.auto_fix_order {
+                let order = camera.order + 1;
+                warn!("PixelCamera {entity:?}: The camera renders later or at the same time as the viewport camera, using viewport order {order} instead");
+                pixel_camera.viewport_order = order;
+            } else {
+                // Go back to the order of the viewport camera, so the error is only reported once.
+                report(&mut errors, entity, PixelCameraErrorKind::BadOrder);
+                pixel_camera.viewport_order = viewport_camera.order;
+                continue;
+            }
+        }
+        if viewport_camera.order != pixel_camera.viewport_order {
+            viewport_camera.order = pixel_camera.viewport_order;
+        }
+    }
+}
+
+/// Checks that the world camera can't see the viewport and the viewport camera can't see the world.
+pub(crate) fn validate_layers(
+    world_layer: Option<&RenderLayers>,
+    viewport_layer: &RenderLayers,
+) -> Result<(), PixelCameraErrorKind> {
+    // Entities without `RenderLayers` are on the default layer, which is also
+    // the layer that a camera without `RenderLayers` sees.
+    let world_layer = world_layer.copied().unwrap_or_default();
+
+    if *viewport_layer == RenderLayers::none() {
+        Err(PixelCameraErrorKind::EmptyViewportLayer)
+    } else if world_layer.intersects(viewport_layer) {
+        Err(PixelCameraErrorKind::LayerConflict)
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns the render layers that are in either `a` or `b`.
+fn layer_union(a: &RenderLayers, b: &RenderLayers) -> RenderLayers {
+    RenderLayers::from_layers(&a.iter().chain(b.iter()).collect::<Vec<_>>())
+}
+
+/// Finds a single render layer that doesn't intersect with the world's render layers.
+///
+/// The [`HIGH_RES_LAYER`](crate::high_res::HIGH_RES_LAYER) is never picked.
+pub(crate) fn free_layer(world_layer: Option<&RenderLayers>) -> Option<RenderLayers> {
+    let world_layer = world_layer.copied().unwrap_or_default();
+
+    (1..crate::high_res::HIGH_RES_LAYER)
+        .map(RenderLayers::layer)
+        .find(|layer| !world_layer.intersects(layer))
+}
+
+/// Clamps the size of a render target to the largest 2D texture the device supports.
+///
+/// Returns the clamped size as an error if the size had to be clamped.
+fn clamp_to_device_limits(
+    size: Extent3d,
+    render_device: Option<&RenderDevice>,
+) -> Result<Extent3d, Extent3d> {
+    // Without a render device (e.g. in headless apps) there is nothing to clamp against.
+    let Some(render_device) = render_device else {
+        return Ok(size);
+    };
+    let max = render_device.limits().max_texture_dimension_2d;
+
+    if size.width > max || size.height > max {
+        Err(Extent3d {
+            width: size.width.min(max),
+            height: size.height.min(max),
+            depth_or_array_layers: 1,
+        })
+    } else {
+        Ok(size)
+    }
+}
+
+/// The texture usages that a render target image needs.
+///
+/// `COPY_SRC` lets [`PixelReadback`](crate::readback::PixelReadback) copy finished frames.
+const TARGET_USAGES: TextureUsages = TextureUsages::TEXTURE_BINDING
+    .union(TextureUsages::COPY_SRC)
+    .union(TextureUsages::COPY_DST)
+    .union(TextureUsages::RENDER_ATTACHMENT);
+
+/// Creates the low resolution image that a [`PixelCamera`] renders to.
+pub(crate) fn create_target_image(
+    size: Extent3d,
+    format: TextureFormat,
+    asset_usage: RenderAssetUsages,
+) -> Image {
+    Image {
+        texture_descriptor: TextureDescriptor {
+            label: None,
+            size,
+            dimension: TextureDimension::D2,
+            format,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TARGET_USAGES,
+            view_formats: &[],
+        },
+        data: zeroed_target_data(size, format),
+        asset_usage,
+        ..default()
+    }
+}
+
+/// Replaces the [`PixelCamera::target_format`] of cameras with a format that the render
+/// device can use as a render target, e.g. on WebGL2.
+pub(crate) fn fallback_target_formats(
+    mut cameras: Query<(Entity, &mut PixelCamera), Changed<PixelCamera>>,
+    adapter: Option<Res<RenderAdapter>>,
+    adapter_info: Option<Res<RenderAdapterInfo>>,
+) {
+    let (Some(adapter), Some(adapter_info)) = (adapter, adapter_info) else {
+        return;
+    };
+
+    for (entity, mut pixel_camera) in &mut cameras {
+        let format = pixel_camera.target_format;
+        // WebGL2 and OpenGL ES claim to support BGRA formats, but can't upload data to them.
+        let gl_bgra = adapter_info.backend.to_str() == "gl"
+            && matches!(
+                format,
+                TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb
+            );
+        let usages = adapter.get_texture_format_features(format).allowed_usages;
+        if !gl_bgra && usages.contains(TARGET_USAGES) {
+            continue;
+        }
+
+        let fallback = if format.is_srgb() {
+            TextureFormat::Rgba8UnormSrgb
+        } else {
+            TextureFormat::Rgba8Unorm
+        };
+        warn!(
+            "PixelCamera {entity:?}: The {} backend can't render to {format:?} targets, using {fallback:?} instead",
+            adapter_info.backend.to_str()
+        );
+        pixel_camera.target_format = fallback;
+    }
+}
+
+/// The render target images of despawned [`PixelCamera`]s, kept for reuse.
+///
+/// Only used when [`PixelCameraConfig::target_image_pool`] is above zero.
+#[derive(Resource, Default)]
+pub(crate) struct TargetImagePool {
+    /// The current target image of every initialized camera, so that it can still be
+    /// released after the camera was despawned.
+    owners: HashMap<Entity, Handle<Image>>,
+    free: Vec<Handle<Image>>,
+}
+
+impl TargetImagePool {
+    /// Takes a released image with the same format and usage, resized to `size`.
+    fn take(
+        &mut self,
+        images: &mut Assets<Image>,
+        size: Extent3d,
+        format: TextureFormat,
+        asset_usage: RenderAssetUsages,
+    ) -> Option<Handle<Image>> {
+        let index = self.free.iter().position(|handle| {
+            images.get(handle).is_some_and(|image| {
+                image.texture_descriptor.format == format && image.asset_usage == asset_usage
+            })
+        })?;
+        let handle = self.free.swap_remove(index);
+        let image = images.get_mut(&handle)?;
+        if image.texture_descriptor.size != size {
+            resize_target(image, size);
+        }
+        Some(handle)
+    }
+}
+
+/// Remembers the target image of every initialized [`PixelCamera`] while images are pooled.
+pub(crate) fn track_target_images(
+    cameras: Query<(Entity, &PixelViewportReferences), Changed<PixelViewportReferences>>,
+    config: Res<PixelCameraConfig>,
+    mut pool: ResMut<TargetImagePool>,
+) {
+    if config.target_image_pool == 0 {
+        // Don't hold on to any images once pooling is turned off.
+        if !pool.owners.is_empty() || !pool.free.is_empty() {
+            *pool = TargetImagePool::default();
+        }
+        return;
+    }
+    for (entity, viewport) in &cameras {
+        // Images supplied by the user may still be used elsewhere, so they're never reused.
+        if viewport.owned_image {
+            pool.owners.insert(entity, viewport.image.clone());
+        } else {
+            pool.owners.remove(&entity);
+        }
+    }
+}
+
+/// Moves the target images of removed or despawned [`PixelCamera`]s into the pool,
+/// or drops them once the pool is full.
+pub(crate) fn release_target_images(
+    mut removed: RemovedComponents<PixelCamera>,
+    images: Res<Assets<Image>>,
+    config: Res<PixelCameraConfig>,
+    mut pool: ResMut<TargetImagePool>,
+) {
+    for entity in removed.read() {
+        let Some(handle) = pool.owners.remove(&entity) else {
+            continue;
+        };
+        // Images that only live in the render world can't be checked or resized, so they
+        // aren't reused.
+        if pool.free.len() < config.target_image_pool && images.contains(&handle) {
+            pool.free.push(handle);
+        }
+    }
+}
+
+/// Allocates zeroed pixel data for a render target.
+///
+/// The first render overwrites the whole target, so instead of filling an existing
+/// buffer (like [`Image::resize`] does) we request already zeroed memory, which the
+/// allocator can usually hand out without touching every byte.
+fn zeroed_target_data(size: Extent3d, format: TextureFormat) -> Vec<u8> {
+    vec![0; size.width as usize * size.height as usize * format.pixel_size()]
+}
+
+/// Resizes a render target image, without filling the new data like [`Image::resize`] does.
+pub(crate) fn resize_target(image: &mut Image, size: Extent3d) {
+    image.texture_descriptor.size = size;
+    image.data = zeroed_target_data(size, image.texture_descriptor.format);
+}
+
+/// Rebinds the viewport when user code changes the [`RenderTarget`] of a [`PixelCamera`].
+///
+/// - Pointing the camera at another image makes that image the new render target.
+/// - Pointing the camera at a window makes the viewport camera render to that window instead,
+///   while the camera keeps rendering to its low resolution image. [`ViewportTarget`] does the
+///   same for any target.
+#[allow(clippy::type_complexity)]
+pub(crate) fn handle_target_changes(
+    mut cameras: Query<
+        (
+            Entity,
+            &mut PixelCamera,
+            &mut Camera,
+            &mut PixelViewportReferences,
+        ),
+        (
+            Changed<Camera>,
+            Without<ViewportCamera>,
+            Without<PixelBypass>,
+        ),
+    >,
+    mut viewport_cameras: Query<&mut Camera, With<ViewportCamera>>,
+    mut viewport_sprites: Query<&mut Handle<Image>, With<PixelViewport>>,
+    mut errors: EventWriter<PixelCameraError>,
+) {
+    for (entity, mut pixel_camera, mut camera, mut viewport) in &mut cameras {
+        match &camera.target {
+            RenderTarget::Image(image) if *image == viewport.image => continue,
+            RenderTarget::Image(image) => {
+                let image = image.clone();
+                if let Ok(mut sprite_image) = viewport_sprites.get_mut(viewport.sprite) {
+                    *sprite_image = image.clone();
+                }
+                // Marks the references as changed, so the new image is resized to fit.
+                viewport.image = image;
+                viewport.owned_image = false;
+            }
+            RenderTarget::Window(window) => {
+                if let Ok(mut viewport_camera) = viewport_cameras.get_mut(viewport.camera) {
+                    viewport_camera.target = RenderTarget::Window(*window);
+                }
+                camera.target = RenderTarget::Image(viewport.image.clone());
+                // Recalculate the viewport size for the new window.
+                pixel_camera.set_changed();
+            }
+            RenderTarget::TextureView(_) => {
+                report(&mut errors, entity, PixelCameraErrorKind::UnsupportedTarget);
+                camera.target = RenderTarget::Image(viewport.image.clone());
+            }
+        }
+    }
+}
+
+/// Points the viewport cameras of [`PixelCamera`]s at their [`ViewportTarget`] when it changes.
+#[allow(clippy::type_complexity)]
+pub(crate) fn apply_viewport_targets(
+    mut cameras: Query<
+        (
+            Entity,
+            &ViewportTarget,
+            &mut PixelCamera,
+            &mut Camera,
+            &PixelViewportReferences,
+            Has<PixelBypass>,
+        ),
+        (
+            Without<ViewportCamera>,
+            Or<(Changed<ViewportTarget>, Added<PixelViewportReferences>)>,
+        ),
+    >,
+    mut viewport_cameras: Query<&mut Camera, With<ViewportCamera>>,
+    mut errors: EventWriter<PixelCameraError>,
+) {
+    for (entity, ViewportTarget(target), mut pixel_camera, mut camera, viewport, bypassed) in
+        &mut cameras
+    {
+        if let RenderTarget::TextureView(_) = target {
+            report(&mut errors, entity, PixelCameraErrorKind::UnsupportedTarget);
+            continue;
+        }
+        let Ok(mut viewport_camera) = viewport_cameras.get_mut(viewport.camera) else {
+            continue;
+        };
+        viewport_camera.target = target.clone();
+        // Bypassed cameras render straight to the output.
+        if bypassed {
+            camera.target = target.clone();
+        }
+        // Recalculate the viewport size for the new target.
+        pixel_camera.set_changed();
+    }
+}
+
+/// Points [`PixelBypass`]ed cameras at the window of their viewport, and back at their low
+/// resolution target once the bypass is removed.
+#[allow(clippy::type_complexity)]
+pub(crate) fn apply_bypass(
+    mut cameras: Query<
+        (&mut Camera, &PixelViewportReferences, Has<PixelBypass>),
+        Without<ViewportCamera>,
+    >,
+    mut viewport_cameras: Query<&mut Camera, With<ViewportCamera>>,
+    // Cameras can be bypassed before they're initialized.
+    added: Query<
+        Entity,
+        (
+            With<PixelBypass>,
+            Or<(Added<PixelBypass>, Added<PixelViewportReferences>)>,
+        ),
+    >,
+    mut removed: RemovedComponents<PixelBypass>,
+) {
+    for entity in &added {
+        let Ok((mut camera, viewport, _)) = cameras.get_mut(entity) else {
+            continue;
+        };
+        if let Ok(viewport_camera) = viewport_cameras.get(viewport.camera) {
+            camera.target = viewport_camera.target.clone();
+            camera.viewport = viewport_camera.viewport.clone();
+        }
+    }
+
+    for entity in removed.read() {
+        let Ok((mut camera, viewport, bypassed)) = cameras.get_mut(entity) else {
+            continue;
+        };
+        // The bypass may have been removed and added again in the same frame.
+        if bypassed {
+            continue;
+        }
+        // Keep the window the camera was pointed at during the bypass.
+        if let (RenderTarget::Window(window), Ok(mut viewport_camera)) =
+            (&camera.target, viewport_cameras.get_mut(viewport.camera))
+        {
+            viewport_camera.target = RenderTarget::Window(*window);
+        }
+        // The same goes for the part of the window, the camera renders to all of its image.
+        if let (Some(sub_viewport), Ok(mut viewport_camera)) = (
+            camera.viewport.take(),
+            viewport_cameras.get_mut(viewport.camera),
+        ) {
+            viewport_camera.viewport = Some(sub_viewport);
+        }
+        camera.target = RenderTarget::Image(viewport.image.clone());
+    }
+}
+
+/// Moves a [`Camera::viewport`] that was set on an initialized [`PixelCamera`] over to its
+/// viewport camera, so that split-screen viewports can be moved and resized at runtime.
+#[allow(clippy::type_complexity)]
+pub(crate) fn apply_sub_viewports(
+    mut cameras: Query<
+        (&mut Camera, &mut PixelCamera, &PixelViewportReferences),
+        (
+            Without<ViewportCamera>,
+            Without<CustomViewport>,
+            Without<PixelBypass>,
+            Changed<Camera>,
+        ),
+    >,
+    mut viewport_cameras: Query<&mut Camera, With<ViewportCamera>>,
+) {
+    for (mut camera, mut pixel_camera, viewport) in &mut cameras {
+        if camera.viewport.is_none() {
+            continue;
         }
+        let Ok(mut viewport_camera) = viewport_cameras.get_mut(viewport.camera) else {
+            continue;
+        };
+        viewport_camera.viewport = camera.viewport.take();
+        // Resize the viewport for its new part of the window.
+        pixel_camera.set_changed();
+    }
+}
 
-        if &camera.order >= viewport_order {
-            error!("The camera is configured to render later or at the same time as of the viewport camera. (camera.order >= viewport_camera.order)");
-            return;
+/// Shows the viewports of [`PixelCamera`]s with a [`ViewportRect`] on their part of the window,
+/// and on the whole window again once it's removed.
+#[allow(clippy::type_complexity)]
+pub(crate) fn apply_viewport_rects(
+    mut cameras: Query<
+        (
+            Option<&ViewportRect>,
+            &mut PixelCamera,
+            &mut Camera,
+            &PixelViewportReferences,
+            Has<PixelBypass>,
+        ),
+        Without<ViewportCamera>,
+    >,
+    changed: Query<
+        Entity,
+        (
+            With<ViewportRect>,
+            Or<(Changed<ViewportRect>, Added<PixelViewportReferences>)>,
+        ),
+    >,
+    mut viewport_cameras: Query<&mut Camera, With<ViewportCamera>>,
+    mut removed: RemovedComponents<ViewportRect>,
+) {
+    for entity in changed.iter().chain(removed.read()) {
+        let Ok((rect, mut pixel_camera, mut camera, viewport, bypassed)) = cameras.get_mut(entity)
+        else {
+            continue;
+        };
+        let Ok(mut viewport_camera) = viewport_cameras.get_mut(viewport.camera) else {
+            continue;
+        };
+        let sub_viewport = rect.map(|ViewportRect(rect)| Viewport {
+            physical_position: rect.min,
+            physical_size: rect.size().max(UVec2::ONE),
+            ..default()
+        });
+        // Bypassed cameras render straight to the output.
+        if bypassed {
+            camera.viewport = sub_viewport.clone();
         }
+        viewport_camera.viewport = sub_viewport;
+        // Recalculate the viewport size for the new part of the window.
+        pixel_camera.set_changed();
+    }
+}
+
+/// Clamps a [`Viewport`] to a target of the given physical size, returns whether it changed.
+fn clamp_viewport(viewport: &mut Viewport, target_size: UVec2) -> bool {
+    let position = viewport.physical_position.min(target_size - 1);
+    let size = viewport
+        .physical_size
+        .min(target_size - position)
+        .max(UVec2::ONE);
+    let changed = position != viewport.physical_position || size != viewport.physical_size;
+    viewport.physical_position = position;
+    viewport.physical_size = size;
+    changed
+}
 
-        let mut size = viewport_size.calculate(&window.resolution);
-        if *smoothing {
-            size.width += 2;
-            size.height += 2;
+/// Makes sure that [`PixelCamera`]s catch up with the changes made while they were frozen
+/// once their [`PixelFreeze`] is removed.
+pub(crate) fn thaw_cameras(
+    mut removed: RemovedComponents<PixelFreeze>,
+    mut cameras: Query<&mut PixelCamera, Without<PixelFreeze>>,
+) {
+    for entity in removed.read() {
+        if let Ok(mut pixel_camera) = cameras.get_mut(entity) {
+            // Resizes the viewport, even if the window didn't change since.
+            pixel_camera.set_changed();
         }
+    }
+}
 
-        // This is the texture that will be rendered to.
-        let mut image = Image {
-            texture_descriptor: TextureDescriptor {
-                label: None,
-                size,
-                dimension: TextureDimension::D2,
-                format: TextureFormat::Bgra8UnormSrgb,
-                mip_level_count: 1,
-                sample_count: 1,
-                usage: TextureUsages::TEXTURE_BINDING
-                    | TextureUsages::COPY_DST
-                    | TextureUsages::RENDER_ATTACHMENT,
-                view_formats: &[],
-            },
-            ..default()
-        };
+/// Recalculates the viewports of all [`PixelCamera`]s when the app is resumed.
+///
+/// Mobile platforms recreate the window's surface when the app is resumed, and the device may
+/// have been rotated in the meantime, so the viewports can't wait for the next resize.
+pub(crate) fn refresh_resumed_cameras(
+    mut lifetime: EventReader<ApplicationLifetime>,
+    mut cameras: Query<&mut PixelCamera, Without<PixelFreeze>>,
+) {
+    if !lifetime
+        .read()
+        .any(|event| *event == ApplicationLifetime::Resumed)
+    {
+        return;
+    }
+
+    for mut pixel_camera in &mut cameras {
+        pixel_camera.set_changed();
+    }
+}
 
-        // fill image.data with zeroes
-        image.resize(size);
+/// Returns the window that the viewport camera of a [`PixelCamera`] renders to.
+pub(crate) fn viewport_window(
+    viewport: &PixelViewportReferences,
+    viewport_cameras: &Query<&Camera, With<ViewportCamera>>,
+    primary_window: Option<Entity>,
+) -> Option<Entity> {
+    let RenderTarget::Window(window_ref) = &viewport_cameras.get(viewport.camera).ok()?.target
+    else {
+        return None;
+    };
 
-        let image_handle = images.add(image);
+    window_ref
+        .normalize(primary_window)
+        .map(|window_ref| window_ref.entity())
+}
 
-        camera.target = RenderTarget::Image(image_handle.clone());
+/// Recalculates the viewports of [`PixelCamera`]s on windows that moved to a monitor with a
+/// different scale factor, along with everything else that follows changes to the camera.
+///
+/// The backend's scale factor is used, which also changes when the window has a
+/// scale factor override and only its physical size changes.
+#[allow(clippy::type_complexity)]
+pub(crate) fn refresh_rescaled_cameras(
+    mut rescaled: EventReader<WindowBackendScaleFactorChanged>,
+    mut cameras: Query<
+        (
+            &mut PixelCamera,
+            Option<&PixelViewportReferences>,
+            Option<&DirectCompositeTarget>,
+        ),
+        Without<PixelFreeze>,
+    >,
+    viewport_cameras: Query<&Camera, With<ViewportCamera>>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+) {
+    let windows: Vec<Entity> = rescaled.read().map(|event| event.window).collect();
+    if windows.is_empty() {
+        return;
+    }
 
-        let viewport_sprite = commands
-            .spawn((
-                SpriteBundle {
-                    texture: image_handle,
-                    transform: Transform::from_scale(Vec3::splat(1.0)),
-                    ..default()
-                },
-                *viewport_layer,
-                PixelViewport,
-            ))
-            .id();
-
-        let viewport_camera = commands
-            .spawn((
-                Camera2dBundle {
-                    camera: Camera {
-                        order: *viewport_order,
-                        clear_color: viewport_size.clear_color(),
-                        ..default()
-                    },
-                    projection: OrthographicProjection {
-                        far: 1000.,
-                        near: -1000.,
-                        scaling_mode: ScalingMode::Fixed {
-                            width: (size.width - 2) as f32,
-                            height: (size.height - 2) as f32,
-                        },
-                        ..default()
-                    },
+    for (mut pixel_camera, viewport, composite) in &mut cameras {
+        let window = match (viewport, composite) {
+            (_, Some(composite)) => Some(composite.window),
+            (Some(viewport), None) => viewport_window(
+                viewport,
+                &viewport_cameras,
+                primary_window.get_single().ok(),
+            ),
+            (None, None) => None,
+        };
+        if window.is_some_and(|window| windows.contains(&window)) {
+            pixel_camera.set_changed();
+        }
+    }
+}
 
-                    ..default()
-                },
-                ViewportCamera,
-                *viewport_layer,
-            ))
-            .id();
+/// Deactivates pixel cameras whose viewport camera renders to a window that has been closed,
+/// and reactivates the ones that were active once their viewport camera renders to an existing
+/// window again.
+#[allow(clippy::type_complexity)]
+pub(crate) fn handle_closed_windows(
+    mut cameras: Query<
+        (
+            Entity,
+            &mut PixelCamera,
+            &mut Camera,
+            &PixelViewportReferences,
+            Option<&TargetClosed>,
+        ),
+        Without<ViewportCamera>,
+    >,
+    viewport_cameras: Query<&Camera, With<ViewportCamera>>,
+    windows: Query<(), With<Window>>,
+    primary_window: Query<(), (With<Window>, With<PrimaryWindow>)>,
+    mut commands: Commands,
+    mut errors: EventWriter<PixelCameraError>,
+) {
+    for (entity, mut pixel_camera, mut camera, viewport, closed) in &mut cameras {
+        let Ok(viewport_camera) = viewport_cameras.get(viewport.camera) else {
+            continue;
+        };
+        let missing_window = match &viewport_camera.target {
+            RenderTarget::Window(WindowRef::Primary) => primary_window.is_empty().then_some(None),
+            &RenderTarget::Window(WindowRef::Entity(window)) => {
+                (!windows.contains(window)).then_some(Some(window))
+            }
+            _ => None,
+        };
 
-        commands.entity(entity).insert(PixelViewportReferences {
-            sprite: viewport_sprite,
-            camera: viewport_camera,
-        });
+        match (missing_window, closed) {
+            (Some(window), None) => {
+                report(
+                    &mut errors,
+                    entity,
+                    PixelCameraErrorKind::MissingWindow(window),
+                );
+                commands.entity(entity).insert(TargetClosed {
+                    reactivate: camera.is_active,
+                });
+                camera.is_active = false;
+            }
+            (None, Some(TargetClosed { reactivate })) => {
+                // Cameras that were deactivated by someone else are left alone.
+                if *reactivate {
+                    camera.is_active = true;
+                }
+                // Make sure the viewport is resized to the new window.
+                pixel_camera.set_changed();
+                commands.entity(entity).remove::<TargetClosed>();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Mirrors [`Camera::is_active`] of [`PixelCamera`]s to their viewport camera and the
+/// visibility of their viewport sprite, so that a deactivated camera doesn't keep showing
+/// its last frame.
+///
+/// The viewport of a [`PixelBypass`]ed camera is always deactivated.
+pub(crate) fn sync_active_state(
+    cameras: Query<(&Camera, &PixelViewportReferences, Has<PixelBypass>), Without<ViewportCamera>>,
+    mut viewport_cameras: Query<&mut Camera, With<ViewportCamera>>,
+    mut viewport_sprites: Query<&mut Visibility, With<PixelViewport>>,
+) {
+    for (camera, viewport, bypassed) in &cameras {
+        let active = camera.is_active && !bypassed;
+        if let Ok(mut viewport_camera) = viewport_cameras.get_mut(viewport.camera) {
+            if viewport_camera.is_active != active {
+                viewport_camera.is_active = active;
+            }
+        }
+        if let Ok(mut visibility) = viewport_sprites.get_mut(viewport.sprite) {
+            let expected = if active {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+            if *visibility != expected {
+                *visibility = expected;
+            }
+        }
     }
 }
 
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 pub(crate) fn update_viewport_size(
-    primary_cameras: Query<
-        (Entity, &PixelCamera, &Camera, &PixelViewportReferences),
-        Without<ViewportCamera>,
+    mut primary_cameras: Query<
+        (
+            Entity,
+            &PixelCamera,
+            &mut PixelViewportReferences,
+            Has<CustomViewport>,
+        ),
+        (Without<ViewportCamera>, Without<PixelFreeze>),
     >,
-    mut viewport_cameras: Query<(&mut OrthographicProjection, &mut Camera), With<ViewportCamera>>,
-    windows: Query<Ref<Window>>,
-    primary_window: Query<Ref<Window>, With<PrimaryWindow>>,
-    mut images: ResMut<Assets<Image>>,
+    mut viewport_cameras: Query<
+        (Option<&mut OrthographicProjection>, &mut Camera),
+        With<ViewportCamera>,
+    >,
+    windows: Query<&Window>,
+    primary_window: Query<&Window, With<PrimaryWindow>>,
+    images: Res<Assets<Image>>,
+    render_device: Option<Res<RenderDevice>>,
+    config: Res<PixelCameraConfig>,
+    mut errors: EventWriter<PixelCameraError>,
 ) {
-    for (
-        entity,
-        PixelCamera {
+    for (entity, pixel_camera, mut viewport, custom) in &mut primary_cameras {
+        let PixelCamera {
             viewport_size,
             smoothing,
             ..
-        },
-        camera,
-        viewport,
-    ) in &primary_cameras
-    {
-        let Ok((mut viewport_projection, mut viewport_camera)) =
+        } = pixel_camera;
+        let Ok((viewport_projection, mut viewport_camera)) =
             viewport_cameras.get_mut(viewport.camera)
         else {
-            error!("PixelCamera {entity:?}'s viewport camera no longer exists.");
+            // Custom viewports are linked again by `link_custom_viewports` once they exist.
+            if !custom {
+                report(&mut errors, entity, PixelCameraErrorKind::MissingViewport);
+            }
             continue;
         };
-        let (mut new_size, aspect_ratio) = match &viewport_camera.target {
+        let resolution = match &viewport_camera.target {
             RenderTarget::Window(window_ref) => {
+                // Missing windows are reported by `handle_closed_windows`.
                 let window = match window_ref {
-                    WindowRef::Primary => {
-                        if let Ok(window) = primary_window.get_single() {
-                            window
-                        } else {
-                            error!("The primary window that the PixelCamera is pointing to doesn't exist.");
-                            continue;
-                        }
-                    }
-                    &WindowRef::Entity(entity) => {
-                        if let Ok(window) = windows.get(entity) {
-                            window
-                        } else {
-                            error!("Window {entity:?} that the PixelCamera is pointing to doesn't exist.");
-                            continue;
-                        }
-                    }
+                    WindowRef::Primary => primary_window.get_single().ok(),
+                    &WindowRef::Entity(window_entity) => windows.get(window_entity).ok(),
+                };
+                let Some(window) = window else {
+                    continue;
                 };
-                if !window.is_changed() {
+                // Keep the current size while the window is minimized, it will be
+                // recalculated once the window is restored and changes size again.
+                if window.physical_width() == 0 || window.physical_height() == 0 {
                     continue;
                 }
+                // A sub-rect that doesn't fit in the (resized) window would fail to render.
+                let window_size = UVec2::new(window.physical_width(), window.physical_height());
+                if let Some(sub_viewport) = &viewport_camera.viewport {
+                    let mut clamped = sub_viewport.clone();
+                    if clamp_viewport(&mut clamped, window_size) {
+                        viewport_camera.viewport = Some(clamped);
+                    }
+                }
 
-                let new_size = viewport_size.calculate(&window.resolution);
-                let aspect_ratio = window.width() / window.height();
-
-                (new_size, aspect_ratio)
+                sizing_resolution(window, &config)
             }
             RenderTarget::Image(image) => {
-                let image = images
-                    .get(image)
-                    .expect("RenderTarget::Image doesn't exist");
-                let size = image.size();
-
-                let new_size = Extent3d {
-                    width: size.x,
-                    height: size.y,
-                    ..default()
+                let Some(image) = images.get(image) else {
+                    report(&mut errors, entity, PixelCameraErrorKind::MissingImage);
+                    continue;
                 };
-                let aspect_ratio = size.x as f32 / size.y as f32;
-
-                (new_size, aspect_ratio)
+                // An image is sized like a window with a scale factor of 1.
+                let size = image.size();
+                if size.x == 0 || size.y == 0 {
+                    continue;
+                }
+                WindowResolution::new(size.x as f32, size.y as f32)
             }
             RenderTarget::TextureView(_) => {
-                error_once!(
-                    "RenderTarget::TextureView is not yet supported for `bevy_smooth_pixel_camera`"
-                );
-                return;
+                report(&mut errors, entity, PixelCameraErrorKind::UnsupportedTarget);
+                continue;
             }
         };
+        let resolution = sub_viewport_resolution(resolution, viewport_camera.viewport.as_ref());
 
-        viewport_projection.scaling_mode = if let ViewportSize::Fixed { fit, .. }
-        | ViewportSize::Custom { fit, .. } = viewport_size
+        // `subpixel_pos` changes whenever the camera moves, so only resize the viewport when
+        // something that its size depends on changed.
+        let sizing = ViewportSizing {
+            viewport_size: viewport_size.clone(),
+            smoothing: *smoothing,
+            camera: viewport.camera,
+            resolution,
+        };
+        if viewport
+            .sized_for
+            .as_ref()
+            .is_some_and(|sized_for| sized_for.same(&sizing))
         {
-            match fit {
-                FitMode::Fit(clear_color) => {
-                    viewport_camera.clear_color = clear_color.clone();
-                    if aspect_ratio > new_size.width as f32 / new_size.height as f32 {
-                        ScalingMode::Fixed {
-                            width: new_size.height as f32 * (aspect_ratio),
-                            height: new_size.height as f32,
-                        }
-                    } else {
-                        ScalingMode::Fixed {
-                            width: new_size.width as f32,
-                            height: new_size.width as f32 / (aspect_ratio),
-                        }
-                    }
-                }
-                FitMode::Crop => {
-                    let axis = new_size.height.min(new_size.width);
-                    if aspect_ratio > 1.0 {
-                        ScalingMode::Fixed {
-                            width: axis as f32,
-                            height: axis as f32 / (aspect_ratio),
-                        }
-                    } else {
-                        ScalingMode::Fixed {
-                            width: axis as f32 * (aspect_ratio),
-                            height: axis as f32,
-                        }
-                    }
-                }
-                FitMode::Stretch => ScalingMode::Fixed {
-                    width: new_size.width as f32,
-                    height: new_size.height as f32,
-                },
+            continue;
+        }
+        let resolution = sizing.resolution.clone();
+        viewport.bypass_change_detection().sized_for = Some(sizing);
+
+        // Work out every value that depends on the viewport size before applying any of them,
+        // so that the target, the projection and the sprite never disagree for a frame.
+        let size = viewport_size
+            .try_calculate(&resolution)
+            .unwrap_or_else(|_| {
+                report(
+                    &mut errors,
+                    entity,
+                    PixelCameraErrorKind::InvalidViewportSize,
+                );
+                viewport_size.calculate(&resolution)
+            });
+        let margin = if *smoothing { 2 } else { 0 };
+        let new_size = clamp_to_device_limits(
+            Extent3d {
+                width: size.width + margin,
+                height: size.height + margin,
+                ..size
+            },
+            render_device.as_deref(),
+        )
+        .unwrap_or_else(|size| {
+            report(&mut errors, entity, PixelCameraErrorKind::TargetTooLarge);
+            size
+        });
+        // The projection is sized for what actually fits in the (possibly clamped) target.
+        let visible_size = Extent3d {
+            width: new_size.width.saturating_sub(margin).max(1),
+            height: new_size.height.saturating_sub(margin).max(1),
+            ..new_size
+        };
+        let target_size = Vec2::new(resolution.width(), resolution.height());
+        let projection_size = fit_projection_size(viewport_size, visible_size, target_size);
+
+        // The viewport camera of a custom viewport is left to the user.
+        if let (false, Some(mut viewport_projection)) = (custom, viewport_projection) {
+            if let ViewportSize::Fixed {
+                fit: FitMode::Fit(clear_color) | FitMode::IntegerFit(clear_color),
+                ..
             }
-        } else {
-            ScalingMode::Fixed {
-                width: new_size.width as f32,
-                height: new_size.height as f32,
+            | ViewportSize::Custom {
+                fit: FitMode::Fit(clear_color) | FitMode::IntegerFit(clear_color),
+                ..
+            } = viewport_size
+            {
+                viewport_camera.clear_color = clear_color.clone();
             }
-        };
+            let unchanged = matches!(
+                viewport_projection.scaling_mode,
+                ScalingMode::Fixed { width, height }
+                    if width == projection_size.x && height == projection_size.y
+            );
+            if !unchanged {
+                viewport_projection.scaling_mode = ScalingMode::Fixed {
+                    width: projection_size.x,
+                    height: projection_size.y,
+                };
+            }
+        }
+
+        // The target is resized by `resize_target_image` and the sprite rect is updated by
+        // `smooth_camera`, both later in this frame and before any camera is updated.
+        if viewport.size != new_size {
+            viewport.size = new_size;
+        }
+    }
+}
+
+/// What the viewport of a [`PixelCamera`] was sized for by [`update_viewport_size`].
+#[derive(Clone)]
+pub(crate) struct ViewportSizing {
+    viewport_size: ViewportSize,
+    smoothing: bool,
+    /// The viewport camera, a new one has to get its projection.
+    camera: Entity,
+    /// The resolution of the part of the target that the viewport is shown on.
+    resolution: WindowResolution,
+}
+
+impl ViewportSizing {
+    fn same(&self, other: &ViewportSizing) -> bool {
+        self.smoothing == other.smoothing
+            && self.camera == other.camera
+            && self.resolution == other.resolution
+            && same_viewport_size(&self.viewport_size, &other.viewport_size)
+    }
+}
+
+/// Returns whether two viewport sizes are the same, including the functions of
+/// [`ViewportSize::Custom`]s, which aren't reflected.
+fn same_viewport_size(a: &ViewportSize, b: &ViewportSize) -> bool {
+    let same_func = match (a, b) {
+        (ViewportSize::Custom { func: a, .. }, ViewportSize::Custom { func: b, .. }) => {
+            std::ptr::fn_addr_eq(*a, *b)
+        }
+        _ => true,
+    };
+    same_func && a.reflect_partial_eq(b) == Some(true)
+}
+
+/// Returns the size of the area that the viewport camera sees, in texels, so that the
+/// viewport (`size`, without the smoothing margin) fits a target of the given logical size.
+pub(crate) fn fit_projection_size(
+    viewport_size: &ViewportSize,
+    size: Extent3d,
+    target_size: Vec2,
+) -> Vec2 {
+    let (ViewportSize::Fixed { fit, .. } | ViewportSize::Custom { fit, .. }) = viewport_size else {
+        return Vec2::new(size.width as f32, size.height as f32);
+    };
+    let aspect_ratio = target_size.x / target_size.y;
 
-        if *smoothing {
-            new_size.width += 2;
-            new_size.height += 2;
+    match fit {
+        FitMode::Fit(_) => {
+            if aspect_ratio > size.width as f32 / size.height as f32 {
+                Vec2::new(size.height as f32 * aspect_ratio, size.height as f32)
+            } else {
+                Vec2::new(size.width as f32, size.width as f32 / aspect_ratio)
+            }
         }
-        if let RenderTarget::Image(image_handle) = &camera.target {
-            if let Some(image) = images.get_mut(image_handle) {
-                image.resize(new_size);
+        FitMode::IntegerFit(_) => {
+            let scale =
+                (target_size / Vec2::new(size.width as f32, size.height as f32)).min_element();
+            // Scale down smoothly if the viewport doesn't fit at all.
+            let scale = if scale >= 1.0 { scale.floor() } else { scale };
+            target_size / scale
+        }
+        FitMode::Crop => {
+            let axis = size.height.min(size.width) as f32;
+            if aspect_ratio > 1.0 {
+                Vec2::new(axis, axis / aspect_ratio)
             } else {
-                error!("Pixel camera render target image doesn't exist!");
+                Vec2::new(axis * aspect_ratio, axis)
+            }
+        }
+        FitMode::Stretch => Vec2::new(size.width as f32, size.height as f32),
+    }
+}
+
+/// Run condition for [`resize_target_image`], so that it only claims
+/// mutable access to [`Assets<Image>`] when there is something to resize.
+pub(crate) fn target_resized(
+    viewports: Query<(), (With<PixelCamera>, Changed<PixelViewportReferences>)>,
+) -> bool {
+    !viewports.is_empty()
+}
+
+/// Resizes the render target of every [`PixelCamera`] whose viewport size has changed.
+#[allow(clippy::type_complexity)]
+pub(crate) fn resize_target_image(
+    mut cameras: Query<
+        (
+            Entity,
+            &PixelCamera,
+            &mut Camera,
+            &mut PixelViewportReferences,
+            Option<&mut OrthographicProjection>,
+            Option<&mut Projection>,
+        ),
+        Changed<PixelViewportReferences>,
+    >,
+    mut viewport_sprites: Query<&mut Handle<Image>, With<PixelViewport>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for (
+        entity,
+        PixelCamera {
+            target_format,
+            target_usage,
+            ..
+        },
+        mut camera,
+        mut viewport,
+        orthographic_projection,
+        projection,
+    ) in &mut cameras
+    {
+        refresh_projection(orthographic_projection, projection);
+        let new_size = viewport.size;
+        if let Some(image) = images.get_mut(&viewport.image) {
+            // Images supplied by the user may not be usable as a render target yet.
+            if !image.texture_descriptor.usage.contains(TARGET_USAGES) {
+                image.texture_descriptor.usage |= TARGET_USAGES;
             }
+            if image.texture_descriptor.size != new_size {
+                resize_target(image, new_size);
+            }
+        } else {
+            // The image has either been moved to the render world, so we can't resize it,
+            // or it has been removed. Create a new one and point everything at it instead.
+            if target_usage.contains(RenderAssetUsages::MAIN_WORLD) {
+                warn!(
+                    "PixelCamera {entity:?}'s render target image no longer exists, recreating it."
+                );
+            }
+            let image_handle =
+                images.add(create_target_image(new_size, *target_format, *target_usage));
+            rebind_target(
+                image_handle,
+                &mut camera,
+                &mut viewport,
+                &mut viewport_sprites,
+            );
+        }
+    }
+}
+
+/// Makes Bevy update a camera's projection for the new size of its render target this frame.
+///
+/// Bevy only notices that an image was resized or replaced through its asset events, which are
+/// sent after [`PostUpdate`]. Until then the camera would render one frame with the old size.
+pub(crate) fn refresh_projection(
+    orthographic_projection: Option<Mut<OrthographicProjection>>,
+    projection: Option<Mut<Projection>>,
+) {
+    if let Some(mut orthographic_projection) = orthographic_projection {
+        orthographic_projection.set_changed();
+    }
+    if let Some(mut projection) = projection {
+        projection.set_changed();
+    }
+}
+
+/// Run condition for [`recreate_removed_targets`], so that it only claims mutable access to
+/// [`Assets<Image>`] when the render target of a [`PixelCamera`] was removed, not for every
+/// change to any image.
+pub(crate) fn target_images_removed(
+    mut asset_events: EventReader<AssetEvent<Image>>,
+    cameras: Query<&PixelViewportReferences, With<PixelCamera>>,
+) -> bool {
+    let mut removed = false;
+    for event in asset_events.read() {
+        if let AssetEvent::Removed { id } = event {
+            removed |= cameras.iter().any(|viewport| viewport.image.id() == *id);
+        }
+    }
+    removed
+}
+
+/// Recreates the render target of a [`PixelCamera`] when its image is removed from [`Assets<Image>`].
+///
+/// Images that only live in the render world are removed from [`Assets<Image>`] on purpose,
+/// these are only recreated when they need to be resized.
+#[allow(clippy::type_complexity)]
+pub(crate) fn recreate_removed_targets(
+    mut asset_events: EventReader<AssetEvent<Image>>,
+    mut cameras: Query<(
+        Entity,
+        &PixelCamera,
+        &mut Camera,
+        &mut PixelViewportReferences,
+    )>,
+    mut viewport_sprites: Query<&mut Handle<Image>, With<PixelViewport>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for event in asset_events.read() {
+        let AssetEvent::Removed { id } = event else {
+            continue;
+        };
+        for (entity, pixel_camera, mut camera, mut viewport) in &mut cameras {
+            if viewport.image.id() != *id
+                || !pixel_camera
+                    .target_usage
+                    .contains(RenderAssetUsages::MAIN_WORLD)
+            {
+                continue;
+            }
+            warn!("PixelCamera {entity:?}'s render target image was removed, recreating it.");
+            let image_handle = images.add(create_target_image(
+                viewport.size,
+                pixel_camera.target_format,
+                pixel_camera.target_usage,
+            ));
+            rebind_target(
+                image_handle,
+                &mut camera,
+                &mut viewport,
+                &mut viewport_sprites,
+            );
+        }
+    }
+}
+
+/// Recreates the render targets of all [`PixelCamera`]s when the app is resumed.
+///
+/// Android can lose the GPU's resources while the app is in the background, which leaves the
+/// old targets showing garbage or nothing at all.
+#[allow(clippy::type_complexity)]
+pub(crate) fn recreate_resumed_targets(
+    mut lifetime: EventReader<ApplicationLifetime>,
+    mut cameras: Query<(&PixelCamera, &mut Camera, &mut PixelViewportReferences)>,
+    mut composites: Query<
+        (&PixelCamera, &mut Camera, &DirectCompositeTarget),
+        Without<PixelViewportReferences>,
+    >,
+    mut viewport_sprites: Query<&mut Handle<Image>, With<PixelViewport>>,
+    mut images: ResMut<Assets<Image>>,
+    mut pool: ResMut<TargetImagePool>,
+) {
+    if !lifetime
+        .read()
+        .any(|event| *event == ApplicationLifetime::Resumed)
+    {
+        return;
+    }
+
+    // The pooled images may have been lost as well.
+    pool.free.clear();
+
+    for (pixel_camera, mut camera, mut viewport) in &mut cameras {
+        if !viewport.owned_image {
+            // Images supplied by the user are uploaded to the GPU again instead.
+            images.get_mut(&viewport.image);
+            continue;
         }
+        let image_handle = images.add(create_target_image(
+            viewport.size,
+            pixel_camera.target_format,
+            pixel_camera.target_usage,
+        ));
+        rebind_target(
+            image_handle,
+            &mut camera,
+            &mut viewport,
+            &mut viewport_sprites,
+        );
+    }
+    for (pixel_camera, mut camera, target) in &mut composites {
+        camera.target = RenderTarget::Image(images.add(create_target_image(
+            target.size,
+            pixel_camera.target_format,
+            pixel_camera.target_usage,
+        )));
+    }
+}
+
+/// Points the camera, its viewport sprite and its viewport references at a new target image.
+fn rebind_target(
+    image_handle: Handle<Image>,
+    camera: &mut Camera,
+    viewport: &mut PixelViewportReferences,
+    viewport_sprites: &mut Query<&mut Handle<Image>, With<PixelViewport>>,
+) {
+    if let Ok(mut sprite_image) = viewport_sprites.get_mut(viewport.sprite) {
+        *sprite_image = image_handle.clone();
+    }
+    // Bypassed cameras render to the window until the bypass is removed.
+    if matches!(camera.target, RenderTarget::Image(_)) {
+        camera.target = RenderTarget::Image(image_handle.clone());
     }
+    viewport.image = image_handle;
+    viewport.owned_image = true;
 }
 
 /// Set the camera transform the rounded down version of the subpixel position
-pub(crate) fn set_camera_position(mut cameras: Query<(&PixelCamera, &mut Transform)>) {
-    for (PixelCamera { subpixel_pos, .. }, mut transform) in &mut cameras {
-        transform.translation.x = subpixel_pos.x.trunc();
-        transform.translation.y = subpixel_pos.y.trunc();
+///
+/// 3D cameras are moved through their [`Transform`] and are left alone.
+/// [`PixelBypass`]ed cameras render at the window's resolution, so they aren't snapped.
+#[allow(clippy::type_complexity)]
+pub(crate) fn set_camera_position(
+    mut cameras: Query<
+        (&PixelCamera, &mut Transform, Has<PixelBypass>),
+        (Without<PixelCamera3d>, Without<PixelFreeze>),
+    >,
+) {
+    for (PixelCamera { subpixel_pos, .. }, mut transform, bypassed) in &mut cameras {
+        let position = if bypassed {
+            *subpixel_pos
+        } else {
+            subpixel_pos.trunc()
+        };
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
+
+/// Snaps the translation of [`PixelCamera3d::GridSnapped`] cameras to the texel grid of
+/// their view plane.
+///
+/// The position on the view plane is written to [`PixelCamera::subpixel_pos`] in texels,
+/// so that [`smooth_camera`] can smooth the remainder like it does for 2D cameras.
+#[allow(clippy::type_complexity)]
+pub(crate) fn snap_3d_cameras(
+    mut cameras: Query<
+        (
+            Entity,
+            &mut PixelCamera,
+            &PixelCamera3d,
+            &Projection,
+            &PixelViewportReferences,
+            &mut Transform,
+        ),
+        Without<PixelFreeze>,
+    >,
+) {
+    for (entity, mut pixel_camera, camera_3d, projection, viewport, mut transform) in &mut cameras {
+        let PixelCamera3d::GridSnapped {
+            position,
+            focus_distance,
+        } = *camera_3d
+        else {
+            continue;
+        };
+        if viewport.size.width == 0 || viewport.size.height == 0 {
+            continue;
+        }
+
+        // The size of a texel on the view plane, in world units.
+        let texel_size = match projection {
+            Projection::Orthographic(projection) => Vec2::new(
+                projection.area.width() / viewport.size.width as f32,
+                projection.area.height() / viewport.size.height as f32,
+            ),
+            // Texels are square, so the vertical field of view gives both axes.
+            Projection::Perspective(projection) => Vec2::splat(
+                2.0 * focus_distance * (projection.fov / 2.0).tan() / viewport.size.height as f32,
+            ),
+        };
+        if !(texel_size.x > 0.0 && texel_size.y > 0.0) {
+            warn_once!("PixelCamera {entity:?}: Can't snap to a grid of size {texel_size}");
+            continue;
+        }
+
+        let local = transform.rotation.inverse() * position;
+        let texels = local.truncate() / texel_size;
+        let snapped = (texels.trunc() * texel_size).extend(local.z);
+
+        transform.translation = transform.rotation * snapped;
+        if pixel_camera.subpixel_pos != texels {
+            pixel_camera.subpixel_pos = texels;
+        }
     }
 }
 
 /// Smooth the camera's subpixel position
 #[allow(clippy::type_complexity)]
 pub(crate) fn smooth_camera(
-    mut cameras: Query<(&PixelCamera, &PixelViewportReferences)>,
-    mut viewports: Query<
-        (&mut Sprite, &Handle<Image>),
-        (With<PixelViewport>, Without<PixelViewportReferences>),
-    >,
-    images: Res<Assets<Image>>,
+    mut cameras: Query<(Entity, &PixelCamera, &PixelViewportReferences), Without<PixelFreeze>>,
+    mut viewports: Query<&mut Sprite, (With<PixelViewport>, Without<PixelViewportReferences>)>,
+    mut errors: EventWriter<PixelCameraError>,
 ) {
     for (
+        entity,
         PixelCamera {
             subpixel_pos,
             smoothing,
@@ -288,17 +1999,23 @@ pub(crate) fn smooth_camera(
         viewport,
     ) in &mut cameras
     {
+        let mut sprite = match viewports.get_mut(viewport.sprite) {
+            Ok(sprite) => sprite,
+            // Missing viewport sprites are respawned by `repair_viewport`.
+            Err(QueryEntityError::NoSuchEntity(_)) => continue,
+            Err(_) => {
+                report(&mut errors, entity, PixelCameraErrorKind::MissingViewport);
+                continue;
+            }
+        };
         if !smoothing {
+            // Smoothing may have been turned off at runtime, show the whole target again.
+            if sprite.rect.is_some() {
+                sprite.rect = None;
+            }
             continue;
         }
-        let (mut sprite, handle) = viewports.get_mut(viewport.sprite).unwrap();
-        let Some(image) = images.get(handle) else {
-            error!(
-                "Pixel camera viewport ({:?}) image doesn't exist",
-                viewport.sprite
-            );
-            continue;
-        };
+        let size = Vec2::new(viewport.size.width as f32, viewport.size.height as f32);
 
         // In order to get smooth camera movement while retaining pixel perfection,
         // we can move the viewport's transform by the remainder of the subpixel.
@@ -312,7 +2029,55 @@ pub(crate) fn smooth_camera(
 
         sprite.rect = Some(Rect {
             min: Vec2::ONE + remainder,
-            max: image.size_f32() - Vec2::ONE + remainder,
+            max: size - Vec2::ONE + remainder,
         })
     }
 }
+
+/// Shows the render target of [`PixelCamera`]s on their [`ViewportMirror`]s, with the same
+/// smoothing rect as the viewport sprite.
+#[allow(clippy::type_complexity)]
+pub(crate) fn sync_viewport_mirrors(
+    mut mirrors: Query<(&ViewportMirror, &mut Sprite, &mut Handle<Image>), Without<PixelViewport>>,
+    cameras: Query<&PixelViewportReferences>,
+    viewports: Query<&Sprite, (With<PixelViewport>, Without<ViewportMirror>)>,
+) {
+    for (ViewportMirror(camera), mut sprite, mut image) in &mut mirrors {
+        let Ok(viewport) = cameras.get(*camera) else {
+            continue;
+        };
+        if *image != viewport.image {
+            *image = viewport.image.clone();
+        }
+        if let Ok(viewport_sprite) = viewports.get(viewport.sprite) {
+            if sprite.rect != viewport_sprite.rect {
+                sprite.rect = viewport_sprite.rect;
+            }
+        }
+    }
+}
+
+/// Push the side planes of the camera's frustum outwards by the culling margin.
+///
+/// Uses the same filter as Bevy's `update_frusta`, so the frustum is only expanded
+/// right after it has been recalculated.
+#[allow(clippy::type_complexity)]
+pub(crate) fn expand_culling_frustum(
+    mut cameras: Query<
+        (&PixelCamera, &OrthographicProjection, &mut Frustum),
+        Or<(Changed<GlobalTransform>, Changed<OrthographicProjection>)>,
+    >,
+) {
+    for (PixelCamera { culling_margin, .. }, projection, mut frustum) in &mut cameras {
+        let margin = culling_margin * projection.scale;
+        if margin <= 0.0 {
+            continue;
+        }
+        // The first four half spaces are the left, right, bottom and top planes.
+        // Their normals are normalized, so increasing `d` moves them outwards by that distance.
+        for half_space in &mut frustum.half_spaces[..4] {
+            let normal_d = half_space.normal_d();
+            *half_space = HalfSpace::new(normal_d + Vec4::W * margin);
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! Crisp [`Text2dBundle`] text in the low resolution world.
+//!
+//! Bevy rasterizes 2D text at the scale factor of the primary window, so on a window with a
+//! scale factor of 2 every glyph is drawn twice as large and then squeezed into the
+//! low resolution target, which blurs it. Text can also land between texels, which makes it
+//! jitter against the snapped camera.
+
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use bevy::text::TextLayoutInfo;
+use bevy::window::PrimaryWindow;
+
+/// Renders the text of a [`Text2dBundle`] with one font pixel per texel, aligned to the texel grid.
+///
+/// Bitmap fonts should use a [`PixelText::font_size`] that's a multiple of the size they were drawn at.
+/// Assumes that one world unit is one texel (an [`OrthographicProjection::scale`] of `1.0`).
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct PixelText {
+    /// The font size of every section of the text, in texels.
+    ///
+    /// Overrides the font sizes in the text's [`TextStyle`]s.
+    pub font_size: f32,
+}
+
+impl PixelText {
+    /// Creates a new [`PixelText`] that renders the text `font_size` texels tall.
+    pub fn new(font_size: f32) -> Self {
+        Self { font_size }
+    }
+}
+
+fn scale_factor(primary_window: &Query<&Window, With<PrimaryWindow>>) -> f32 {
+    primary_window
+        .get_single()
+        .map(|window| window.resolution.scale_factor())
+        .unwrap_or(1.0)
+}
+
+/// Picks the font size that is rasterized at exactly [`PixelText::font_size`] pixels.
+pub(crate) fn scale_pixel_text(
+    mut texts: Query<(&PixelText, &mut Text)>,
+    primary_window: Query<&Window, With<PrimaryWindow>>,
+) {
+    let scale_factor = scale_factor(&primary_window);
+
+    for (pixel_text, mut text) in &mut texts {
+        let font_size = pixel_text.font_size / scale_factor;
+        if text
+            .sections
+            .iter()
+            .any(|section| section.style.font_size != font_size)
+        {
+            for section in &mut text.sections {
+                section.style.font_size = font_size;
+            }
+        }
+    }
+}
+
+/// Makes sure that text is snapped again after its layout changes, even if it didn't move.
+pub(crate) fn resnap_changed_text(
+    mut texts: Query<&mut Transform, (With<PixelText>, Changed<TextLayoutInfo>)>,
+) {
+    for mut transform in &mut texts {
+        transform.set_changed();
+    }
+}
+
+/// Scales text back up by the scale factor and snaps its top left corner to the texel grid.
+///
+/// Only the [`GlobalTransform`] is changed, so text can still be moved smoothly through its [`Transform`].
+/// Runs right after transform propagation, and only on freshly propagated transforms.
+#[allow(clippy::type_complexity)]
+pub(crate) fn snap_pixel_text(
+    mut texts: Query<
+        (&mut GlobalTransform, &TextLayoutInfo, &Anchor),
+        (With<PixelText>, Changed<GlobalTransform>),
+    >,
+    primary_window: Query<&Window, With<PrimaryWindow>>,
+) {
+    let scale_factor = scale_factor(&primary_window);
+
+    for (mut transform, layout, anchor) in &mut texts {
+        let scaled = transform.mul_transform(Transform::from_scale(Vec3::new(
+            scale_factor,
+            scale_factor,
+            1.0,
+        )));
+        // Where Bevy will place the corner of the text, relative to the entity.
+        let alignment = layout.logical_size * -(anchor.as_vec() + 0.5) * scale_factor;
+
+        let mut affine = scaled.affine();
+        let corner = affine.translation.truncate() + alignment;
+        affine.translation.x = corner.x.round() - alignment.x;
+        affine.translation.y = corner.y.round() - alignment.y;
+        *transform = GlobalTransform::from(affine);
+    }
+}
@@ -0,0 +1,104 @@
+//! Camera bounds for [Tiled](https://www.mapeditor.org/) maps.
+//!
+//! This works with any Tiled-based crate: add [`TiledMapBounds`] to the entity that
+//! holds the map (e.g. with [`TiledMapBounds::from_map`]), and [`TiledCameraBounds`]
+//! to the [`PixelCamera`].
+
+use bevy::prelude::*;
+use tiled::{Map, Orientation};
+
+use crate::bounds::CameraBounds;
+use crate::components::PixelCamera;
+use crate::CameraSystems;
+
+/// Keeps the [`CameraBounds`] of cameras with [`TiledCameraBounds`] on the active Tiled map.
+pub struct PixelCameraTiledPlugin;
+impl Plugin for PixelCameraTiledPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            update_tiled_bounds.before(CameraSystems::Bounds),
+        );
+    }
+}
+
+/// The area covered by a Tiled map, in the map entity's local space.
+///
+/// The map that was added or changed last is the active map.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct TiledMapBounds {
+    /// The corner of the map closest to negative infinity, relative to the map entity.
+    pub origin: Vec2,
+    /// The size of the map, in world units.
+    pub size: Vec2,
+}
+
+impl TiledMapBounds {
+    /// Calculates the bounds of a map from its dimensions and tile size.
+    ///
+    /// Isometric maps are measured by their diamond's bounding box, every other
+    /// orientation is measured as a grid of tiles. The origin is always zero, set
+    /// [`TiledMapBounds::origin`] if your crate places the map differently.
+    pub fn from_map(map: &Map) -> Self {
+        let tiles = Vec2::new(map.width as f32, map.height as f32);
+        let tile_size = Vec2::new(map.tile_width as f32, map.tile_height as f32);
+
+        let size = match map.orientation {
+            Orientation::Isometric => Vec2::splat(tiles.x + tiles.y) * tile_size / 2.0,
+            _ => tiles * tile_size,
+        };
+
+        Self {
+            origin: Vec2::ZERO,
+            size,
+        }
+    }
+}
+
+/// Sets the [`CameraBounds`] of a [`PixelCamera`] to the active [`TiledMapBounds`].
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct TiledCameraBounds;
+
+#[allow(clippy::type_complexity)]
+fn update_tiled_bounds(
+    maps: Query<(Entity, Ref<TiledMapBounds>, Ref<GlobalTransform>)>,
+    mut cameras: Query<
+        (Entity, Ref<TiledCameraBounds>, Option<&mut CameraBounds>),
+        With<PixelCamera>,
+    >,
+    mut active_map: Local<Option<Entity>>,
+    mut commands: Commands,
+) {
+    let mut map_changed = false;
+    for (entity, map, _) in &maps {
+        if map.is_changed() {
+            *active_map = Some(entity);
+            map_changed = true;
+        }
+    }
+    let Some(Ok((_, map, transform))) = active_map.map(|entity| maps.get(entity)) else {
+        return;
+    };
+    // The active map may also have been moved.
+    map_changed |= transform.is_changed();
+    let rect = Rect::from_corners(
+        transform.transform_point(map.origin.extend(0.0)).truncate(),
+        transform
+            .transform_point((map.origin + map.size).extend(0.0))
+            .truncate(),
+    );
+
+    for (entity, marker, bounds) in &mut cameras {
+        if !map_changed && !marker.is_added() {
+            continue;
+        }
+        match bounds {
+            Some(mut bounds) => {
+                bounds.set_if_neq(CameraBounds(rect));
+            }
+            None => {
+                commands.entity(entity).insert(CameraBounds(rect));
+            }
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! Helpers for aligning tilemaps with a [`PixelCamera`](crate::components::PixelCamera).
+//!
+//! They work on a tilemap's size in tiles, the size of its grid and its [`GlobalTransform`], so
+//! they can be used with any tilemap crate. Tiles are centered on their grid position, like in
+//! `bevy_ecs_tilemap`. Only square tilemaps are supported.
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_smooth_pixel_camera::bounds::CameraBounds;
+//! # use bevy_smooth_pixel_camera::tilemap::tilemap_rect;
+//! fn bound_camera_to_map(mut commands: Commands, camera: Entity, map: &GlobalTransform) {
+//!     let rect = tilemap_rect(UVec2::new(40, 30), Vec2::splat(16.0), map);
+//!     commands.entity(camera).insert(CameraBounds(rect));
+//! }
+//! ```
+
+use bevy::prelude::*;
+
+/// Returns the area of the world covered by a tilemap of `size` tiles.
+pub fn tilemap_rect(size: UVec2, grid_size: Vec2, transform: &GlobalTransform) -> Rect {
+    // Tiles are centered on their grid position.
+    let min = -grid_size / 2.0;
+    let max = size.as_vec2() * grid_size + min;
+
+    Rect::from_corners(
+        transform.transform_point(min.extend(0.0)).truncate(),
+        transform.transform_point(max.extend(0.0)).truncate(),
+    )
+}
+
+/// Returns the positions of the first and last tiles inside `visible`,
+/// e.g. the [`PixelCamera::visible_rect`](crate::components::PixelCamera::visible_rect) of a camera.
+///
+/// Returns [`None`] if none of the tilemap is visible.
+pub fn visible_tiles(
+    visible: Rect,
+    size: UVec2,
+    grid_size: Vec2,
+    transform: &GlobalTransform,
+) -> Option<(UVec2, UVec2)> {
+    if size.x == 0 || size.y == 0 {
+        return None;
+    }
+    let inverse = transform.affine().inverse();
+    let local = Rect::from_corners(
+        inverse.transform_point3(visible.min.extend(0.0)).truncate(),
+        inverse.transform_point3(visible.max.extend(0.0)).truncate(),
+    );
+
+    let min = ((local.min + grid_size / 2.0) / grid_size).floor();
+    let max = ((local.max + grid_size / 2.0) / grid_size).floor();
+    let last = (size - UVec2::ONE).as_vec2();
+    if max.x < 0.0 || max.y < 0.0 || min.x > last.x || min.y > last.y {
+        return None;
+    }
+
+    Some((
+        min.clamp(Vec2::ZERO, last).as_uvec2(),
+        max.clamp(Vec2::ZERO, last).as_uvec2(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: UVec2 = UVec2::new(10, 8);
+    const GRID: Vec2 = Vec2::splat(16.0);
+
+    fn visible(min: Vec2, max: Vec2, transform: &GlobalTransform) -> Option<(UVec2, UVec2)> {
+        visible_tiles(Rect::from_corners(min, max), SIZE, GRID, transform)
+    }
+
+    #[test]
+    fn tilemap_rect_covers_every_tile() {
+        let rect = tilemap_rect(SIZE, GRID, &GlobalTransform::IDENTITY);
+        assert_eq!(rect, Rect::new(-8.0, -8.0, 152.0, 120.0));
+
+        let moved = GlobalTransform::from_xyz(100.0, -50.0, 0.0);
+        let rect = tilemap_rect(SIZE, GRID, &moved);
+        assert_eq!(rect, Rect::new(92.0, -58.0, 252.0, 70.0));
+    }
+
+    #[test]
+    fn visible_tiles_inside() {
+        let transform = GlobalTransform::IDENTITY;
+        assert_eq!(
+            visible(Vec2::ZERO, Vec2::splat(32.0), &transform),
+            Some((UVec2::ZERO, UVec2::splat(2)))
+        );
+        // Edges that fall exactly between two tiles include the next tile.
+        assert_eq!(
+            visible(Vec2::splat(8.0), Vec2::splat(24.0), &transform),
+            Some((UVec2::ONE, UVec2::splat(2)))
+        );
+    }
+
+    #[test]
+    fn visible_tiles_clamped_to_the_tilemap() {
+        let transform = GlobalTransform::IDENTITY;
+        assert_eq!(
+            visible(Vec2::splat(-1000.0), Vec2::splat(1000.0), &transform),
+            Some((UVec2::ZERO, SIZE - UVec2::ONE))
+        );
+        assert_eq!(
+            visible(Vec2::new(-100.0, 40.0), Vec2::new(20.0, 1000.0), &transform),
+            Some((UVec2::new(0, 3), UVec2::new(1, 7)))
+        );
+    }
+
+    #[test]
+    fn visible_tiles_outside() {
+        let transform = GlobalTransform::IDENTITY;
+        assert_eq!(
+            visible(Vec2::splat(-100.0), Vec2::splat(-9.0), &transform),
+            None
+        );
+        assert_eq!(
+            visible(Vec2::new(152.0, 0.0), Vec2::new(200.0, 50.0), &transform),
+            None
+        );
+    }
+
+    #[test]
+    fn visible_tiles_transformed() {
+        let moved = GlobalTransform::from_xyz(160.0, 0.0, 0.0);
+        assert_eq!(visible(Vec2::ZERO, Vec2::splat(32.0), &moved), None);
+        assert_eq!(
+            visible(Vec2::new(160.0, 0.0), Vec2::new(192.0, 32.0), &moved),
+            Some((UVec2::ZERO, UVec2::splat(2)))
+        );
+
+        let scaled = GlobalTransform::from_scale(Vec3::splat(2.0));
+        assert_eq!(
+            visible(Vec2::ZERO, Vec2::splat(64.0), &scaled),
+            Some((UVec2::ZERO, UVec2::splat(2)))
+        );
+    }
+
+    #[test]
+    fn visible_tiles_empty() {
+        let transform = GlobalTransform::IDENTITY;
+        // A visible area without any size still touches one tile.
+        assert_eq!(
+            visible(Vec2::splat(20.0), Vec2::splat(20.0), &transform),
+            Some((UVec2::ONE, UVec2::ONE))
+        );
+        // A tilemap without tiles is never visible.
+        for size in [UVec2::ZERO, UVec2::new(10, 0), UVec2::new(0, 8)] {
+            let visible = Rect::new(-1000.0, -1000.0, 1000.0, 1000.0);
+            assert_eq!(visible_tiles(visible, size, GRID, &transform), None);
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Lenses for animating a [`PixelCamera`] with any tweening crate.
+//!
+//! Each lens has a `lerp` method with the same signature as `bevy_tweening`'s `Lens::lerp`,
+//! so it can be called from a tweening crate's lens, or from your own systems.
+//!
+//! ```ignore
+//! use bevy_tweening::Lens;
+//!
+//! struct CameraPosition(PixelCameraPositionLens);
+//!
+//! impl Lens<PixelCamera> for CameraPosition {
+//!     fn lerp(&mut self, target: &mut PixelCamera, ratio: f32) {
+//!         self.0.lerp(target, ratio);
+//!     }
+//! }
+//! ```
+
+use bevy::prelude::*;
+
+use crate::components::PixelCamera;
+use crate::viewport::ViewportSize;
+
+/// A lens that moves [`PixelCamera::subpixel_pos`] from `start` to `end`.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// # use bevy_smooth_pixel_camera::tweening::PixelCameraPositionLens;
+/// let mut camera = PixelCamera::from_size(ViewportSize::PixelFixed(4));
+/// let mut lens = PixelCameraPositionLens {
+///     start: Vec2::ZERO,
+///     end: Vec2::new(100.0, 0.0),
+/// };
+///
+/// lens.lerp(&mut camera, 0.5);
+/// assert_eq!(camera.subpixel_pos, Vec2::new(50.0, 0.0));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PixelCameraPositionLens {
+    /// The position at the start of the animation.
+    pub start: Vec2,
+    /// The position at the end of the animation.
+    pub end: Vec2,
+}
+
+impl PixelCameraPositionLens {
+    /// Moves the camera to the position at `ratio` (from 0 to 1) of the animation.
+    pub fn lerp(&mut self, target: &mut PixelCamera, ratio: f32) {
+        target.subpixel_pos = self.start.lerp(self.end, ratio);
+    }
+}
+
+/// A lens that steps the scale of [`ViewportSize::PixelFixed`] from `start` to `end`.
+///
+/// The scale is rounded to the nearest whole number, so the camera stays pixel perfect
+/// and the render target is only resized when the scale actually changes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PixelCameraScaleLens {
+    /// The pixel scale at the start of the animation.
+    pub start: u32,
+    /// The pixel scale at the end of the animation.
+    pub end: u32,
+}
+
+impl PixelCameraScaleLens {
+    /// Sets the camera to the pixel scale at `ratio` (from 0 to 1) of the animation.
+    pub fn lerp(&mut self, target: &mut PixelCamera, ratio: f32) {
+        let scale = (self.start as f32 + (self.end as f32 - self.start as f32) * ratio)
+            .round()
+            .max(1.0) as u32;
+
+        if !matches!(target.viewport_size, ViewportSize::PixelFixed(current) if current == scale) {
+            target.viewport_size = ViewportSize::PixelFixed(scale);
+        }
+    }
+}
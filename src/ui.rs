@@ -0,0 +1,535 @@
+//! `bevy_ui` support: interaction with nodes rendered by a [`PixelCamera`], [`SyncUiScale`],
+//! pixelated UI with [`PixelUi`], and showing the viewport in a UI node with [`PixelUiViewport`].
+//!
+//! Bevy's [`Interaction`] only works for UI rendered directly to a window. UI rendered by a
+//! [`PixelCamera`] (through [`TargetCamera`]) ends up in the low resolution image, so its
+//! hit-testing has to go through the viewport. Add [`PixelInteraction`] to those nodes instead.
+
+use bevy::ecs::entity::{EntityMapper, MapEntities};
+use bevy::ecs::reflect::ReflectMapEntities;
+use bevy::input::touch::Touches;
+use bevy::prelude::*;
+use bevy::render::camera::{ClearColorConfig, NormalizedRenderTarget, RenderTarget, ScalingMode};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureFormat};
+use bevy::render::texture::BevyDefault;
+use bevy::render::view::RenderLayers;
+use bevy::ui::{CalculatedClip, DefaultUiCamera, FocusPolicy, UiStack};
+use bevy::utils::{HashMap, HashSet};
+use bevy::window::PrimaryWindow;
+
+use crate::components::{
+    PixelCamera, PixelViewport, PixelViewportReferences, ViewportCamera, ViewportTarget,
+};
+use crate::coords::ViewportChain;
+use crate::cursor::PointerCaptured;
+use crate::systems::{create_target_image, resize_target};
+
+/// The [`Interaction`] of a UI node that is rendered by a [`PixelCamera`].
+///
+/// Works just like [`Interaction`], except the cursor is mapped through the pixel camera's viewport.
+/// The node needs a [`TargetCamera`] pointing at the [`PixelCamera`] (or the pixel camera must be
+/// the default UI camera).
+#[derive(Component, Copy, Clone, Eq, PartialEq, Debug, Default, Reflect)]
+pub enum PixelInteraction {
+    /// The node has been pressed.
+    Pressed,
+    /// The node has been hovered over.
+    Hovered,
+    /// Nothing has happened.
+    #[default]
+    None,
+}
+
+/// Keeps Bevy's [`UiScale`] at the size of one of this [`PixelCamera`]'s pixels on the window,
+/// so that a native resolution HUD lines up with the pixels of the game.
+///
+/// Only add this to one camera. Since [`UiScale`] applies to all UI, this doesn't mix well
+/// with UI that is rendered by the pixel camera itself.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct SyncUiScale;
+
+/// Renders the UI at the resolution of a [`PixelCamera`], so that the HUD gets the same chunky
+/// pixels as the game.
+///
+/// The UI is rendered by an extra camera into its own low resolution image, which is the size of
+/// the visible part of the viewport and shown on top of it. Unlike the world, the UI doesn't move
+/// with the smoothing. The extra camera is made the [`IsDefaultUiCamera`], so it renders every
+/// node without a [`TargetCamera`]. Add [`PixelInteraction`] to the nodes to interact with them.
+///
+/// Only add this to one camera. Removing it renders the UI at native resolution again.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn setup(mut commands: Commands) {
+///     commands.spawn((
+///         Camera2dBundle::default(),
+///         PixelCamera::from_size(ViewportSize::PixelFixed(4)),
+///         PixelUi,
+///     ));
+///     commands.spawn(TextBundle::from_section("Score: 0", TextStyle::default()));
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub struct PixelUi;
+
+/// The UI camera, image and sprite of a [`PixelCamera`] with [`PixelUi`].
+#[derive(Component)]
+pub(crate) struct PixelUiLink {
+    camera: Entity,
+    sprite: Entity,
+    image: Handle<Image>,
+}
+/// Marks the UI camera of [`PixelUi`], and points back to its [`PixelCamera`].
+#[derive(Component)]
+pub(crate) struct PixelUiCamera(Entity);
+
+/// Returns the size of the visible part of a viewport, without the smoothing margin.
+fn visible_size(pixel_camera: &PixelCamera, viewport: &PixelViewportReferences) -> Extent3d {
+    let margin = if pixel_camera.smoothing { 2 } else { 0 };
+    Extent3d {
+        width: viewport.size.width.saturating_sub(margin).max(1),
+        height: viewport.size.height.saturating_sub(margin).max(1),
+        depth_or_array_layers: 1,
+    }
+}
+
+/// Adds an image to [`Assets<Image>`] once the commands are applied, so that systems that
+/// rarely add images don't need mutable access to the assets every frame.
+fn add_image_deferred(
+    images: &Assets<Image>,
+    commands: &mut Commands,
+    image: Image,
+) -> Handle<Image> {
+    let handle = images.reserve_handle();
+    let id = handle.id();
+    commands.add(move |world: &mut World| {
+        world.resource_mut::<Assets<Image>>().insert(id, image);
+    });
+    handle
+}
+
+/// Resizes an image in [`Assets<Image>`] once the commands are applied.
+fn resize_image_deferred(commands: &mut Commands, id: AssetId<Image>, size: Extent3d) {
+    commands.add(move |world: &mut World| {
+        if let Some(image) = world.resource_mut::<Assets<Image>>().get_mut(id) {
+            resize_target(image, size);
+        }
+    });
+}
+
+/// Spawns and despawns the UI cameras and sprites of [`PixelUi`].
+#[allow(clippy::type_complexity)]
+pub(crate) fn manage_pixel_ui(
+    cameras: Query<(
+        Entity,
+        &PixelCamera,
+        &PixelViewportReferences,
+        Has<PixelUi>,
+        Option<&PixelUiLink>,
+    )>,
+    ui_cameras: Query<(Entity, &PixelUiCamera)>,
+    pixel_cameras: Query<(), With<PixelCamera>>,
+    images: Res<Assets<Image>>,
+    mut commands: Commands,
+) {
+    for (entity, pixel_camera, viewport, enabled, link) in &cameras {
+        match (enabled, link) {
+            (true, None) => {
+                let image = add_image_deferred(
+                    &images,
+                    &mut commands,
+                    create_target_image(
+                        visible_size(pixel_camera, viewport),
+                        TextureFormat::bevy_default(),
+                        RenderAssetUsages::default(),
+                    ),
+                );
+                let camera = commands
+                    .spawn((
+                        Camera2dBundle {
+                            camera: Camera {
+                                // Before the viewport camera, which shows the image.
+                                order: pixel_camera.viewport_order - 1,
+                                target: RenderTarget::Image(image.clone()),
+                                clear_color: ClearColorConfig::Custom(Color::NONE),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        // Only the UI is rendered.
+                        RenderLayers::none(),
+                        IsDefaultUiCamera,
+                        PixelUiCamera(entity),
+                    ))
+                    .id();
+                // Over the viewport sprite, without its smoothing rect.
+                let sprite = commands
+                    .spawn((
+                        SpriteBundle {
+                            texture: image.clone(),
+                            transform: Transform::from_xyz(0.0, 0.0, 1.0),
+                            ..default()
+                        },
+                        pixel_camera.viewport_layer,
+                    ))
+                    .set_parent(viewport.sprite)
+                    .id();
+                commands.entity(entity).insert(PixelUiLink {
+                    camera,
+                    sprite,
+                    image,
+                });
+            }
+            (false, Some(link)) => {
+                commands.entity(link.camera).despawn();
+                if let Some(sprite) = commands.get_entity(link.sprite) {
+                    sprite.despawn_recursive();
+                }
+                commands.entity(entity).remove::<PixelUiLink>();
+            }
+            _ => {}
+        }
+    }
+
+    // UI cameras of pixel cameras that were despawned.
+    for (ui_camera, PixelUiCamera(owner)) in &ui_cameras {
+        if !pixel_cameras.contains(*owner) {
+            commands.entity(ui_camera).despawn();
+        }
+    }
+}
+
+/// Keeps the UI images of [`PixelUi`] at the visible size of the viewport, and the UI camera and
+/// sprite in line with the viewport.
+#[allow(clippy::type_complexity)]
+pub(crate) fn sync_pixel_ui(
+    cameras: Query<(&PixelCamera, &PixelViewportReferences, &PixelUiLink)>,
+    viewport_cameras: Query<&Camera, (With<ViewportCamera>, Without<PixelUiCamera>)>,
+    mut ui_cameras: Query<&mut Camera, With<PixelUiCamera>>,
+    mut sprites: Query<&mut RenderLayers, Without<PixelUiCamera>>,
+    images: Res<Assets<Image>>,
+    mut commands: Commands,
+) {
+    for (pixel_camera, viewport, link) in &cameras {
+        let size = visible_size(pixel_camera, viewport);
+        if images
+            .get(&link.image)
+            .is_some_and(|image| image.texture_descriptor.size != size)
+        {
+            resize_image_deferred(&mut commands, link.image.id(), size);
+        }
+
+        if let (Ok(viewport_camera), Ok(mut camera)) = (
+            viewport_cameras.get(viewport.camera),
+            ui_cameras.get_mut(link.camera),
+        ) {
+            let order = viewport_camera.order - 1;
+            if camera.is_active != viewport_camera.is_active || camera.order != order {
+                camera.is_active = viewport_camera.is_active;
+                camera.order = order;
+            }
+        }
+        if let Ok(mut layer) = sprites.get_mut(link.sprite) {
+            layer.set_if_neq(pixel_camera.viewport_layer);
+        }
+    }
+}
+
+/// Shows the viewport of the [`PixelCamera`] entity it holds in the `bevy_ui` image node it's on,
+/// so that the pixelated view can be laid out with the rest of the UI, e.g. in a panel of an
+/// editor.
+///
+/// The viewport camera renders to an image that is kept at the physical size of the node, and
+/// set as the node's [`UiImage`]. The viewport is sized and fit (letterboxed) for the node
+/// instead of a window, and it's still smoothed. The image is resized a frame after the node's
+/// layout changes.
+///
+/// Give the node a size that doesn't depend on its image (e.g. with [`Style::flex_grow`] or a
+/// percentage), since the image is sized for the node. The UI itself needs a camera on the
+/// window that renders after the viewport camera, without seeing the world.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy::render::view::RenderLayers;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn setup(mut commands: Commands) {
+///     let camera = commands
+///         .spawn((Camera2dBundle::default(), PixelCamera::default()))
+///         .id();
+///     // The camera for the UI.
+///     commands.spawn((
+///         Camera2dBundle {
+///             camera: Camera {
+///                 order: 2,
+///                 ..default()
+///             },
+///             ..default()
+///         },
+///         RenderLayers::none(),
+///     ));
+///
+///     commands
+///         .spawn(NodeBundle {
+///             style: Style {
+///                 width: Val::Percent(100.0),
+///                 height: Val::Percent(100.0),
+///                 ..default()
+///             },
+///             ..default()
+///         })
+///         .with_children(|parent| {
+///             // A side panel, with the game view taking up the rest of the window.
+///             parent.spawn(NodeBundle {
+///                 style: Style {
+///                     width: Val::Px(200.0),
+///                     ..default()
+///                 },
+///                 background_color: Color::DARK_GRAY.into(),
+///                 ..default()
+///             });
+///             parent.spawn((
+///                 ImageBundle {
+///                     style: Style {
+///                         flex_grow: 1.0,
+///                         ..default()
+///                     },
+///                     ..default()
+///                 },
+///                 PixelUiViewport(camera),
+///             ));
+///         });
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component, MapEntities)]
+pub struct PixelUiViewport(pub Entity);
+
+impl MapEntities for PixelUiViewport {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.0 = entity_mapper.map_entity(self.0);
+    }
+}
+
+/// Points the viewports of the cameras of [`PixelUiViewport`] nodes at an image of the size of
+/// the node, and shows it on the node.
+pub(crate) fn update_ui_viewports(
+    mut nodes: Query<(&PixelUiViewport, &Node, &mut UiImage, Option<&TargetCamera>)>,
+    cameras: Query<Option<&ViewportTarget>, With<PixelCamera>>,
+    ui_cameras: Query<&Camera>,
+    default_ui_camera: DefaultUiCamera,
+    ui_scale: Res<UiScale>,
+    images: Res<Assets<Image>>,
+    mut commands: Commands,
+) {
+    for (&PixelUiViewport(camera), node, mut ui_image, target_camera) in &mut nodes {
+        let Ok(viewport_target) = cameras.get(camera) else {
+            continue;
+        };
+        // The node is laid out for the target of the camera that renders the UI, like Bevy does.
+        let scale_factor = target_camera
+            .map(TargetCamera::entity)
+            .or_else(|| default_ui_camera.get())
+            .and_then(|ui_camera| ui_cameras.get(ui_camera).ok())
+            .and_then(Camera::target_scaling_factor)
+            .unwrap_or(1.0);
+        let size = node
+            .physical_size(scale_factor, ui_scale.0)
+            .round()
+            .as_uvec2()
+            .max(UVec2::ONE);
+        let size = Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        };
+
+        // Keep using the image of the node while the viewport is shown on it.
+        let current = match viewport_target {
+            Some(ViewportTarget(RenderTarget::Image(image))) if *image == ui_image.texture => {
+                images.get(image).map(|current| (image.id(), current))
+            }
+            _ => None,
+        };
+        match current {
+            Some((id, image)) => {
+                if image.texture_descriptor.size != size {
+                    resize_image_deferred(&mut commands, id, size);
+                }
+            }
+            None => {
+                let image = add_image_deferred(
+                    &images,
+                    &mut commands,
+                    create_target_image(
+                        size,
+                        TextureFormat::bevy_default(),
+                        RenderAssetUsages::default(),
+                    ),
+                );
+                ui_image.texture = image.clone();
+                commands
+                    .entity(camera)
+                    .insert(ViewportTarget(RenderTarget::Image(image)));
+            }
+        }
+    }
+}
+
+/// Sets [`UiScale`] to the pixel scale of the camera with [`SyncUiScale`].
+pub(crate) fn sync_ui_scale(
+    cameras: Query<&PixelViewportReferences, (With<PixelCamera>, With<SyncUiScale>)>,
+    viewport_cameras: Query<(&Camera, &OrthographicProjection), With<ViewportCamera>>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    let Some(viewport) = cameras.iter().next() else {
+        return;
+    };
+    let Ok((camera, projection)) = viewport_cameras.get(viewport.camera) else {
+        return;
+    };
+    // The logical size of the part of the target that the viewport is shown on, in the scale
+    // factor of the target itself rather than that of the primary window.
+    let (Some(physical_size), Some(scale_factor)) = (
+        camera.physical_viewport_size(),
+        camera.target_scaling_factor(),
+    ) else {
+        return;
+    };
+    let logical_size = physical_size.as_vec2() / scale_factor;
+    // The viewport camera sees exactly one unit per texel.
+    let ScalingMode::Fixed { width, height } = projection.scaling_mode else {
+        return;
+    };
+    if width <= 0.0 || height <= 0.0 {
+        return;
+    }
+
+    let scale = (logical_size.x / width).min(logical_size.y / height);
+    if scale > 0.0 && ui_scale.0 != scale {
+        ui_scale.0 = scale;
+    }
+}
+
+/// Sets the [`PixelInteraction`] of UI nodes rendered by [`PixelCamera`]s.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub(crate) fn pixel_ui_interaction(
+    cameras: Query<
+        (
+            Entity,
+            &Camera,
+            &GlobalTransform,
+            &PixelViewportReferences,
+            Option<&PixelUiLink>,
+        ),
+        With<PixelCamera>,
+    >,
+    viewport_cameras: Query<(&Camera, &GlobalTransform), With<ViewportCamera>>,
+    viewport_sprites: Query<(&Sprite, &GlobalTransform), With<PixelViewport>>,
+    windows: Query<&Window>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    default_ui_camera: DefaultUiCamera,
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    touches_input: Res<Touches>,
+    ui_scale: Res<UiScale>,
+    ui_stack: Res<UiStack>,
+    pointer_captured: Option<Res<PointerCaptured>>,
+    mut nodes: Query<(
+        &Node,
+        &GlobalTransform,
+        &mut PixelInteraction,
+        Option<&TargetCamera>,
+        Option<&FocusPolicy>,
+        Option<&CalculatedClip>,
+        Option<&ViewVisibility>,
+    )>,
+) {
+    let primary_window = primary_window.get_single().ok();
+    let pointer_captured = pointer_captured.is_some_and(|captured| captured.0);
+
+    // The cursor position of every pixel camera (and `PixelUi` camera), in logical pixels of
+    // its low resolution target.
+    let cursor_positions: HashMap<Entity, Vec2> = cameras
+        .iter()
+        .filter(|_| !pointer_captured)
+        .filter_map(|(entity, camera, camera_transform, viewport, ui_link)| {
+            let viewport_camera = viewport_cameras.get(viewport.camera).ok()?;
+            let sprite = viewport_sprites.get(viewport.sprite).ok()?;
+            let window_entity = match viewport_camera.0.target.normalize(primary_window)? {
+                NormalizedRenderTarget::Window(window) => window.entity(),
+                _ => return None,
+            };
+            let cursor = windows
+                .get(window_entity)
+                .ok()?
+                .cursor_position()
+                .or_else(|| touches_input.first_pressed_position())?;
+
+            let chain = ViewportChain {
+                camera: (camera, camera_transform),
+                viewport_camera,
+                sprite,
+                size: viewport.size,
+            };
+            let texel = chain.window_to_texel(cursor)?;
+
+            // The UI image of `PixelUi` lines up with the visible part of the target.
+            let ui_texel = texel - sprite.0.rect.map_or(Vec2::ZERO, |rect| rect.min);
+            let ui_position = ui_link.map(|link| (link.camera, ui_texel / ui_scale.0));
+            Some([Some((entity, texel / ui_scale.0)), ui_position])
+        })
+        .flatten()
+        .flatten()
+        .collect();
+
+    let mouse_pressed =
+        mouse_button_input.just_pressed(MouseButton::Left) || touches_input.any_just_pressed();
+    let mouse_released =
+        mouse_button_input.just_released(MouseButton::Left) || touches_input.any_just_released();
+
+    // Walk the nodes from top to bottom until a node blocks the cursor.
+    let mut blocked_cameras = HashSet::new();
+    for &entity in ui_stack.uinodes.iter().rev() {
+        let Ok((node, transform, mut interaction, target_camera, focus_policy, clip, visibility)) =
+            nodes.get_mut(entity)
+        else {
+            continue;
+        };
+        let Some(camera) = target_camera
+            .map(TargetCamera::entity)
+            .or(default_ui_camera.get())
+        else {
+            continue;
+        };
+        let Some(cursor) = cursor_positions.get(&camera) else {
+            continue;
+        };
+
+        let node_rect = node.logical_rect(transform);
+        let visible_rect = clip.map_or(node_rect, |clip| node_rect.intersect(clip.clip));
+        let hovered = visibility.is_none_or(|visibility| visibility.get())
+            && !blocked_cameras.contains(&camera)
+            && visible_rect.contains(*cursor);
+
+        let new_interaction = match *interaction {
+            _ if mouse_released => {
+                if hovered {
+                    PixelInteraction::Hovered
+                } else {
+                    PixelInteraction::None
+                }
+            }
+            _ if hovered && mouse_pressed => PixelInteraction::Pressed,
+            PixelInteraction::Pressed => PixelInteraction::Pressed,
+            _ if hovered => PixelInteraction::Hovered,
+            _ => PixelInteraction::None,
+        };
+        interaction.set_if_neq(new_interaction);
+
+        if hovered && focus_policy.is_none_or(|policy| *policy == FocusPolicy::Block) {
+            blocked_cameras.insert(camera);
+        }
+    }
+}
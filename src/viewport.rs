@@ -1,11 +1,15 @@
 //! Viewport Scaling and Stretching.
 
+use std::fmt;
+
+use bevy::reflect::Reflect;
 use bevy::render::camera::ClearColorConfig;
 use bevy::render::render_resource::Extent3d;
 use bevy::window::WindowResolution;
 
 /// The way the viewport scales to fit the window.
 #[doc(alias = "stretching")]
+#[derive(Reflect, Clone)]
 pub enum FitMode {
     /// The viewport will be stretched to the size of the window.
     Stretch,
@@ -16,12 +20,72 @@ pub enum FitMode {
     ///
     /// The unused space will be filled with the color.
     Fit(ClearColorConfig),
+    /// Like [`FitMode::Fit`], but the viewport only scales by whole numbers, so every texel
+    /// stays the same size (e.g. when toggling fullscreen). Uses the largest scale that
+    /// fits, or scales down like [`FitMode::Fit`] if the window is smaller than the viewport.
+    ///
+    /// The unused space will be filled with the color.
+    IntegerFit(ClearColorConfig),
+}
+
+/// How the scale factor (DPI) of a window affects the size of its viewports,
+/// set with [`PixelCameraConfig::dpi_policy`](crate::PixelCameraConfig::dpi_policy).
+///
+/// Sizes like [`ViewportSize::PixelFixed`] are calculated from the window's resolution
+/// divided by the scale factor that this policy picks.
+/// Image targets always have a scale factor of 1.
+#[derive(Reflect, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DpiPolicy {
+    /// Use the window's scale factor. Each texel of `PixelFixed(N)` covers N logical pixels,
+    /// so 2N physical pixels on a display with a scale factor of 2 (like a Retina display).
+    ///
+    /// Fractional scale factors (like 1.25 or 1.5) make texels cover a fractional number of
+    /// physical pixels, which looks blurry or uneven.
+    #[default]
+    Logical,
+    /// Round the window's scale factor to the nearest whole number (at least 1).
+    ///
+    /// The same as [`DpiPolicy::Logical`] for whole scale factors, but keeps every texel
+    /// covering a whole number of physical pixels for fractional ones, e.g. on the web where
+    /// the scale factor is the canvas's `devicePixelRatio`, which changes with the browser's zoom.
+    Rounded,
+    /// Ignore the window's scale factor. Each texel of `PixelFixed(N)` covers N physical pixels.
+    Physical,
 }
 
+impl DpiPolicy {
+    /// Returns the scale factor used for sizing viewports on a window with the given scale factor.
+    pub fn scale_factor(self, window_scale_factor: f32) -> f32 {
+        match self {
+            DpiPolicy::Logical => window_scale_factor,
+            DpiPolicy::Rounded => window_scale_factor.round().max(1.0),
+            DpiPolicy::Physical => 1.0,
+        }
+    }
+}
+
+/// The error returned by [`ViewportSize::try_calculate`] when a [`ViewportSize`]
+/// has a scale of zero or results in a viewport without any pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidViewportSize;
+
+impl fmt::Display for InvalidViewportSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(
+            "the viewport size has a scale of zero or results in a viewport without any pixels",
+        )
+    }
+}
+
+impl std::error::Error for InvalidViewportSize {}
+
 /// Different methods of calculating the viewport's size
+#[derive(Reflect, Clone)]
 pub enum ViewportSize {
     /// Each pixel's size is fixed.
     /// The viewport scales with the window.
+    ///
+    /// On windows, the size is in the pixels picked by the [`DpiPolicy`].
     #[doc(alias = "WindowSize")]
     PixelFixed(u32),
     /// The viewport's size is fixed.
@@ -57,12 +121,21 @@ pub enum ViewportSize {
     /// Use your own function for converting a window resolution to viewport size.
     Custom {
         /// The function used for converting a window resolution to viewport size.
+        ///
+        /// Functions can't be reflected, so a reflected (e.g. loaded) `Custom` viewport size
+        /// uses the window's size until the function is set again.
+        #[reflect(ignore, default = "window_size")]
         func: fn(&WindowResolution) -> (u32, u32),
         /// The way the viewport scales to fit the window.
         fit: FitMode,
     },
 }
 
+/// The default function of a reflected [`ViewportSize::Custom`], which doesn't scale the window.
+fn window_size() -> fn(&WindowResolution) -> (u32, u32) {
+    |resolution| (resolution.width() as u32, resolution.height() as u32)
+}
+
 impl Default for ViewportSize {
     fn default() -> Self {
         Self::PixelFixed(4)
@@ -71,14 +144,47 @@ impl Default for ViewportSize {
 
 impl ViewportSize {
     /// Calculates the size of the viewport based on the [`ViewportSize`] and the [`WindowResolution`].
+    ///
+    /// The returned size is always at least 1×1, even for minimized (zero-size) windows
+    /// or invalid [`ViewportSize`]s. Use [`ViewportSize::try_calculate`] to detect these.
     pub fn calculate(&self, window_resolution: &WindowResolution) -> Extent3d {
-        let window_width = window_resolution.width();
-        let window_height = window_resolution.height();
+        let size = self.calculate_unclamped(window_resolution);
+
+        Extent3d {
+            width: size.width.max(1),
+            height: size.height.max(1),
+            depth_or_array_layers: 1,
+        }
+    }
+    /// Calculates the size of the viewport like [`ViewportSize::calculate`], but returns
+    /// an error instead of clamping if the [`ViewportSize`] has a scale of zero, the window
+    /// has no area (e.g. while it's minimized), or the result is a viewport without any pixels.
+    pub fn try_calculate(
+        &self,
+        window_resolution: &WindowResolution,
+    ) -> Result<Extent3d, InvalidViewportSize> {
+        if let ViewportSize::PixelFixed(0) = self {
+            return Err(InvalidViewportSize);
+        }
+        if window_resolution.physical_width() == 0 || window_resolution.physical_height() == 0 {
+            return Err(InvalidViewportSize);
+        }
+        let size = self.calculate_unclamped(window_resolution);
+        if size.width == 0 || size.height == 0 {
+            return Err(InvalidViewportSize);
+        }
+
+        Ok(size)
+    }
+    fn calculate_unclamped(&self, window_resolution: &WindowResolution) -> Extent3d {
+        // Minimized windows can report a size of zero, which would make us divide by zero.
+        let window_width = window_resolution.width().max(1.0);
+        let window_height = window_resolution.height().max(1.0);
 
         match *self {
             ViewportSize::PixelFixed(scaling) => Extent3d {
-                width: (window_width / scaling as f32).ceil() as u32,
-                height: (window_height / scaling as f32).ceil() as u32,
+                width: (window_width / scaling.max(1) as f32).ceil() as u32,
+                height: (window_height / scaling.max(1) as f32).ceil() as u32,
                 depth_or_array_layers: 1,
             },
             ViewportSize::Fixed { width, height, .. } => Extent3d {
@@ -158,14 +264,14 @@ impl ViewportSize {
         }
     }
     /// Returns the clear color for this [`ViewportSize`] if the current variant
-    /// has a [`FitMode::Fit`], otherwise returns [`ClearColorConfig::None`].
+    /// has a [`FitMode::Fit`] or [`FitMode::IntegerFit`], otherwise returns [`ClearColorConfig::None`].
     pub fn clear_color(&self) -> ClearColorConfig {
         if let ViewportSize::Fixed {
-            fit: FitMode::Fit(config),
+            fit: FitMode::Fit(config) | FitMode::IntegerFit(config),
             ..
         }
         | ViewportSize::Custom {
-            fit: FitMode::Fit(config),
+            fit: FitMode::Fit(config) | FitMode::IntegerFit(config),
             ..
         } = self
         {
@@ -175,3 +281,135 @@ impl ViewportSize {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn size(width: u32, height: u32) -> Extent3d {
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        }
+    }
+
+    /// A window of `width`×`height` physical pixels with the given scale factor.
+    fn physical(width: u32, height: u32, scale_factor: f32) -> WindowResolution {
+        let mut resolution = WindowResolution::default().with_scale_factor_override(scale_factor);
+        resolution.set_physical_resolution(width, height);
+        resolution
+    }
+
+    #[test]
+    fn dpi_policy_whole_scale_factor() {
+        for policy in [DpiPolicy::Logical, DpiPolicy::Rounded] {
+            assert_eq!(policy.scale_factor(1.0), 1.0);
+            assert_eq!(policy.scale_factor(2.0), 2.0);
+        }
+        assert_eq!(DpiPolicy::Physical.scale_factor(2.0), 1.0);
+    }
+
+    #[test]
+    fn dpi_policy_fractional_scale_factor() {
+        assert_eq!(DpiPolicy::Logical.scale_factor(1.25), 1.25);
+        assert_eq!(DpiPolicy::Rounded.scale_factor(1.25), 1.0);
+        assert_eq!(DpiPolicy::Rounded.scale_factor(1.5), 2.0);
+        assert_eq!(DpiPolicy::Rounded.scale_factor(2.75), 3.0);
+        assert_eq!(DpiPolicy::Physical.scale_factor(1.5), 1.0);
+    }
+
+    #[test]
+    fn dpi_policy_scale_factor_below_one() {
+        // Browsers report scale factors below 1 when zoomed out.
+        assert_eq!(DpiPolicy::Logical.scale_factor(0.5), 0.5);
+        assert_eq!(DpiPolicy::Rounded.scale_factor(0.5), 1.0);
+        assert_eq!(DpiPolicy::Rounded.scale_factor(0.25), 1.0);
+        assert_eq!(DpiPolicy::Physical.scale_factor(0.5), 1.0);
+    }
+
+    #[test]
+    fn try_calculate_pixel_fixed() {
+        let resolution = WindowResolution::new(1280.0, 720.0);
+        assert_eq!(
+            ViewportSize::PixelFixed(4).try_calculate(&resolution),
+            Ok(size(320, 180))
+        );
+        // Partial texels at the edge of the window are rounded up.
+        assert_eq!(
+            ViewportSize::PixelFixed(3).try_calculate(&resolution),
+            Ok(size(427, 240))
+        );
+    }
+
+    #[test]
+    fn try_calculate_fractional_scale_factor() {
+        // 1366×768 physical pixels at a scale factor of 1.25 are 1092.8×614.4 logical pixels.
+        let resolution = physical(1366, 768, 1.25);
+        assert_eq!(
+            ViewportSize::PixelFixed(4).try_calculate(&resolution),
+            Ok(size(274, 154))
+        );
+        assert_eq!(
+            ViewportSize::FixedHeight(180).try_calculate(&resolution),
+            Ok(size(320, 180))
+        );
+    }
+
+    #[test]
+    fn try_calculate_zero_scale() {
+        let resolution = WindowResolution::new(1280.0, 720.0);
+        assert_eq!(
+            ViewportSize::PixelFixed(0).try_calculate(&resolution),
+            Err(InvalidViewportSize)
+        );
+        assert_eq!(
+            ViewportSize::PixelFixed(0).calculate(&resolution),
+            size(1280, 720)
+        );
+    }
+
+    #[test]
+    fn try_calculate_empty_viewport() {
+        let resolution = WindowResolution::new(1280.0, 720.0);
+        let empty = ViewportSize::Fixed {
+            width: 0,
+            height: 0,
+            fit: FitMode::Stretch,
+        };
+        assert_eq!(empty.try_calculate(&resolution), Err(InvalidViewportSize));
+        assert_eq!(empty.calculate(&resolution), size(1, 1));
+
+        // A window much wider than it's tall leaves no rows for a small fixed width.
+        let wide = WindowResolution::new(4000.0, 10.0);
+        assert_eq!(
+            ViewportSize::FixedWidth(100).try_calculate(&wide),
+            Err(InvalidViewportSize)
+        );
+    }
+
+    #[test]
+    fn try_calculate_minimized_window() {
+        let minimized = WindowResolution::new(0.0, 0.0);
+        let auto_min = ViewportSize::AutoMin {
+            min_width: 320,
+            min_height: 180,
+        };
+        assert_eq!(
+            ViewportSize::PixelFixed(4).try_calculate(&minimized),
+            Err(InvalidViewportSize)
+        );
+        assert_eq!(
+            ViewportSize::FixedWidth(320).try_calculate(&minimized),
+            Err(InvalidViewportSize)
+        );
+        assert_eq!(auto_min.try_calculate(&minimized), Err(InvalidViewportSize));
+
+        // `calculate` still returns a usable size.
+        assert_eq!(
+            ViewportSize::PixelFixed(4).calculate(&minimized),
+            size(1, 1)
+        );
+        assert_eq!(auto_min.calculate(&minimized), size(320, 320));
+    }
+}
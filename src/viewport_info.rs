@@ -0,0 +1,130 @@
+//! The render target, resolution and scale of every [`PixelCamera`], for UI from any crate.
+//!
+//! [`PixelViewportInfoPlugin`] keeps a [`PixelViewportInfo`] on each initialized camera, e.g.
+//! for showing the viewport as an image in an egui window, or for a debug overlay. Set
+//! [`PointerCaptured`](crate::cursor::PointerCaptured) while such a UI uses the pointer.
+
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+use bevy::render::render_resource::Extent3d;
+use bevy::render::texture::TextureFormatPixelInfo;
+
+use crate::components::{PixelCamera, PixelViewportReferences, ViewportCamera};
+use crate::composite::DirectCompositeTarget;
+use crate::systems::fit_projection_size;
+use crate::CameraSystems;
+
+/// Keeps a [`PixelViewportInfo`] on every initialized [`PixelCamera`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn log_scale(cameras: Query<&PixelViewportInfo, Changed<PixelViewportInfo>>) {
+///     for info in &cameras {
+///         info!("{} upscaled by {:?}", info.resolution, info.scale);
+///     }
+/// }
+///
+/// App::new()
+///     .add_plugins(PixelViewportInfoPlugin)
+///     .add_systems(Update, log_scale);
+/// ```
+pub struct PixelViewportInfoPlugin;
+impl Plugin for PixelViewportInfoPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PixelViewportInfo>().add_systems(
+            PostUpdate,
+            update_viewport_info.after(CameraSystems::Update),
+        );
+    }
+}
+
+/// The render target, resolution and scale of a [`PixelCamera`],
+/// kept up to date by [`PixelViewportInfoPlugin`].
+#[derive(Component, Reflect, Debug, Clone, PartialEq)]
+#[reflect(Component)]
+pub struct PixelViewportInfo {
+    /// The low resolution image that the camera renders to, including the smoothing margin.
+    pub image: Option<Handle<Image>>,
+    /// The visible size of the viewport in texels, without the smoothing margin.
+    pub resolution: UVec2,
+    /// The size of the render target in texels, including the smoothing margin.
+    pub target_size: UVec2,
+    /// How many physical pixels of the output each texel covers.
+    ///
+    /// [`None`] if the size of the output isn't known, e.g. while its window is minimized.
+    pub scale: Option<Vec2>,
+    /// The memory used by the render target, in bytes.
+    pub memory: usize,
+}
+
+#[allow(clippy::type_complexity)]
+fn update_viewport_info(
+    mut cameras: Query<(
+        Entity,
+        &PixelCamera,
+        &Camera,
+        Option<&PixelViewportReferences>,
+        Option<&DirectCompositeTarget>,
+        Option<&mut PixelViewportInfo>,
+    )>,
+    viewport_cameras: Query<&Camera, With<ViewportCamera>>,
+    windows: Query<&Window>,
+    mut commands: Commands,
+) {
+    for (entity, pixel_camera, camera, references, direct, info) in &mut cameras {
+        // The size of the target and the physical size of the output it's shown on.
+        let (size, output_size) = match (references, direct) {
+            (Some(references), _) => (
+                references.size,
+                viewport_cameras
+                    .get(references.camera)
+                    .ok()
+                    .and_then(Camera::physical_target_size),
+            ),
+            (None, Some(direct)) => (
+                direct.size,
+                windows
+                    .get(direct.window)
+                    .ok()
+                    .map(|window| UVec2::new(window.physical_width(), window.physical_height())),
+            ),
+            (None, None) => continue,
+        };
+
+        let margin = if pixel_camera.smoothing { 2 } else { 0 };
+        let visible = Extent3d {
+            width: size.width.saturating_sub(margin),
+            height: size.height.saturating_sub(margin),
+            ..default()
+        };
+        let scale = output_size
+            .filter(|output_size| output_size.x > 0 && output_size.y > 0)
+            .map(|output_size| {
+                let output_size = output_size.as_vec2();
+                output_size / fit_projection_size(&pixel_camera.viewport_size, visible, output_size)
+            });
+        let image = match &camera.target {
+            RenderTarget::Image(image) => Some(image.clone()),
+            _ => None,
+        };
+
+        let new_info = PixelViewportInfo {
+            image,
+            resolution: UVec2::new(visible.width, visible.height),
+            target_size: UVec2::new(size.width, size.height),
+            scale,
+            memory: size.width as usize
+                * size.height as usize
+                * pixel_camera.target_format.pixel_size(),
+        };
+        match info {
+            Some(mut info) => {
+                info.set_if_neq(new_info);
+            }
+            None => {
+                commands.entity(entity).insert(new_info);
+            }
+        }
+    }
+}
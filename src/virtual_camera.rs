@@ -0,0 +1,138 @@
+//! Virtual cameras that a [`PixelCamera`] blends between, by priority.
+//!
+//! Spawn [`VirtualPixelCamera`]s where the camera should look, e.g. one that follows the player
+//! and a higher priority one for a boss intro, and add a [`PixelCameraBrain`] to the
+//! [`PixelCamera`]. The brain moves the camera to the virtual camera with the highest priority,
+//! and blends over whenever another one takes over.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::transform::helper::TransformHelper;
+
+use crate::components::{PixelCamera, PixelCamera3d, PixelFreeze};
+use crate::zoom::PixelZoom;
+
+/// A place for [`PixelCamera`]s with a [`PixelCameraBrain`] to look at, at the position of its
+/// [`GlobalTransform`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn boss_intro(mut commands: Commands) {
+///     commands.spawn((
+///         TransformBundle::from_transform(Transform::from_xyz(320.0, 48.0, 0.0)),
+///         VirtualPixelCamera {
+///             priority: 10,
+///             zoom: Some(6),
+///             blend_time: 1.5,
+///         },
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component, Default)]
+pub struct VirtualPixelCamera {
+    /// The virtual camera with the highest priority is live. Ties go to the one that was live.
+    pub priority: i32,
+    /// The [`PixelCamera::zoom`] of the virtual camera, or [`None`] to keep the current zoom.
+    ///
+    /// See [`PixelCamera::set_zoom`].
+    pub zoom: Option<u32>,
+    /// How long blending to this virtual camera takes, in seconds. Zero cuts straight to it.
+    pub blend_time: f32,
+}
+
+impl Default for VirtualPixelCamera {
+    fn default() -> Self {
+        Self {
+            priority: 0,
+            zoom: None,
+            blend_time: 1.0,
+        }
+    }
+}
+
+/// Drives the [`PixelCamera::subpixel_pos`] (and zoom) of a 2D [`PixelCamera`] with the live
+/// [`VirtualPixelCamera`].
+///
+/// The camera is left alone while there are no virtual cameras, and blends from wherever it is
+/// to the first one that appears. The brain runs after
+/// [`PixelCameraFollow`](crate::follow::PixelCameraFollow), so it takes over from it while a
+/// virtual camera is live. The zoom is changed with a [`PixelZoom`] transition if the camera has
+/// one, and at the end of the blend otherwise.
+#[derive(Component, Debug, Clone, Default)]
+pub struct PixelCameraBrain {
+    live: Option<Entity>,
+    /// The position that the blend started at.
+    from: Vec2,
+    elapsed: f32,
+}
+
+impl PixelCameraBrain {
+    /// Returns the live virtual camera.
+    pub fn live(&self) -> Option<Entity> {
+        self.live
+    }
+}
+
+/// Moves cameras with a [`PixelCameraBrain`] to their live virtual camera.
+#[allow(clippy::type_complexity)]
+pub(crate) fn drive_brains(
+    mut cameras: Query<
+        (
+            &mut PixelCamera,
+            &mut PixelCameraBrain,
+            Option<&mut PixelZoom>,
+        ),
+        (Without<PixelCamera3d>, Without<PixelFreeze>),
+    >,
+    virtual_cameras: Query<(Entity, &VirtualPixelCamera)>,
+    transforms: TransformHelper,
+    time: Res<Time>,
+) {
+    for (mut camera, mut brain, mut zoom) in &mut cameras {
+        let live = virtual_cameras
+            .iter()
+            .max_by_key(|(entity, virtual_camera)| {
+                (virtual_camera.priority, Some(*entity) == brain.live)
+            });
+        let Some((entity, virtual_camera)) = live else {
+            brain.live = None;
+            continue;
+        };
+        let Ok(transform) = transforms.compute_global_transform(entity) else {
+            continue;
+        };
+        let target = transform.translation().truncate();
+
+        if brain.live != Some(entity) {
+            // Blend from wherever the camera is, also when it wasn't driven by the brain before.
+            brain.live = Some(entity);
+            brain.from = camera.subpixel_pos;
+            brain.elapsed = 0.0;
+            if let (Some(new_zoom), Some(zoom)) = (virtual_camera.zoom, &mut zoom) {
+                let duration =
+                    Duration::try_from_secs_f32(virtual_camera.blend_time).unwrap_or_default();
+                zoom.zoom_to(new_zoom, duration);
+            }
+        }
+        brain.elapsed += time.delta_seconds();
+
+        let t = if virtual_camera.blend_time > 0.0 {
+            (brain.elapsed / virtual_camera.blend_time).min(1.0)
+        } else {
+            1.0
+        };
+        // Ease in and out.
+        let t = t * t * (3.0 - 2.0 * t);
+        let position = brain.from.lerp(target, t);
+        if position != camera.subpixel_pos {
+            camera.subpixel_pos = position;
+        }
+
+        if let (true, Some(new_zoom), None) = (t >= 1.0, virtual_camera.zoom, &zoom) {
+            camera.set_zoom(new_zoom);
+        }
+    }
+}
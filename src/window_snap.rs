@@ -0,0 +1,231 @@
+//! Keeping windowed pixel cameras pixel perfect by resizing their window.
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy::window::{PrimaryWindow, WindowMode, WindowResolution};
+
+use crate::components::{PixelCamera, PixelFreeze, PixelViewportReferences, ViewportCamera};
+use crate::composite::DirectCompositeTarget;
+use crate::systems::{sizing_resolution, viewport_window};
+use crate::viewport::ViewportSize;
+use crate::PixelCameraConfig;
+
+/// Resizes the window of a [`PixelCamera`] to the nearest integer multiple of its viewport,
+/// so that every texel covers the same whole number of pixels in windowed mode.
+///
+/// Resizing the window by hand snaps it to these steps as well. A [`ViewportSize::Fixed`]
+/// viewport keeps its aspect ratio, [`ViewportSize::PixelFixed`] snaps each axis to its pixel
+/// scale, [`ViewportSize::FixedWidth`] and [`ViewportSize::FixedHeight`] snap the other axis
+/// to the pixel scale of the fixed one. Fullscreen windows are left alone.
+///
+/// The window can't be smaller than one multiple of the viewport. Windows with a fractional
+/// scale factor can only be snapped exactly with a [`DpiPolicy`](crate::viewport::DpiPolicy)
+/// other than [`DpiPolicy::Logical`](crate::viewport::DpiPolicy::Logical).
+#[derive(Component, Reflect, Debug, Clone, Copy, Default)]
+#[reflect(Component, Default)]
+pub struct SnapWindowSize;
+
+/// Snaps the windows of [`PixelCamera`]s with a [`SnapWindowSize`] whenever their size changes.
+#[allow(clippy::type_complexity)]
+pub(crate) fn snap_window_sizes(
+    cameras: Query<
+        (
+            Entity,
+            Ref<PixelCamera>,
+            Option<&PixelViewportReferences>,
+            Option<&DirectCompositeTarget>,
+        ),
+        (With<SnapWindowSize>, Without<PixelFreeze>),
+    >,
+    viewport_cameras: Query<&Camera, With<ViewportCamera>>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    mut windows: Query<&mut Window>,
+    config: Res<PixelCameraConfig>,
+    // The last physical size of every window, the window also changes when the cursor moves.
+    mut last_sizes: Local<HashMap<Entity, UVec2>>,
+) {
+    for (entity, pixel_camera, viewport, composite) in &cameras {
+        let window_entity = match (viewport, composite) {
+            (_, Some(composite)) => Some(composite.window),
+            (Some(viewport), None) => viewport_window(
+                viewport,
+                &viewport_cameras,
+                primary_window.get_single().ok(),
+            ),
+            (None, None) => None,
+        };
+        let Some(mut window) = window_entity.and_then(|window| windows.get_mut(window).ok()) else {
+            continue;
+        };
+        let physical_size = UVec2::new(window.physical_width(), window.physical_height());
+        if window.mode != WindowMode::Windowed || physical_size.min_element() == 0 {
+            continue;
+        }
+        let last_size = last_sizes.insert(entity, physical_size);
+        if last_size == Some(physical_size) && !pixel_camera.is_changed() && !config.is_changed() {
+            continue;
+        }
+
+        let resolution = sizing_resolution(&window, &config);
+        let (size, min_size) = snapped_size(&pixel_camera.viewport_size, &resolution);
+        let scale_factor = resolution.scale_factor();
+
+        let min_size = min_size * scale_factor / window.scale_factor();
+        let constraints = &mut window.resize_constraints;
+        if constraints.min_width < min_size.x || constraints.min_height < min_size.y {
+            constraints.min_width = constraints.min_width.max(min_size.x);
+            constraints.min_height = constraints.min_height.max(min_size.y);
+        }
+
+        let snapped = (size * scale_factor).round().as_uvec2();
+        if snapped != physical_size {
+            window
+                .resolution
+                .set_physical_resolution(snapped.x, snapped.y);
+            last_sizes.insert(entity, snapped);
+        }
+    }
+}
+
+/// Returns the nearest size to the resolution that's an integer multiple of the viewport,
+/// and the smallest such size, in the units of the resolution.
+fn snapped_size(viewport_size: &ViewportSize, resolution: &WindowResolution) -> (Vec2, Vec2) {
+    let window_size = Vec2::new(resolution.width(), resolution.height());
+    let multiple = |size: f32, step: f32| (size / step).round().max(1.0) * step;
+
+    match *viewport_size {
+        ViewportSize::PixelFixed(scale) => {
+            let scale = scale.max(1) as f32;
+            (
+                Vec2::new(
+                    multiple(window_size.x, scale),
+                    multiple(window_size.y, scale),
+                ),
+                Vec2::splat(scale),
+            )
+        }
+        ViewportSize::FixedWidth(width) => {
+            let width = width.max(1) as f32;
+            let scale = (window_size.x / width).round().max(1.0);
+            (
+                Vec2::new(width * scale, multiple(window_size.y, scale)),
+                Vec2::new(width, 1.0),
+            )
+        }
+        ViewportSize::FixedHeight(height) => {
+            let height = height.max(1) as f32;
+            let scale = (window_size.y / height).round().max(1.0);
+            (
+                Vec2::new(multiple(window_size.x, scale), height * scale),
+                Vec2::new(1.0, height),
+            )
+        }
+        _ => {
+            // The size of a fixed viewport, or of the viewport that the current size results in.
+            let viewport = match *viewport_size {
+                ViewportSize::Fixed { width, height, .. } => UVec2::new(width, height),
+                _ => {
+                    let size = viewport_size.calculate(resolution);
+                    UVec2::new(size.width, size.height)
+                }
+            }
+            .max(UVec2::ONE)
+            .as_vec2();
+            let scale = (window_size / viewport).min_element().round().max(1.0);
+            (viewport * scale, viewport)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::viewport::FitMode;
+
+    fn fixed(width: u32, height: u32) -> ViewportSize {
+        ViewportSize::Fixed {
+            width,
+            height,
+            fit: FitMode::Stretch,
+        }
+    }
+
+    #[test]
+    fn snapped_size_pixel_fixed() {
+        let resolution = WindowResolution::new(1283.0, 719.0);
+        assert_eq!(
+            snapped_size(&ViewportSize::PixelFixed(4), &resolution),
+            (Vec2::new(1284.0, 720.0), Vec2::splat(4.0))
+        );
+        // A scale of zero is treated like a scale of one.
+        assert_eq!(
+            snapped_size(&ViewportSize::PixelFixed(0), &resolution),
+            (Vec2::new(1283.0, 719.0), Vec2::ONE)
+        );
+    }
+
+    #[test]
+    fn snapped_size_fixed_axis() {
+        let resolution = WindowResolution::new(1000.0, 700.0);
+        assert_eq!(
+            snapped_size(&ViewportSize::FixedWidth(320), &resolution),
+            (Vec2::new(960.0, 699.0), Vec2::new(320.0, 1.0))
+        );
+        assert_eq!(
+            snapped_size(&ViewportSize::FixedHeight(180), &resolution),
+            (Vec2::new(1000.0, 720.0), Vec2::new(1.0, 180.0))
+        );
+    }
+
+    #[test]
+    fn snapped_size_fixed_keeps_aspect_ratio() {
+        let resolution = WindowResolution::new(1000.0, 700.0);
+        assert_eq!(
+            snapped_size(&fixed(320, 180), &resolution),
+            (Vec2::new(960.0, 540.0), Vec2::new(320.0, 180.0))
+        );
+    }
+
+    #[test]
+    fn snapped_size_fractional_scale_factor() {
+        // 1366×768 physical pixels at a scale factor of 1.25 are 1092.8×614.4 logical pixels.
+        let mut resolution = WindowResolution::default().with_scale_factor_override(1.25);
+        resolution.set_physical_resolution(1366, 768);
+        assert_eq!(
+            snapped_size(&ViewportSize::PixelFixed(4), &resolution),
+            (Vec2::new(1092.0, 616.0), Vec2::splat(4.0))
+        );
+    }
+
+    #[test]
+    fn snapped_size_minimized_window() {
+        // The window never snaps smaller than one multiple of the viewport.
+        let minimized = WindowResolution::new(0.0, 0.0);
+        assert_eq!(
+            snapped_size(&ViewportSize::PixelFixed(4), &minimized),
+            (Vec2::splat(4.0), Vec2::splat(4.0))
+        );
+        assert_eq!(
+            snapped_size(&ViewportSize::FixedHeight(180), &minimized),
+            (Vec2::new(1.0, 180.0), Vec2::new(1.0, 180.0))
+        );
+        assert_eq!(
+            snapped_size(&fixed(320, 180), &minimized),
+            (Vec2::new(320.0, 180.0), Vec2::new(320.0, 180.0))
+        );
+    }
+
+    #[test]
+    fn snapped_size_empty_viewport() {
+        // An empty viewport is snapped like a single texel instead of dividing by zero.
+        let resolution = WindowResolution::new(1000.0, 700.0);
+        assert_eq!(
+            snapped_size(&fixed(0, 0), &resolution),
+            (Vec2::splat(700.0), Vec2::ONE)
+        );
+        assert_eq!(
+            snapped_size(&ViewportSize::FixedWidth(0), &resolution),
+            (Vec2::splat(1000.0), Vec2::ONE)
+        );
+    }
+}
@@ -0,0 +1,264 @@
+//! Areas of the world that change how a [`PixelCamera`] behaves while its follow target is in them.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::transform::helper::TransformHelper;
+
+use crate::bounds::CameraBounds;
+use crate::components::{PixelCamera, PixelCamera3d, PixelFreeze};
+use crate::follow::PixelCameraFollow;
+use crate::viewport::ViewportSize;
+use crate::zoom::PixelZoom;
+
+/// An area of the world, in world units, that overrides the settings of cameras with
+/// [`PixelCameraZones`] while their [`PixelCameraFollow::target`] is in it.
+///
+/// The overrides blend in over [`CameraZone::blend_in`] seconds when the target enters the
+/// zone, and back out over [`CameraZone::blend_out`] seconds when it leaves.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn setup(mut commands: Commands) {
+///     // Show the whole secret room while the player is in it.
+///     commands.spawn(CameraZone {
+///         position: Some(Vec2::new(512.0, 64.0)),
+///         zoom: Some(3),
+///         ..CameraZone::new(Rect::new(448.0, 0.0, 576.0, 128.0))
+///     });
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct CameraZone {
+    /// The area that the follow target has to be in.
+    pub area: Rect,
+    /// The [`CameraBounds`] inside of the zone.
+    pub bounds: Option<Rect>,
+    /// The [`PixelCamera::zoom`] inside of the zone, see [`PixelCamera::set_zoom`].
+    pub zoom: Option<u32>,
+    /// Moves the camera by this offset from where it would be, in world units.
+    pub offset: Vec2,
+    /// Holds the camera at this position instead of following the target, in world units.
+    pub position: Option<Vec2>,
+    /// How long the overrides take to blend in, in seconds.
+    pub blend_in: f32,
+    /// How long the overrides take to blend out, in seconds.
+    pub blend_out: f32,
+}
+
+impl CameraZone {
+    /// Creates a zone over `area` that doesn't override anything yet, and blends in and out
+    /// over half a second.
+    pub fn new(area: Rect) -> Self {
+        Self {
+            area,
+            bounds: None,
+            zoom: None,
+            offset: Vec2::ZERO,
+            position: None,
+            blend_in: 0.5,
+            blend_out: 0.5,
+        }
+    }
+}
+
+/// Lets the [`CameraZone`]s that the [`PixelCameraFollow::target`] of a 2D [`PixelCamera`]
+/// enters override its settings.
+///
+/// The overrides are applied on top of [`PixelCameraFollow`], and of [`PixelCameraBrain`](crate::virtual_camera::PixelCameraBrain)
+/// and [`PixelCameraRooms`](crate::rooms::PixelCameraRooms). The camera's own bounds and
+/// viewport size are restored once the target leaves the zone.
+#[derive(Component, Clone, Default)]
+pub struct PixelCameraZones {
+    zone: Option<Entity>,
+    leaving: bool,
+    /// How far the overrides are blended in, from 0 to 1.
+    weight: f32,
+    /// The offset from the followed position that was applied last frame.
+    applied: Vec2,
+    /// The bounds and viewport size of the camera before the zone overrode them.
+    saved_bounds: Option<Option<Rect>>,
+    saved_viewport_size: Option<ViewportSize>,
+}
+
+impl PixelCameraZones {
+    /// Returns the zone that the camera is in (or is leaving).
+    pub fn zone(&self) -> Option<Entity> {
+        self.zone
+    }
+    /// Returns how far the overrides of the zone are blended in, from 0 to 1.
+    pub fn weight(&self) -> f32 {
+        self.weight
+    }
+}
+
+/// Takes the offset that a zone applied last frame back out, so that the camera is followed
+/// from where it would be without the zone.
+pub(crate) fn unapply_zones(mut cameras: Query<(&mut PixelCamera, &mut PixelCameraZones)>) {
+    for (mut camera, mut zones) in &mut cameras {
+        if zones.applied != Vec2::ZERO {
+            camera.subpixel_pos -= zones.applied;
+            zones.applied = Vec2::ZERO;
+        }
+    }
+}
+
+/// Blends the overrides of the zone that the follow target is in.
+#[allow(clippy::type_complexity)]
+pub(crate) fn apply_zones(
+    mut cameras: Query<
+        (
+            Entity,
+            &mut PixelCamera,
+            &mut PixelCameraZones,
+            &PixelCameraFollow,
+            Option<&mut CameraBounds>,
+            Option<&mut PixelZoom>,
+        ),
+        (Without<PixelCamera3d>, Without<PixelFreeze>),
+    >,
+    camera_zones: Query<(Entity, &CameraZone)>,
+    transforms: TransformHelper,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (entity, mut camera, mut zones, follow, mut bounds, mut pixel_zoom) in &mut cameras {
+        let Ok(target) = transforms.compute_global_transform(follow.target) else {
+            continue;
+        };
+        let target = target.translation().truncate();
+
+        // Stay in the current zone for as long as possible where zones overlap.
+        let contains = |zone: Entity| {
+            camera_zones
+                .get(zone)
+                .is_ok_and(|(_, zone)| zone.area.contains(target))
+        };
+        let inside = zones.zone.filter(|zone| contains(*zone)).or_else(|| {
+            camera_zones
+                .iter()
+                .find(|(zone, _)| contains(*zone))
+                .map(|(zone, _)| zone)
+        });
+
+        if zones.zone.is_none() {
+            let Some(zone) = inside else {
+                continue;
+            };
+            // Entered below, like coming back to a zone that the camera was leaving.
+            zones.zone = Some(zone);
+            zones.leaving = true;
+        }
+        let Some(zone_entity) = zones.zone else {
+            continue;
+        };
+        // A despawned zone is left right away.
+        let zone = camera_zones.get(zone_entity).ok().map(|(_, zone)| *zone);
+
+        let delta = time.delta_seconds();
+        let ramp = |weight: f32, duration: f32, direction: f32| {
+            if duration > 0.0 {
+                (weight + direction * delta / duration).clamp(0.0, 1.0)
+            } else {
+                direction.max(0.0)
+            }
+        };
+
+        match (zones.leaving, inside == Some(zone_entity), zone) {
+            // Entering (or coming back to) the zone.
+            (true, true, Some(zone)) => {
+                zones.leaving = false;
+                if zone.bounds.is_some() && zones.saved_bounds.is_none() {
+                    zones.saved_bounds = Some(bounds.as_ref().map(|bounds| bounds.0));
+                }
+                if let Some(zoom) = zone.zoom {
+                    zones
+                        .saved_viewport_size
+                        .get_or_insert_with(|| camera.viewport_size.clone());
+                    match &mut pixel_zoom {
+                        Some(pixel_zoom) => pixel_zoom.zoom_to(zoom, blend_duration(zone.blend_in)),
+                        None => camera.set_zoom(zoom),
+                    }
+                }
+            }
+            // Leaving the zone.
+            (false, false, _) | (false, true, None) => {
+                zones.leaving = true;
+                if let Some(saved) = &zones.saved_viewport_size {
+                    match (&mut pixel_zoom, saved, zone) {
+                        (Some(pixel_zoom), &ViewportSize::PixelFixed(saved), Some(zone)) => {
+                            pixel_zoom.zoom_to(saved, blend_duration(zone.blend_out));
+                        }
+                        _ => camera.viewport_size = saved.clone(),
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        zones.weight = match (zones.leaving, zone) {
+            (false, Some(zone)) => ramp(zones.weight, zone.blend_in, 1.0),
+            (true, Some(zone)) => ramp(zones.weight, zone.blend_out, -1.0),
+            (_, None) => 0.0,
+        };
+        let weight = zones.weight;
+
+        if zones.leaving && weight <= 0.0 {
+            // Done leaving, give the camera its own bounds back. The viewport size was
+            // restored when the camera started leaving.
+            match (zones.saved_bounds.take(), &mut bounds) {
+                (Some(Some(saved)), Some(bounds)) => {
+                    bounds.set_if_neq(CameraBounds(saved));
+                }
+                (Some(Some(saved)), None) => {
+                    commands.entity(entity).insert(CameraBounds(saved));
+                }
+                (Some(None), _) => {
+                    commands.entity(entity).remove::<CameraBounds>();
+                }
+                (None, _) => {}
+            }
+            zones.saved_viewport_size = None;
+            zones.zone = None;
+            zones.leaving = false;
+            continue;
+        }
+        let Some(zone) = zone else {
+            continue;
+        };
+
+        // Ease in and out.
+        let eased = weight * weight * (3.0 - 2.0 * weight);
+        let base = camera.subpixel_pos;
+        let goal = zone.position.unwrap_or(base) + zone.offset;
+        let applied = (goal - base) * eased;
+        if applied != Vec2::ZERO {
+            camera.subpixel_pos += applied;
+            zones.applied = applied;
+        }
+
+        if let Some(zone_bounds) = zone.bounds {
+            // Without bounds of its own, the camera blends in from the zone's bounds.
+            let from = zones.saved_bounds.flatten().unwrap_or(zone_bounds);
+            let rect = Rect {
+                min: from.min.lerp(zone_bounds.min, eased),
+                max: from.max.lerp(zone_bounds.max, eased),
+            };
+            match &mut bounds {
+                Some(bounds) => {
+                    bounds.set_if_neq(CameraBounds(rect));
+                }
+                None => {
+                    commands.entity(entity).insert(CameraBounds(rect));
+                }
+            }
+        }
+    }
+}
+
+/// Returns a blend time in seconds as a [`Duration`], or zero if it's invalid.
+fn blend_duration(seconds: f32) -> Duration {
+    Duration::try_from_secs_f32(seconds).unwrap_or_default()
+}
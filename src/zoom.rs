@@ -0,0 +1,138 @@
+//! Animated transitions between the zoom levels of a [`PixelCamera`].
+//!
+//! Changing [`PixelCamera::zoom`] directly cuts to the new scale. [`PixelZoom`] scales the
+//! viewport sprite from the old to the new scale instead, and switches the render target to
+//! the new scale once the transition is done, so the camera is pixel perfect again.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::components::{CustomViewport, PixelCamera, PixelViewport, PixelViewportReferences};
+
+/// Animates the zoom of a [`PixelCamera`] with a [`ViewportSize::PixelFixed`](crate::viewport::ViewportSize::PixelFixed)
+/// viewport.
+///
+/// While zooming, the target is rendered at the smaller of both scales (so it covers everything
+/// that is shown) and the viewport sprite is scaled up to the current scale.
+/// The sprites of a [`CustomViewport`] are left alone, so those cameras only change their zoom
+/// at the end of the transition.
+///
+/// ```
+/// # use std::time::Duration;
+/// # use bevy::prelude::*;
+/// # use bevy_smooth_pixel_camera::prelude::*;
+/// fn zoom_in(mut zooms: Query<&mut PixelZoom>, keys: Res<ButtonInput<KeyCode>>) {
+///     if keys.just_pressed(KeyCode::Equal) {
+///         for mut zoom in &mut zooms {
+///             zoom.zoom_to(6, Duration::from_millis(300));
+///         }
+///     }
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone, Default)]
+#[reflect(Component, Default)]
+pub struct PixelZoom {
+    transition: Option<Transition>,
+}
+
+#[derive(Reflect, Debug, Clone)]
+struct Transition {
+    /// The scale that the transition started at, once it started.
+    from: Option<f32>,
+    to: u32,
+    duration: Duration,
+    elapsed: Duration,
+    /// The current scale of the viewport sprite.
+    current: Option<f32>,
+}
+
+impl PixelZoom {
+    /// Starts zooming to a whole number scale (at least 1) over `duration`.
+    ///
+    /// Interrupts the current transition, starting from wherever it got to.
+    pub fn zoom_to(&mut self, zoom: u32, duration: Duration) {
+        let current = self
+            .transition
+            .as_ref()
+            .and_then(|transition| transition.current);
+        self.transition = Some(Transition {
+            from: current,
+            to: zoom.max(1),
+            duration,
+            elapsed: Duration::ZERO,
+            current,
+        });
+    }
+
+    /// Returns whether the camera is zooming.
+    pub fn is_zooming(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    /// Returns the scale that the viewport is currently shown at, between the scales
+    /// of the transition while zooming.
+    pub fn current(&self) -> Option<f32> {
+        self.transition
+            .as_ref()
+            .and_then(|transition| transition.current)
+    }
+}
+
+/// Advances the [`PixelZoom`] transitions, before the viewports are sized for their zoom.
+#[allow(clippy::type_complexity)]
+pub(crate) fn animate_zoom(
+    mut cameras: Query<(
+        Entity,
+        &mut PixelCamera,
+        &mut PixelZoom,
+        Option<&PixelViewportReferences>,
+        Has<CustomViewport>,
+    )>,
+    mut sprites: Query<&mut Transform, With<PixelViewport>>,
+    time: Res<Time>,
+) {
+    for (entity, mut camera, mut zoom, viewport, custom) in &mut cameras {
+        let Some(transition) = &mut zoom.transition else {
+            continue;
+        };
+        let Some(zoom_level) = camera.zoom() else {
+            warn!("PixelCamera {entity:?}: Only `ViewportSize::PixelFixed` can be zoomed");
+            zoom.transition = None;
+            continue;
+        };
+        let from = *transition.from.get_or_insert(zoom_level as f32);
+        let to = transition.to;
+
+        transition.elapsed += time.delta();
+        let progress = if transition.duration.is_zero() {
+            1.0
+        } else {
+            (transition.elapsed.as_secs_f32() / transition.duration.as_secs_f32()).min(1.0)
+        };
+        let mut sprite = viewport
+            .filter(|_| !custom)
+            .and_then(|viewport| sprites.get_mut(viewport.sprite).ok());
+
+        if progress >= 1.0 {
+            camera.set_zoom(to);
+            if let Some(sprite) = &mut sprite {
+                sprite.scale = Vec3::ONE;
+            }
+            zoom.transition = None;
+            continue;
+        }
+
+        // Ease in and out, and zoom by the same factor each frame.
+        let eased = progress * progress * (3.0 - 2.0 * progress);
+        let current = from * (to as f32 / from).powf(eased);
+        transition.current = Some(current);
+
+        if let Some(sprite) = &mut sprite {
+            let render_zoom = (from.floor() as u32).min(to).max(1);
+            camera.set_zoom(render_zoom);
+            let scale = current / render_zoom as f32;
+            sprite.scale = Vec3::new(scale, scale, 1.0);
+        }
+    }
+}